@@ -0,0 +1,233 @@
+use nalgebra::DMatrix;
+
+use crate::losses::classification::{log_softmax, softmax};
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Gaussian classifier sharing one covariance matrix between all classes, which gives
+/// linear decision boundaries.
+///
+/// `shrinkage` in `[0, 1]` blends the pooled covariance with a multiple of the identity
+/// of the same average variance, which keeps it invertible with few samples or
+/// collinear features. `priors` default to the class frequencies.
+///
+/// `transform` projects onto the discriminant axes, the directions that best separate
+/// the class means relative to the within-class scatter; at most `n_classes - 1` of them
+/// carry information.
+pub struct LinearDiscriminantAnalysis {
+    pub shrinkage: Option<f32>,
+    pub priors: Option<Vec<f32>>,
+    pub n_components: Option<usize>,
+    pub classes: Vec<f32>,
+    pub means: DMatrix<f32>,
+    pub covariance: DMatrix<f32>,
+    pub coefficients: DMatrix<f32>,
+    pub intercept: DMatrix<f32>,
+    pub scalings: DMatrix<f32>,
+    pub explained_variance_ratio: Vec<f32>,
+    overall_mean: DMatrix<f32>,
+}
+
+impl LinearDiscriminantAnalysis {
+    pub fn new() -> LinearDiscriminantAnalysis {
+        LinearDiscriminantAnalysis {
+            shrinkage: None,
+            priors: None,
+            n_components: None,
+            classes: Vec::new(),
+            means: DMatrix::zeros(0, 0),
+            covariance: DMatrix::zeros(0, 0),
+            coefficients: DMatrix::zeros(0, 0),
+            intercept: DMatrix::zeros(1, 0),
+            scalings: DMatrix::zeros(0, 0),
+            explained_variance_ratio: Vec::new(),
+            overall_mean: DMatrix::zeros(1, 0),
+        }
+    }
+
+    pub fn with_shrinkage(mut self, shrinkage: f32) -> LinearDiscriminantAnalysis {
+        self.shrinkage = Some(shrinkage);
+        self
+    }
+
+    pub fn with_priors(mut self, priors: Vec<f32>) -> LinearDiscriminantAnalysis {
+        self.priors = Some(priors);
+        self
+    }
+
+    pub fn with_n_components(mut self, n_components: usize) -> LinearDiscriminantAnalysis {
+        self.n_components = Some(n_components);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        let (nrows, ncols) = x.shape();
+        self.classes = unique_classes(y);
+        let n_classes = self.classes.len();
+        if n_classes < 2 || nrows <= n_classes {
+            return Err(format!(
+                "LDA needs at least two classes and more samples than classes, got {} classes and {} samples",
+                n_classes, nrows
+            ));
+        }
+        if let Some(shrinkage) = self.shrinkage {
+            if !(0.0..=1.0).contains(&shrinkage) {
+                return Err(format!("shrinkage should be in [0, 1], got {}", shrinkage));
+            }
+        }
+        let priors = class_priors(y, &self.classes, &self.priors)?;
+
+        self.means = class_means(x, y, &self.classes);
+        self.overall_mean = DMatrix::from_iterator(1, ncols, x.row_mean().iter().cloned());
+
+        let mut within = DMatrix::<f32>::zeros(ncols, ncols);
+        for i in 0..nrows {
+            let k = self.classes.iter().position(|c| *c == y[(i, 0)]).unwrap();
+            let centered = x.row(i) - self.means.row(k);
+            within += centered.transpose() * centered;
+        }
+        within /= (nrows - n_classes) as f32;
+        self.covariance = shrink(&within, self.shrinkage.unwrap_or(0.0));
+
+        let cholesky =
+            self.covariance.clone().cholesky().ok_or(
+                "the within-class covariance is singular; set a shrinkage to regularise it",
+            )?;
+
+        // log p(x | k) + log prior_k = x^T S^-1 mu_k - mu_k^T S^-1 mu_k / 2 + log prior_k
+        // up to terms shared by every class.
+        self.coefficients = cholesky.solve(&self.means.transpose());
+        self.intercept = DMatrix::from_fn(1, n_classes, |_, k| {
+            -0.5 * (self.means.row(k) * self.coefficients.column(k))[(0, 0)] + priors[k].ln()
+        });
+
+        // Generalised eigenproblem S_b v = lambda S_w v, made symmetric with the
+        // Cholesky factor S_w = L L^T.
+        let mut between = DMatrix::<f32>::zeros(ncols, ncols);
+        for (k, prior) in priors.iter().enumerate() {
+            let centered = self.means.row(k) - &self.overall_mean;
+            between += centered.transpose() * centered * *prior;
+        }
+        let l = cholesky.l();
+        let l_inverse = l
+            .clone()
+            .solve_lower_triangular(&DMatrix::identity(ncols, ncols))
+            .ok_or("the within-class covariance is singular")?;
+        let eigen = (&l_inverse * between * l_inverse.transpose()).symmetric_eigen();
+
+        let mut order: Vec<usize> = (0..ncols).collect();
+        order.sort_by(|a, b| {
+            eigen.eigenvalues[*b]
+                .partial_cmp(&eigen.eigenvalues[*a])
+                .unwrap()
+        });
+        let max_components = (n_classes - 1).min(ncols);
+        let n_components = self.n_components.unwrap_or(max_components);
+        if n_components == 0 || n_components > max_components {
+            return Err(format!(
+                "n_components should be between 1 and min(n_classes - 1, n_features) = {}",
+                max_components
+            ));
+        }
+
+        let total: f32 = eigen.eigenvalues.iter().map(|v| v.max(0.0)).sum();
+        self.explained_variance_ratio = order[..n_components]
+            .iter()
+            .map(|j| eigen.eigenvalues[*j].max(0.0) / total)
+            .collect();
+        let directions = DMatrix::from_fn(ncols, n_components, |i, k| {
+            eigen.eigenvectors[(i, order[k])]
+        });
+        self.scalings = l_inverse.transpose() * directions;
+        Ok(())
+    }
+
+    /// Unnormalised log posterior of every class, one column per class.
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut scores = x * &self.coefficients;
+        for mut row in scores.row_iter_mut() {
+            row += &self.intercept;
+        }
+
+        return scores;
+    }
+
+    pub fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return softmax(&self.decision_function(x));
+    }
+
+    pub fn predict_log_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return log_softmax(&self.decision_function(x));
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return argmax_classes(&self.decision_function(x), &self.classes);
+    }
+
+    /// Coordinates of `x` along the discriminant axes.
+    pub fn transform(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut centered = x.clone();
+        for mut row in centered.row_iter_mut() {
+            row -= &self.overall_mean;
+        }
+
+        return centered * &self.scalings;
+    }
+}
+
+impl Default for LinearDiscriminantAnalysis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Given priors, checked against the classes, or the class frequencies of `y`.
+pub(crate) fn class_priors(
+    y: &DMatrix<f32>,
+    classes: &[f32],
+    priors: &Option<Vec<f32>>,
+) -> Result<Vec<f32>, String> {
+    match priors {
+        Some(priors) => {
+            if priors.len() != classes.len() {
+                return Err(format!(
+                    "{} priors were given for {} classes",
+                    priors.len(),
+                    classes.len()
+                ));
+            }
+            if priors.iter().any(|p| *p <= 0.0) || (priors.iter().sum::<f32>() - 1.0).abs() > 1e-4 {
+                return Err("priors should be positive and sum to 1".to_string());
+            }
+            Ok(priors.clone())
+        }
+        None => Ok(classes
+            .iter()
+            .map(|c| y.iter().filter(|v| *v == c).count() as f32 / y.nrows() as f32)
+            .collect()),
+    }
+}
+
+/// Mean of the samples of every class, one row per class.
+pub(crate) fn class_means(x: &DMatrix<f32>, y: &DMatrix<f32>, classes: &[f32]) -> DMatrix<f32> {
+    let mut means = DMatrix::<f32>::zeros(classes.len(), x.ncols());
+    let mut counts = vec![0.0; classes.len()];
+    for i in 0..x.nrows() {
+        let k = classes.iter().position(|c| *c == y[(i, 0)]).unwrap();
+        let mut row = means.row_mut(k);
+        row += x.row(i);
+        counts[k] += 1.0;
+    }
+    for (k, count) in counts.iter().enumerate() {
+        means.row_mut(k).scale_mut(1.0 / count);
+    }
+
+    return means;
+}
+
+/// `(1 - shrinkage) * covariance + shrinkage * mean_variance * I`.
+pub(crate) fn shrink(covariance: &DMatrix<f32>, shrinkage: f32) -> DMatrix<f32> {
+    let n = covariance.nrows();
+    let mean_variance = covariance.trace() / n as f32;
+    return covariance * (1.0 - shrinkage)
+        + DMatrix::<f32>::identity(n, n) * (shrinkage * mean_variance);
+}
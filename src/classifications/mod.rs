@@ -0,0 +1,2 @@
+pub mod linear_discriminant_analysis;
+pub mod quadratic_discriminant_analysis;
@@ -0,0 +1,129 @@
+use nalgebra::DMatrix;
+
+use crate::classifications::linear_discriminant_analysis::{class_means, class_priors};
+use crate::losses::classification::{log_softmax, softmax};
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Gaussian classifier with one covariance matrix per class, which gives quadratic
+/// decision boundaries.
+///
+/// `regularization` in `[0, 1]` blends every class covariance with the identity,
+/// `(1 - r) * S_k + r * I`, so classes with few samples stay invertible. `priors`
+/// default to the class frequencies.
+pub struct QuadraticDiscriminantAnalysis {
+    pub regularization: f32,
+    pub priors: Option<Vec<f32>>,
+    pub classes: Vec<f32>,
+    pub means: DMatrix<f32>,
+    pub covariances: Vec<DMatrix<f32>>,
+    log_priors: Vec<f32>,
+    precisions: Vec<DMatrix<f32>>,
+    log_determinants: Vec<f32>,
+}
+
+impl QuadraticDiscriminantAnalysis {
+    pub fn new() -> QuadraticDiscriminantAnalysis {
+        QuadraticDiscriminantAnalysis {
+            regularization: 0.0,
+            priors: None,
+            classes: Vec::new(),
+            means: DMatrix::zeros(0, 0),
+            covariances: Vec::new(),
+            log_priors: Vec::new(),
+            precisions: Vec::new(),
+            log_determinants: Vec::new(),
+        }
+    }
+
+    pub fn with_regularization(mut self, regularization: f32) -> QuadraticDiscriminantAnalysis {
+        self.regularization = regularization;
+        self
+    }
+
+    pub fn with_priors(mut self, priors: Vec<f32>) -> QuadraticDiscriminantAnalysis {
+        self.priors = Some(priors);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        let ncols = x.ncols();
+        if !(0.0..=1.0).contains(&self.regularization) {
+            return Err(format!(
+                "regularization should be in [0, 1], got {}",
+                self.regularization
+            ));
+        }
+        self.classes = unique_classes(y);
+        if self.classes.len() < 2 {
+            return Err("QDA needs at least two classes".to_string());
+        }
+        self.log_priors = class_priors(y, &self.classes, &self.priors)?
+            .iter()
+            .map(|p| p.ln())
+            .collect();
+        self.means = class_means(x, y, &self.classes);
+
+        self.covariances.clear();
+        self.precisions.clear();
+        self.log_determinants.clear();
+        for (k, class) in self.classes.iter().enumerate() {
+            let rows: Vec<usize> = (0..x.nrows()).filter(|i| y[(*i, 0)] == *class).collect();
+            if rows.len() < 2 {
+                return Err(format!("class {} needs at least two samples", class));
+            }
+
+            let mut covariance = DMatrix::<f32>::zeros(ncols, ncols);
+            for i in rows.iter() {
+                let centered = x.row(*i) - self.means.row(k);
+                covariance += centered.transpose() * centered;
+            }
+            covariance /= (rows.len() - 1) as f32;
+            covariance = covariance * (1.0 - self.regularization)
+                + DMatrix::<f32>::identity(ncols, ncols) * self.regularization;
+
+            let cholesky = covariance.clone().cholesky().ok_or(format!(
+                "the covariance of class {} is singular; set a regularization",
+                class
+            ))?;
+            self.log_determinants.push(
+                2.0 * cholesky
+                    .l_dirty()
+                    .diagonal()
+                    .iter()
+                    .map(|v| v.ln())
+                    .sum::<f32>(),
+            );
+            self.precisions.push(cholesky.inverse());
+            self.covariances.push(covariance);
+        }
+
+        Ok(())
+    }
+
+    /// Unnormalised log posterior of every class, one column per class.
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return DMatrix::from_fn(x.nrows(), self.classes.len(), |i, k| {
+            let centered = x.row(i) - self.means.row(k);
+            let mahalanobis = (&centered * &self.precisions[k] * centered.transpose())[(0, 0)];
+            -0.5 * (self.log_determinants[k] + mahalanobis) + self.log_priors[k]
+        });
+    }
+
+    pub fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return softmax(&self.decision_function(x));
+    }
+
+    pub fn predict_log_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return log_softmax(&self.decision_function(x));
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return argmax_classes(&self.decision_function(x), &self.classes);
+    }
+}
+
+impl Default for QuadraticDiscriminantAnalysis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
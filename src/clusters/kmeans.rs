@@ -0,0 +1,185 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::kernels::kernel::squared_distances;
+use crate::online::incremental::IncrementalModel;
+
+/// K-means clustering with k-means++ seeding.
+///
+/// `fit` runs Lloyd's iterations on the whole dataset until the centers move less than
+/// `tolerance` (in squared distance). `partial_fit` is mini-batch k-means (Sculley,
+/// 2010): each batch is assigned to the current centers, then every center moves towards
+/// its samples with a step of one over the number of samples it has received so far.
+/// `centers` has one row per cluster and `counts` the samples assigned to each.
+pub struct KMeans {
+    pub n_clusters: usize,
+    pub max_iter: usize,
+    pub tolerance: f32,
+    pub seed: Option<u64>,
+    pub centers: DMatrix<f32>,
+    pub counts: Vec<f32>,
+    pub inertia: f32,
+    pub n_iter: usize,
+}
+
+impl KMeans {
+    pub fn new(n_clusters: usize) -> KMeans {
+        KMeans {
+            n_clusters,
+            max_iter: 300,
+            tolerance: 1e-4,
+            seed: None,
+            centers: DMatrix::zeros(0, 0),
+            counts: Vec::new(),
+            inertia: f32::INFINITY,
+            n_iter: 0,
+        }
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> KMeans {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> KMeans {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> KMeans {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>) -> Result<(), String> {
+        self.check_samples(x)?;
+        self.centers = self.initial_centers(x);
+
+        self.n_iter = 0;
+        let mut labels = self.predict(x);
+        while self.n_iter < self.max_iter {
+            self.n_iter += 1;
+            let mut sums = DMatrix::zeros(self.n_clusters, x.ncols());
+            let mut counts = vec![0.0; self.n_clusters];
+            for (i, label) in labels.iter().enumerate() {
+                let mut row = sums.row_mut(*label);
+                row += x.row(i);
+                counts[*label] += 1.0;
+            }
+
+            let mut shift = 0.0;
+            for (c, count) in counts.iter().enumerate() {
+                // An empty cluster keeps its previous center.
+                if *count > 0.0 {
+                    let center = sums.row(c) / *count;
+                    shift += (&center - self.centers.row(c)).norm_squared();
+                    self.centers.set_row(c, &center);
+                }
+            }
+            self.counts = counts;
+            labels = self.predict(x);
+            if shift <= self.tolerance {
+                break;
+            }
+        }
+
+        self.inertia = self.score(x);
+        Ok(())
+    }
+
+    /// Mini-batch update with a new batch; the first batch seeds the centers and needs at
+    /// least `n_clusters` samples.
+    pub fn partial_fit(&mut self, x: &DMatrix<f32>) -> Result<(), String> {
+        if self.centers.nrows() == 0 {
+            self.check_samples(x)?;
+            self.centers = self.initial_centers(x);
+            self.counts = vec![0.0; self.n_clusters];
+        } else if x.ncols() != self.centers.ncols() {
+            return Err(format!(
+                "x has {} features but the centers have {}",
+                x.ncols(),
+                self.centers.ncols()
+            ));
+        }
+
+        for (i, label) in self.predict(x).iter().enumerate() {
+            self.counts[*label] += 1.0;
+            let step = 1.0 / self.counts[*label];
+            let center = self.centers.row(*label) * (1.0 - step) + x.row(i) * step;
+            self.centers.set_row(*label, &center);
+        }
+        self.inertia = self.score(x);
+        Ok(())
+    }
+
+    /// Index of the nearest center of every row of `x`.
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<usize> {
+        return squared_distances(x, &self.centers)
+            .row_iter()
+            .map(|row| row.transpose().argmin().0)
+            .collect();
+    }
+
+    /// Sum of the squared distances of the rows of `x` to their nearest center.
+    pub fn score(&self, x: &DMatrix<f32>) -> f32 {
+        return squared_distances(x, &self.centers)
+            .row_iter()
+            .map(|row| row.min())
+            .sum();
+    }
+
+    fn check_samples(&self, x: &DMatrix<f32>) -> Result<(), String> {
+        if self.n_clusters == 0 || x.nrows() < self.n_clusters {
+            return Err(format!(
+                "{} clusters need at least as many samples, got {}",
+                self.n_clusters,
+                x.nrows()
+            ));
+        }
+        Ok(())
+    }
+
+    /// k-means++: each new center is a sample drawn with probability proportional to its
+    /// squared distance to the nearest center chosen so far.
+    fn initial_centers(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut centers = DMatrix::zeros(self.n_clusters, x.ncols());
+        centers.set_row(0, &x.row(rng.gen_range(0..x.nrows())));
+        let mut distances = vec![f32::INFINITY; x.nrows()];
+
+        for c in 1..self.n_clusters {
+            let newest = centers.rows(c - 1, 1).into_owned();
+            for (distance, d) in distances
+                .iter_mut()
+                .zip(squared_distances(x, &newest).iter())
+            {
+                *distance = distance.min(*d);
+            }
+
+            let total: f32 = distances.iter().sum();
+            let mut target = rng.gen::<f32>() * total;
+            let mut chosen = x.nrows() - 1;
+            for (i, distance) in distances.iter().enumerate() {
+                if target < *distance {
+                    chosen = i;
+                    break;
+                }
+                target -= distance;
+            }
+            centers.set_row(c, &x.row(chosen));
+        }
+
+        return centers;
+    }
+}
+
+/// `y` is ignored.
+impl IncrementalModel for KMeans {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, _y: &DMatrix<f32>) -> Result<(), String> {
+        KMeans::partial_fit(self, x)
+    }
+}
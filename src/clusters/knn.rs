@@ -2,15 +2,20 @@ use std::collections::HashMap;
 
 use nalgebra::DMatrix;
 
+use crate::utils::utils::validate_sample_weight;
+
 pub struct KNN {
     pub x: DMatrix<f32>,
     pub y: DMatrix<f32>,
     pub n_neighborhood: usize,
+    /// Vote of each training sample; every neighbour counts once when `None`.
+    pub sample_weight: Option<DMatrix<f32>>,
 }
 
 pub struct NeighborhoodItem {
     pub dist: f32,
     pub class: f32,
+    pub weight: f32,
 }
 
 impl NeighborhoodItem {
@@ -18,6 +23,7 @@ impl NeighborhoodItem {
         NeighborhoodItem {
             dist: -1.0,
             class: f32::MAX,
+            weight: 1.0,
         }
     }
 }
@@ -28,9 +34,16 @@ impl KNN {
             x,
             y,
             n_neighborhood,
+            sample_weight: None,
         }
     }
 
+    pub fn with_sample_weight(mut self, sample_weight: DMatrix<f32>) -> Result<KNN, String> {
+        validate_sample_weight(&sample_weight, self.x.nrows())?;
+        self.sample_weight = Some(sample_weight);
+        Ok(self)
+    }
+
     pub fn predict(&mut self, x: DMatrix<f32>) -> Vec<String> {
         let mut y_hat = Vec::new();
         for idx in 0..x.nrows() {
@@ -44,6 +57,9 @@ impl KNN {
 
                 neighborhood_item.dist = dist;
                 neighborhood_item.class = self.y[(i, 0)];
+                if let Some(sample_weight) = &self.sample_weight {
+                    neighborhood_item.weight = sample_weight[(i, 0)];
+                }
 
                 neighborhood_list.push(neighborhood_item);
             }
@@ -51,13 +67,19 @@ impl KNN {
             neighborhood_list
                 .sort_by(|a: &NeighborhoodItem, b| a.dist.partial_cmp(&b.dist).unwrap());
 
-            let mut m: HashMap<String, usize> = HashMap::new();
+            let mut m: HashMap<String, f32> = HashMap::new();
             for ii in 0..self.n_neighborhood {
                 *m.entry(neighborhood_list[ii].class.to_string())
-                    .or_default() += 1;
+                    .or_default() += neighborhood_list[ii].weight;
             }
 
-            y_hat.push(m.iter().max_by_key(|entry| entry.1).unwrap().0.clone());
+            y_hat.push(
+                m.iter()
+                    .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+                    .unwrap()
+                    .0
+                    .clone(),
+            );
         }
 
         return y_hat;
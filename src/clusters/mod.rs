@@ -1 +1,2 @@
-pub mod knn;
\ No newline at end of file
+pub mod kmeans;
+pub mod knn;
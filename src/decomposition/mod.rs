@@ -0,0 +1,3 @@
+pub mod pca;
+pub mod randomized_svd;
+pub mod truncated_svd;
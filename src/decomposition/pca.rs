@@ -0,0 +1,196 @@
+use nalgebra::DMatrix;
+
+use crate::decomposition::randomized_svd::{flip_signs, full_svd, randomized_svd};
+use crate::utils::types::TypeSVD;
+
+/// Principal component analysis.
+///
+/// The number of components is either fixed (`new`) or the smallest number explaining
+/// at least a fraction of the variance (`with_variance_threshold`, full solver only).
+/// With `whiten` the projected components are scaled to unit variance.
+///
+/// After `fit`, `components` has one principal axis per row, sorted by decreasing
+/// `explained_variance`.
+pub struct PCA {
+    pub n_components: Option<usize>,
+    pub variance_threshold: Option<f32>,
+    pub whiten: bool,
+    pub solver: TypeSVD,
+    pub n_oversamples: usize,
+    pub n_power_iterations: usize,
+    pub seed: Option<u64>,
+    pub mean: DMatrix<f32>,
+    pub components: DMatrix<f32>,
+    pub singular_values: Vec<f32>,
+    pub explained_variance: Vec<f32>,
+    pub explained_variance_ratio: Vec<f32>,
+}
+
+impl PCA {
+    pub fn new(n_components: usize) -> PCA {
+        PCA {
+            n_components: Some(n_components),
+            variance_threshold: None,
+            whiten: false,
+            solver: TypeSVD::FULL,
+            n_oversamples: 10,
+            n_power_iterations: 4,
+            seed: None,
+            mean: DMatrix::zeros(1, 0),
+            components: DMatrix::zeros(0, 0),
+            singular_values: Vec::new(),
+            explained_variance: Vec::new(),
+            explained_variance_ratio: Vec::new(),
+        }
+    }
+
+    /// Keeps the fewest components whose explained variance ratios add up to at least
+    /// `threshold`.
+    pub fn with_variance_threshold(threshold: f32) -> PCA {
+        PCA {
+            n_components: None,
+            variance_threshold: Some(threshold),
+            ..PCA::new(0)
+        }
+    }
+
+    pub fn with_whitening(mut self) -> PCA {
+        self.whiten = true;
+        self
+    }
+
+    pub fn with_solver(mut self, solver: TypeSVD) -> PCA {
+        self.solver = solver;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> PCA {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>) -> Result<(), String> {
+        let (nrows, ncols) = x.shape();
+        if nrows < 2 {
+            return Err("PCA needs at least two samples".to_string());
+        }
+        let max_components = nrows.min(ncols);
+
+        self.mean = DMatrix::from_iterator(1, ncols, x.row_mean().iter().cloned());
+        let mut centered = x.clone();
+        for mut row in centered.row_iter_mut() {
+            row -= &self.mean;
+        }
+        let total_variance = centered.norm_squared() / (nrows - 1) as f32;
+
+        let (mut u, singular_values, mut v_t) = match (self.solver, self.n_components) {
+            (TypeSVD::FULL, _) => full_svd(&centered),
+            (TypeSVD::RANDOMIZED, Some(n_components)) if n_components <= max_components => {
+                randomized_svd(
+                    &centered,
+                    n_components,
+                    self.n_oversamples,
+                    self.n_power_iterations,
+                    self.seed,
+                )
+            }
+            (TypeSVD::RANDOMIZED, Some(n_components)) => {
+                return Err(format!(
+                    "n_components ({}) should be at most min(nrows, ncols) = {}",
+                    n_components, max_components
+                ))
+            }
+            (TypeSVD::RANDOMIZED, None) => {
+                return Err("a variance threshold needs the full solver".to_string())
+            }
+        };
+        flip_signs(&mut u, &mut v_t);
+
+        let explained_variance: Vec<f32> = singular_values
+            .iter()
+            .map(|s| s * s / (nrows - 1) as f32)
+            .collect();
+        let explained_variance_ratio: Vec<f32> = explained_variance
+            .iter()
+            .map(|v| {
+                if total_variance > 0.0 {
+                    v / total_variance
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let n_components = match (self.n_components, self.variance_threshold) {
+            (Some(n_components), _) if n_components <= max_components => n_components,
+            (Some(n_components), _) => {
+                return Err(format!(
+                    "n_components ({}) should be at most min(nrows, ncols) = {}",
+                    n_components, max_components
+                ))
+            }
+            (None, Some(threshold)) => {
+                if threshold <= 0.0 || threshold > 1.0 {
+                    return Err("variance_threshold should be in (0, 1]".to_string());
+                }
+                let mut cumulative = 0.0;
+                explained_variance_ratio
+                    .iter()
+                    .position(|ratio| {
+                        cumulative += ratio;
+                        cumulative >= threshold - 1e-6
+                    })
+                    .map_or(explained_variance_ratio.len(), |k| k + 1)
+            }
+            (None, None) => max_components,
+        };
+
+        self.components = v_t.rows(0, n_components).into_owned();
+        self.singular_values = singular_values[..n_components].to_vec();
+        self.explained_variance = explained_variance[..n_components].to_vec();
+        self.explained_variance_ratio = explained_variance_ratio[..n_components].to_vec();
+        Ok(())
+    }
+
+    /// Projects `x` onto the principal axes, one column per component.
+    pub fn transform(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut centered = x.clone();
+        for mut row in centered.row_iter_mut() {
+            row -= &self.mean;
+        }
+
+        let mut projected = centered * self.components.transpose();
+        if self.whiten {
+            for (k, variance) in self.explained_variance.iter().enumerate() {
+                if *variance > 0.0 {
+                    projected.column_mut(k).scale_mut(1.0 / variance.sqrt());
+                }
+            }
+        }
+
+        return projected;
+    }
+
+    pub fn fit_transform(&mut self, x: &DMatrix<f32>) -> Result<DMatrix<f32>, String> {
+        self.fit(x)?;
+        Ok(self.transform(x))
+    }
+
+    /// Maps projected points back to the original space; exact when every component
+    /// was kept.
+    pub fn inverse_transform(&self, projected: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut projected = projected.clone();
+        if self.whiten {
+            for (k, variance) in self.explained_variance.iter().enumerate() {
+                projected.column_mut(k).scale_mut(variance.sqrt());
+            }
+        }
+
+        let mut x = projected * &self.components;
+        for mut row in x.row_iter_mut() {
+            row += &self.mean;
+        }
+
+        return x;
+    }
+}
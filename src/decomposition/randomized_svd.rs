@@ -0,0 +1,73 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::utils::utils::random_normal_matrix;
+
+/// Leading `n_components` singular triplets `(u, s, v_t)` of `a`, as returned by a full
+/// SVD truncated to `n_components`.
+///
+/// Follows Halko, Martinsson and Tropp: `a` is projected onto `n_components +
+/// n_oversamples` random directions, refined with `n_power_iterations` power iterations
+/// (each one re-orthonormalised with QR), and the small projected matrix is decomposed
+/// exactly.
+pub fn randomized_svd(
+    a: &DMatrix<f32>,
+    n_components: usize,
+    n_oversamples: usize,
+    n_power_iterations: usize,
+    seed: Option<u64>,
+) -> (DMatrix<f32>, Vec<f32>, DMatrix<f32>) {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let n_random = (n_components + n_oversamples).min(a.nrows().min(a.ncols()));
+
+    let omega = random_normal_matrix(a.ncols(), n_random, &mut rng);
+    let mut q = (a * omega).qr().q();
+    for _ in 0..n_power_iterations {
+        let z = (a.transpose() * &q).qr().q();
+        q = (a * z).qr().q();
+    }
+
+    let svd = (q.transpose() * a).svd(true, true);
+    let u = &q * svd.u.unwrap();
+    let v_t = svd.v_t.unwrap();
+
+    (
+        u.columns(0, n_components).into_owned(),
+        svd.singular_values
+            .iter()
+            .take(n_components)
+            .cloned()
+            .collect(),
+        v_t.rows(0, n_components).into_owned(),
+    )
+}
+
+/// Exact SVD of `a`, with singular values in decreasing order.
+pub(crate) fn full_svd(a: &DMatrix<f32>) -> (DMatrix<f32>, Vec<f32>, DMatrix<f32>) {
+    let svd = a.clone().svd(true, true);
+    (
+        svd.u.unwrap(),
+        svd.singular_values.iter().cloned().collect(),
+        svd.v_t.unwrap(),
+    )
+}
+
+/// Flips the sign of each singular pair so the largest entry of every row of `v_t` is
+/// positive, making the decomposition deterministic.
+pub(crate) fn flip_signs(u: &mut DMatrix<f32>, v_t: &mut DMatrix<f32>) {
+    for k in 0..v_t.nrows() {
+        let largest =
+            v_t.row(k)
+                .iter()
+                .cloned()
+                .fold(0f32, |acc, v| if v.abs() > acc.abs() { v } else { acc });
+        if largest < 0.0 {
+            v_t.row_mut(k).neg_mut();
+            u.column_mut(k).neg_mut();
+        }
+    }
+}
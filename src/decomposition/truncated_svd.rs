@@ -0,0 +1,108 @@
+use nalgebra::DMatrix;
+
+use crate::decomposition::randomized_svd::{flip_signs, full_svd, randomized_svd};
+use crate::utils::types::TypeSVD;
+
+/// Rank `n_components` approximation of `x` by its leading singular vectors.
+///
+/// Unlike [`PCA`](crate::decomposition::pca::PCA) the data is not centered, which keeps
+/// sparse or count data (e.g. term frequencies) meaningful. Defaults to the randomized
+/// solver.
+pub struct TruncatedSVD {
+    pub n_components: usize,
+    pub solver: TypeSVD,
+    pub n_oversamples: usize,
+    pub n_power_iterations: usize,
+    pub seed: Option<u64>,
+    pub components: DMatrix<f32>,
+    pub singular_values: Vec<f32>,
+    pub explained_variance: Vec<f32>,
+    pub explained_variance_ratio: Vec<f32>,
+}
+
+impl TruncatedSVD {
+    pub fn new(n_components: usize) -> TruncatedSVD {
+        TruncatedSVD {
+            n_components,
+            solver: TypeSVD::RANDOMIZED,
+            n_oversamples: 10,
+            n_power_iterations: 4,
+            seed: None,
+            components: DMatrix::zeros(0, 0),
+            singular_values: Vec::new(),
+            explained_variance: Vec::new(),
+            explained_variance_ratio: Vec::new(),
+        }
+    }
+
+    pub fn with_solver(mut self, solver: TypeSVD) -> TruncatedSVD {
+        self.solver = solver;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> TruncatedSVD {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>) -> Result<(), String> {
+        let max_components = x.nrows().min(x.ncols());
+        if self.n_components == 0 || self.n_components > max_components {
+            return Err(format!(
+                "n_components ({}) should be between 1 and min(nrows, ncols) = {}",
+                self.n_components, max_components
+            ));
+        }
+
+        let (mut u, singular_values, mut v_t) = match self.solver {
+            TypeSVD::FULL => full_svd(x),
+            TypeSVD::RANDOMIZED => randomized_svd(
+                x,
+                self.n_components,
+                self.n_oversamples,
+                self.n_power_iterations,
+                self.seed,
+            ),
+        };
+        flip_signs(&mut u, &mut v_t);
+
+        self.components = v_t.rows(0, self.n_components).into_owned();
+        self.singular_values = singular_values[..self.n_components].to_vec();
+
+        let projected = self.transform(x);
+        self.explained_variance = (0..self.n_components)
+            .map(|k| column_variance(&projected, k))
+            .collect();
+        let total_variance: f32 = (0..x.ncols()).map(|j| column_variance(x, j)).sum();
+        self.explained_variance_ratio = self
+            .explained_variance
+            .iter()
+            .map(|v| {
+                if total_variance > 0.0 {
+                    v / total_variance
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    pub fn transform(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return x * self.components.transpose();
+    }
+
+    pub fn fit_transform(&mut self, x: &DMatrix<f32>) -> Result<DMatrix<f32>, String> {
+        self.fit(x)?;
+        Ok(self.transform(x))
+    }
+
+    pub fn inverse_transform(&self, projected: &DMatrix<f32>) -> DMatrix<f32> {
+        return projected * &self.components;
+    }
+}
+
+fn column_variance(x: &DMatrix<f32>, j: usize) -> f32 {
+    let mean = x.column(j).mean();
+    return x.column(j).map(|v| (v - mean).powf(2.0)).mean();
+}
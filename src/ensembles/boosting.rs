@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::optim::training::TrainingHistory;
+use crate::trees::tree::Tree;
+
+/// Boosting parameters shared by the gradient boosting models.
+///
+/// Every stage is shrunk by `learning_rate` and its tree grown on a `subsample` fraction
+/// of the training samples drawn without replacement. With a `validation_fraction`, that
+/// share of the samples is held out and boosting stops once the validation loss has not
+/// improved by `tolerance` for `n_iter_no_change` stages; the stages after the best one
+/// are then dropped.
+#[derive(Clone, Debug)]
+pub struct BoostingParams {
+    pub n_estimators: usize,
+    pub learning_rate: f32,
+    pub subsample: f32,
+    pub validation_fraction: Option<f32>,
+    pub n_iter_no_change: usize,
+    pub tolerance: f32,
+    pub seed: Option<u64>,
+}
+
+impl BoostingParams {
+    pub fn new(n_estimators: usize) -> BoostingParams {
+        BoostingParams {
+            n_estimators,
+            learning_rate: 0.1,
+            subsample: 1.0,
+            validation_fraction: None,
+            n_iter_no_change: 10,
+            tolerance: 1e-4,
+            seed: None,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.n_estimators == 0 || self.n_iter_no_change == 0 {
+            return Err("n_estimators and n_iter_no_change should be at least 1".to_string());
+        }
+        if self.learning_rate <= 0.0 {
+            return Err(format!(
+                "learning_rate should be positive, got {}",
+                self.learning_rate
+            ));
+        }
+        if self.subsample <= 0.0 || self.subsample > 1.0 {
+            return Err(format!(
+                "subsample should be in (0, 1], got {}",
+                self.subsample
+            ));
+        }
+        if let Some(fraction) = self.validation_fraction {
+            if fraction <= 0.0 || fraction >= 1.0 {
+                return Err(format!(
+                    "validation_fraction should be in (0, 1), got {}",
+                    fraction
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Shuffled training and validation row indices; all rows train without validation.
+    pub(crate) fn split_validation(
+        &self,
+        nrows: usize,
+        rng: &mut StdRng,
+    ) -> Result<(Vec<usize>, Vec<usize>), String> {
+        let mut indices: Vec<usize> = (0..nrows).collect();
+        let n_validation = match self.validation_fraction {
+            Some(fraction) => {
+                indices.shuffle(rng);
+                (fraction * nrows as f32).round() as usize
+            }
+            None => 0,
+        };
+        if self.validation_fraction.is_some() && (n_validation == 0 || n_validation == nrows) {
+            return Err(format!(
+                "cannot hold out a validation fraction of {:?} from {} samples",
+                self.validation_fraction, nrows
+            ));
+        }
+
+        let validation = indices.split_off(nrows - n_validation);
+        Ok((indices, validation))
+    }
+
+    /// Weight 1 for the training rows drawn for the next stage and 0 for the others.
+    pub(crate) fn stage_weights(
+        &self,
+        train: &[usize],
+        nrows: usize,
+        rng: &mut StdRng,
+    ) -> Vec<f32> {
+        let n_drawn = ((self.subsample * train.len() as f32).round() as usize).max(1);
+        let mut weights = vec![0.0; nrows];
+        for i in train.choose_multiple(rng, n_drawn) {
+            weights[*i] = 1.0;
+        }
+
+        return weights;
+    }
+
+    /// Records the losses of a stage and tells whether boosting should stop.
+    pub(crate) fn record_stage(
+        &self,
+        history: &mut TrainingHistory,
+        train_loss: f32,
+        validation_loss: Option<f32>,
+    ) -> bool {
+        history.train_loss.push(train_loss);
+        history.learning_rate.push(self.learning_rate);
+        let Some(loss) = validation_loss else {
+            return false;
+        };
+
+        history.validation_loss.push(loss);
+        let stage = history.validation_loss.len() - 1;
+        match history.best_epoch {
+            Some(best) if loss >= history.validation_loss[best] - self.tolerance => {
+                history.stopped_early = stage - best >= self.n_iter_no_change;
+            }
+            _ => history.best_epoch = Some(stage),
+        }
+
+        return history.stopped_early;
+    }
+}
+
+/// Overwrites the value of every leaf of `tree` reached by a row with a positive weight
+/// by `leaf_value` of those rows.
+pub(crate) fn update_leaves<F>(tree: &mut Tree, x: &DMatrix<f32>, weights: &[f32], leaf_value: F)
+where
+    F: Fn(&[usize]) -> f32,
+{
+    let mut rows: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in (0..x.nrows()).filter(|i| weights[*i] > 0.0) {
+        rows.entry(tree.leaf(x, i)).or_default().push(i);
+    }
+    for (leaf, indices) in rows.iter() {
+        tree.nodes[*leaf].value[0] = leaf_value(indices);
+    }
+}
+
+/// Normalised sum of the feature importances of the trees of every stage.
+pub(crate) fn sum_feature_importances<'a, I>(trees: I) -> Vec<f32>
+where
+    I: Iterator<Item = &'a Tree>,
+{
+    let mut importances: Vec<f32> = Vec::new();
+    for tree in trees {
+        importances.resize(tree.n_features, 0.0);
+        for (j, importance) in tree.feature_importances().iter().enumerate() {
+            importances[j] += importance;
+        }
+    }
+    let total: f32 = importances.iter().sum();
+    if total > 0.0 {
+        importances
+            .iter_mut()
+            .for_each(|importance| *importance /= total);
+    }
+
+    return importances;
+}
+
+/// Newton step `-sum(g) / sum(h)` over `indices`, or `None` where the curvature vanishes.
+pub(crate) fn newton_step(
+    gradient: &DMatrix<f32>,
+    hessian: &DMatrix<f32>,
+    column: usize,
+    indices: &[usize],
+) -> Option<f32> {
+    let g: f32 = indices.iter().map(|i| gradient[(*i, column)]).sum();
+    let h: f32 = indices.iter().map(|i| hessian[(*i, column)]).sum();
+    if h <= 1e-12 {
+        return None;
+    }
+
+    return Some(-g / h);
+}
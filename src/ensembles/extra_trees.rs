@@ -0,0 +1,19 @@
+use crate::ensembles::random_forest_classifier::RandomForestClassifier;
+use crate::ensembles::random_forest_regressor::RandomForestRegressor;
+use crate::utils::types::{Criterion, Splitter};
+
+/// Extremely randomized trees for classification: a forest whose splits use one random
+/// threshold per candidate feature and whose trees see the whole training set. Cheaper
+/// to grow than a random forest and often smoother.
+pub fn extra_trees_classifier(n_estimators: usize, criterion: Criterion) -> RandomForestClassifier {
+    RandomForestClassifier::new(n_estimators, criterion)
+        .with_splitter(Splitter::RANDOM)
+        .with_bootstrap(false)
+}
+
+/// Extremely randomized trees for regression, see [`extra_trees_classifier`].
+pub fn extra_trees_regressor(n_estimators: usize, criterion: Criterion) -> RandomForestRegressor {
+    RandomForestRegressor::new(n_estimators, criterion)
+        .with_splitter(Splitter::RANDOM)
+        .with_bootstrap(false)
+}
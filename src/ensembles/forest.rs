@@ -0,0 +1,126 @@
+use std::thread;
+
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::trees::tree::{Target, Tree, TreeParams};
+
+/// Bagging parameters shared by the forests.
+///
+/// With `bootstrap` every tree is grown on `max_samples` (a fraction of the training set,
+/// all of it by default) samples drawn with replacement; otherwise every tree sees the
+/// whole training set and only the split randomisation differs between trees. Trees are
+/// built on `n_jobs` threads; the result only depends on `seed`, not on `n_jobs`.
+#[derive(Clone, Debug)]
+pub struct ForestParams {
+    pub n_estimators: usize,
+    pub bootstrap: bool,
+    pub max_samples: Option<f32>,
+    pub n_jobs: usize,
+    pub seed: Option<u64>,
+}
+
+impl ForestParams {
+    pub fn new(n_estimators: usize) -> ForestParams {
+        ForestParams {
+            n_estimators,
+            bootstrap: true,
+            max_samples: None,
+            n_jobs: 1,
+            seed: None,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.n_estimators == 0 || self.n_jobs == 0 {
+            return Err("n_estimators and n_jobs should be at least 1".to_string());
+        }
+        if let Some(max_samples) = self.max_samples {
+            if max_samples <= 0.0 || max_samples > 1.0 {
+                return Err(format!(
+                    "max_samples should be in (0, 1], got {}",
+                    max_samples
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tree of a forest with the number of times each training sample was drawn for it.
+pub(crate) struct BaggedTree {
+    pub tree: Tree,
+    pub in_bag: Vec<f32>,
+}
+
+pub(crate) fn build_trees(
+    x: &DMatrix<f32>,
+    target: &Target,
+    forest: &ForestParams,
+    params: &TreeParams,
+) -> Vec<BaggedTree> {
+    let mut rng = match forest.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let seeds: Vec<u64> = (0..forest.n_estimators).map(|_| rng.gen()).collect();
+    let chunk_size = forest.n_estimators.div_ceil(forest.n_jobs);
+
+    return thread::scope(|scope| {
+        let handles: Vec<_> = seeds
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|seed| build_tree(x, target, forest, params, *seed))
+                        .collect::<Vec<BaggedTree>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+}
+
+fn build_tree(
+    x: &DMatrix<f32>,
+    target: &Target,
+    forest: &ForestParams,
+    params: &TreeParams,
+    seed: u64,
+) -> BaggedTree {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let nrows = x.nrows();
+
+    let mut in_bag = vec![0.0; nrows];
+    if forest.bootstrap {
+        let n_draws = ((forest.max_samples.unwrap_or(1.0) * nrows as f32) as usize).max(1);
+        for _ in 0..n_draws {
+            in_bag[rng.gen_range(0..nrows)] += 1.0;
+        }
+    } else {
+        in_bag.iter_mut().for_each(|count| *count = 1.0);
+    }
+
+    BaggedTree {
+        tree: Tree::build(x, target, &in_bag, params, &mut rng),
+        in_bag,
+    }
+}
+
+/// Mean of the normalised feature importances of the trees.
+pub(crate) fn mean_feature_importances(trees: &[BaggedTree]) -> Vec<f32> {
+    let mut importances = vec![0.0; trees[0].tree.n_features];
+    for bagged in trees {
+        for (j, importance) in bagged.tree.feature_importances().iter().enumerate() {
+            importances[j] += importance / trees.len() as f32;
+        }
+    }
+
+    return importances;
+}
@@ -0,0 +1,225 @@
+use nalgebra::DMatrix;
+
+use crate::ensembles::boosting::{
+    newton_step, sum_feature_importances, update_leaves, BoostingParams,
+};
+use crate::losses::classification::{sigmoid, softmax, CrossEntropy, LogLoss};
+use crate::losses::loss::Loss;
+use crate::optim::training::TrainingHistory;
+use crate::trees::decision_tree_classifier::class_labels;
+use crate::trees::tree::{Target, Tree, TreeParams};
+use crate::utils::types::{Criterion, MaxFeatures, Splitter};
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Gradient boosted regression trees on the deviance of the class probabilities.
+///
+/// Two classes share one score column under the binomial deviance (`LogLoss`); with more
+/// classes every stage grows one tree per class on the multinomial deviance
+/// (`CrossEntropy`). Leaves take a Newton step, scaled by `(K - 1) / K` for `K` classes.
+pub struct GradientBoostingClassifier {
+    pub params: TreeParams,
+    pub boosting: BoostingParams,
+    pub classes: Vec<f32>,
+    pub init: Vec<f32>,
+    pub history: TrainingHistory,
+    stages: Vec<Vec<Tree>>,
+}
+
+impl GradientBoostingClassifier {
+    pub fn new(n_estimators: usize) -> GradientBoostingClassifier {
+        let mut params = TreeParams::new(Criterion::MSE);
+        params.max_depth = Some(3);
+        GradientBoostingClassifier {
+            params,
+            boosting: BoostingParams::new(n_estimators),
+            classes: Vec::new(),
+            init: Vec::new(),
+            history: TrainingHistory::default(),
+            stages: Vec::new(),
+        }
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> GradientBoostingClassifier {
+        self.boosting.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn with_subsample(mut self, subsample: f32) -> GradientBoostingClassifier {
+        self.boosting.subsample = subsample;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> GradientBoostingClassifier {
+        self.params.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> GradientBoostingClassifier {
+        self.params.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    pub fn with_max_features(mut self, max_features: MaxFeatures) -> GradientBoostingClassifier {
+        self.params.max_features = max_features;
+        self
+    }
+
+    /// Finds splits among the boundaries of at most `max_bins` bins per feature.
+    pub fn with_histogram(mut self, max_bins: usize) -> GradientBoostingClassifier {
+        self.params.splitter = Splitter::HISTOGRAM(max_bins);
+        self
+    }
+
+    pub fn with_early_stopping(
+        mut self,
+        validation_fraction: f32,
+        n_iter_no_change: usize,
+    ) -> GradientBoostingClassifier {
+        self.boosting.validation_fraction = Some(validation_fraction);
+        self.boosting.n_iter_no_change = n_iter_no_change;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> GradientBoostingClassifier {
+        self.boosting.seed = Some(seed);
+        self
+    }
+
+    pub fn n_estimators(&self) -> usize {
+        return self.stages.len();
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.params.validate(false)?;
+        self.boosting.validate()?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+
+        self.classes = unique_classes(y);
+        if self.classes.len() < 2 {
+            return Err("gradient boosting needs at least two classes".to_string());
+        }
+        let labels = class_labels(y, &self.classes);
+        let n_classes = self.classes.len();
+        let n_scores = if n_classes == 2 { 1 } else { n_classes };
+        let targets = DMatrix::from_fn(x.nrows(), n_scores, |i, k| match n_scores {
+            1 => labels[i] as f32,
+            _ => (labels[i] == k) as usize as f32,
+        });
+        let loss: Box<dyn Loss> = match n_scores {
+            1 => Box::new(LogLoss),
+            _ => Box::new(CrossEntropy),
+        };
+
+        let mut rng = self.boosting.rng();
+        let (train, validation) = self.boosting.split_validation(x.nrows(), &mut rng)?;
+        let priors: Vec<f32> = (0..n_scores)
+            .map(|k| {
+                let count = train.iter().filter(|i| targets[(**i, k)] == 1.0).count();
+                (count as f32 / train.len() as f32).clamp(1e-6, 1.0 - 1e-6)
+            })
+            .collect();
+        self.init = match n_scores {
+            1 => vec![(priors[0] / (1.0 - priors[0])).ln()],
+            _ => priors.iter().map(|p| p.ln()).collect(),
+        };
+        self.stages = Vec::new();
+        self.history = TrainingHistory::default();
+
+        let scale = match n_scores {
+            1 => 1.0,
+            _ => (n_classes - 1) as f32 / n_classes as f32,
+        };
+        let mut scores = DMatrix::from_fn(x.nrows(), n_scores, |_, k| self.init[k]);
+        for _ in 0..self.boosting.n_estimators {
+            let gradient = loss.gradient(&targets, &scores);
+            let hessian = loss.hessian(&targets, &scores).unwrap();
+            let weights = self.boosting.stage_weights(&train, x.nrows(), &mut rng);
+
+            let mut stage = Vec::with_capacity(n_scores);
+            for k in 0..n_scores {
+                let negative_gradient: Vec<f32> = gradient.column(k).iter().map(|g| -g).collect();
+                let mut tree = Tree::build(
+                    x,
+                    &Target::VALUES(&negative_gradient),
+                    &weights,
+                    &self.params,
+                    &mut rng,
+                );
+                update_leaves(&mut tree, x, &weights, |indices| {
+                    scale * newton_step(&gradient, &hessian, k, indices).unwrap_or(0.0)
+                });
+                for (i, leaf) in tree.apply(x).iter().enumerate() {
+                    scores[(i, k)] += self.boosting.learning_rate * tree.nodes[*leaf].value[0];
+                }
+                stage.push(tree);
+            }
+            self.stages.push(stage);
+
+            let train_loss = loss.value(&targets.select_rows(&train), &scores.select_rows(&train));
+            let validation_loss = match validation.is_empty() {
+                true => None,
+                false => Some(loss.value(
+                    &targets.select_rows(&validation),
+                    &scores.select_rows(&validation),
+                )),
+            };
+            if self
+                .boosting
+                .record_stage(&mut self.history, train_loss, validation_loss)
+            {
+                break;
+            }
+        }
+
+        if let Some(best) = self.history.best_epoch {
+            self.stages.truncate(best + 1);
+        }
+        Ok(())
+    }
+
+    /// Raw scores: the log-odds of the second class for two classes, otherwise one
+    /// column of logits per class.
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut scores = DMatrix::from_fn(x.nrows(), self.init.len(), |_, k| self.init[k]);
+        for stage in self.stages.iter() {
+            for (k, tree) in stage.iter().enumerate() {
+                for (i, leaf) in tree.apply(x).iter().enumerate() {
+                    scores[(i, k)] += self.boosting.learning_rate * tree.nodes[*leaf].value[0];
+                }
+            }
+        }
+
+        return scores;
+    }
+
+    pub fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let scores = self.decision_function(x);
+        if scores.ncols() > 1 {
+            return softmax(&scores);
+        }
+
+        return DMatrix::from_fn(x.nrows(), 2, |i, k| {
+            let p = sigmoid(scores[(i, 0)]);
+            if k == 1 {
+                p
+            } else {
+                1.0 - p
+            }
+        });
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return argmax_classes(&self.predict_proba(x), &self.classes);
+    }
+
+    /// Normalised importances summed over the trees of every stage and class.
+    pub fn feature_importances(&self) -> Vec<f32> {
+        return sum_feature_importances(self.stages.iter().flatten());
+    }
+}
@@ -0,0 +1,210 @@
+use nalgebra::DMatrix;
+
+use crate::ensembles::boosting::{
+    newton_step, sum_feature_importances, update_leaves, BoostingParams,
+};
+use crate::losses::loss::Loss;
+use crate::optim::training::TrainingHistory;
+use crate::trees::tree::{weighted_median, weighted_quantile, Target, Tree, TreeParams};
+use crate::utils::types::{Criterion, MaxFeatures, Splitter, TypeRegression};
+
+/// Additive model of regression trees, each fitted to the negative gradient of `loss`
+/// at the current predictions.
+///
+/// `MSE`, `MAE`, `HUBER` and `QUANTILE` get their optimal leaf values (mean, median,
+/// Huber M-estimate and quantile of the residuals); the other losses take a Newton
+/// step where they have a hessian and keep the tree's fit of the gradient otherwise.
+pub struct GradientBoostingRegressor {
+    pub loss: TypeRegression,
+    pub params: TreeParams,
+    pub boosting: BoostingParams,
+    pub init: f32,
+    pub history: TrainingHistory,
+    trees: Vec<Tree>,
+}
+
+impl GradientBoostingRegressor {
+    pub fn new(n_estimators: usize, loss: TypeRegression) -> GradientBoostingRegressor {
+        let mut params = TreeParams::new(Criterion::MSE);
+        params.max_depth = Some(3);
+        GradientBoostingRegressor {
+            loss,
+            params,
+            boosting: BoostingParams::new(n_estimators),
+            init: 0.0,
+            history: TrainingHistory::default(),
+            trees: Vec::new(),
+        }
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f32) -> GradientBoostingRegressor {
+        self.boosting.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn with_subsample(mut self, subsample: f32) -> GradientBoostingRegressor {
+        self.boosting.subsample = subsample;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> GradientBoostingRegressor {
+        self.params.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> GradientBoostingRegressor {
+        self.params.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    pub fn with_max_features(mut self, max_features: MaxFeatures) -> GradientBoostingRegressor {
+        self.params.max_features = max_features;
+        self
+    }
+
+    /// Finds splits among the boundaries of at most `max_bins` bins per feature.
+    pub fn with_histogram(mut self, max_bins: usize) -> GradientBoostingRegressor {
+        self.params.splitter = Splitter::HISTOGRAM(max_bins);
+        self
+    }
+
+    pub fn with_early_stopping(
+        mut self,
+        validation_fraction: f32,
+        n_iter_no_change: usize,
+    ) -> GradientBoostingRegressor {
+        self.boosting.validation_fraction = Some(validation_fraction);
+        self.boosting.n_iter_no_change = n_iter_no_change;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> GradientBoostingRegressor {
+        self.boosting.seed = Some(seed);
+        self
+    }
+
+    pub fn n_estimators(&self) -> usize {
+        return self.trees.len();
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.params.validate(false)?;
+        self.boosting.validate()?;
+        if x.nrows() != y.nrows() || y.ncols() != 1 {
+            return Err(format!(
+                "y should be a single column with one row per sample. |x|: {}, |y|: {}x{}",
+                x.nrows(),
+                y.nrows(),
+                y.ncols()
+            ));
+        }
+
+        let mut rng = self.boosting.rng();
+        let (train, validation) = self.boosting.split_validation(x.nrows(), &mut rng)?;
+        let targets: Vec<f32> = y.iter().cloned().collect();
+        let ones = vec![1.0; x.nrows()];
+
+        self.init = match self.loss {
+            TypeRegression::MAE | TypeRegression::HUBER(_) => {
+                weighted_median(&targets, &ones, &train)
+            }
+            TypeRegression::QUANTILE(q) => weighted_quantile(&targets, &ones, &train, q),
+            _ => train.iter().map(|i| targets[*i]).sum::<f32>() / train.len() as f32,
+        };
+        self.trees = Vec::new();
+        self.history = TrainingHistory::default();
+
+        let mut y_hat = DMatrix::<f32>::from_element(x.nrows(), 1, self.init);
+        for _ in 0..self.boosting.n_estimators {
+            let gradient = self.loss.gradient(y, &y_hat);
+            let hessian = self.loss.hessian(y, &y_hat);
+            let negative_gradient: Vec<f32> = gradient.iter().map(|g| -g).collect();
+            let weights = self.boosting.stage_weights(&train, x.nrows(), &mut rng);
+
+            let mut tree = Tree::build(
+                x,
+                &Target::VALUES(&negative_gradient),
+                &weights,
+                &self.params,
+                &mut rng,
+            );
+            let residuals: Vec<f32> = (0..x.nrows()).map(|i| targets[i] - y_hat[(i, 0)]).collect();
+            match self.loss {
+                TypeRegression::MAE => update_leaves(&mut tree, x, &weights, |indices| {
+                    weighted_median(&residuals, &weights, indices)
+                }),
+                TypeRegression::QUANTILE(q) => update_leaves(&mut tree, x, &weights, |indices| {
+                    weighted_quantile(&residuals, &weights, indices, q)
+                }),
+                TypeRegression::HUBER(delta) => update_leaves(&mut tree, x, &weights, |indices| {
+                    huber_leaf_value(&residuals, indices, delta)
+                }),
+                _ => {
+                    if let Some(hessian) = hessian {
+                        update_leaves(&mut tree, x, &weights, |indices| {
+                            newton_step(&gradient, &hessian, 0, indices).unwrap_or(
+                                indices.iter().map(|i| negative_gradient[*i]).sum::<f32>()
+                                    / indices.len() as f32,
+                            )
+                        });
+                    }
+                }
+            }
+
+            for (i, leaf) in tree.apply(x).iter().enumerate() {
+                y_hat[(i, 0)] += self.boosting.learning_rate * tree.nodes[*leaf].value[0];
+            }
+            self.trees.push(tree);
+
+            let train_loss = self.subset_loss(y, &y_hat, &train);
+            let validation_loss = match validation.is_empty() {
+                true => None,
+                false => Some(self.subset_loss(y, &y_hat, &validation)),
+            };
+            if self
+                .boosting
+                .record_stage(&mut self.history, train_loss, validation_loss)
+            {
+                break;
+            }
+        }
+
+        if let Some(best) = self.history.best_epoch {
+            self.trees.truncate(best + 1);
+        }
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut y_hat = DMatrix::<f32>::from_element(x.nrows(), 1, self.init);
+        for tree in self.trees.iter() {
+            for (i, leaf) in tree.apply(x).iter().enumerate() {
+                y_hat[(i, 0)] += self.boosting.learning_rate * tree.nodes[*leaf].value[0];
+            }
+        }
+
+        return y_hat;
+    }
+
+    /// Normalised importances summed over the stages.
+    pub fn feature_importances(&self) -> Vec<f32> {
+        return sum_feature_importances(self.trees.iter());
+    }
+
+    fn subset_loss(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>, indices: &[usize]) -> f32 {
+        return self
+            .loss
+            .value(&y.select_rows(indices), &y_hat.select_rows(indices));
+    }
+}
+
+/// Median of the residuals plus the mean of their deviations from it clipped to `delta`.
+fn huber_leaf_value(residuals: &[f32], indices: &[usize], delta: f32) -> f32 {
+    let median = weighted_median(residuals, &vec![1.0; residuals.len()], indices);
+    let correction: f32 = indices
+        .iter()
+        .map(|i| (residuals[*i] - median).clamp(-delta, delta))
+        .sum();
+
+    return median + correction / indices.len() as f32;
+}
@@ -0,0 +1,7 @@
+pub mod boosting;
+pub mod extra_trees;
+pub mod forest;
+pub mod gradient_boosting_classifier;
+pub mod gradient_boosting_regressor;
+pub mod random_forest_classifier;
+pub mod random_forest_regressor;
@@ -0,0 +1,156 @@
+use nalgebra::DMatrix;
+
+use crate::ensembles::forest::{build_trees, mean_feature_importances, BaggedTree, ForestParams};
+use crate::trees::decision_tree_classifier::class_labels;
+use crate::trees::tree::{Target, TreeParams};
+use crate::utils::types::{Criterion, MaxFeatures, Splitter};
+use crate::utils::utils::{accuracy, argmax_classes, unique_classes};
+
+/// Bagged classification trees whose class probabilities are averaged. Each split only
+/// considers `sqrt(n_features)` random features by default.
+///
+/// With `compute_oob` and bootstrapping, `oob_score` is the accuracy of every training
+/// sample predicted by the trees that did not see it.
+pub struct RandomForestClassifier {
+    pub params: TreeParams,
+    pub forest: ForestParams,
+    pub compute_oob: bool,
+    pub classes: Vec<f32>,
+    pub oob_score: Option<f32>,
+    trees: Vec<BaggedTree>,
+}
+
+impl RandomForestClassifier {
+    pub fn new(n_estimators: usize, criterion: Criterion) -> RandomForestClassifier {
+        let mut params = TreeParams::new(criterion);
+        params.max_features = MaxFeatures::SQRT;
+        RandomForestClassifier {
+            params,
+            forest: ForestParams::new(n_estimators),
+            compute_oob: false,
+            classes: Vec::new(),
+            oob_score: None,
+            trees: Vec::new(),
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> RandomForestClassifier {
+        self.params.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_min_samples_split(mut self, min_samples_split: usize) -> RandomForestClassifier {
+        self.params.min_samples_split = min_samples_split;
+        self
+    }
+
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> RandomForestClassifier {
+        self.params.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    pub fn with_max_features(mut self, max_features: MaxFeatures) -> RandomForestClassifier {
+        self.params.max_features = max_features;
+        self
+    }
+
+    pub fn with_splitter(mut self, splitter: Splitter) -> RandomForestClassifier {
+        self.params.splitter = splitter;
+        self
+    }
+
+    pub fn with_bootstrap(mut self, bootstrap: bool) -> RandomForestClassifier {
+        self.forest.bootstrap = bootstrap;
+        self
+    }
+
+    pub fn with_max_samples(mut self, max_samples: f32) -> RandomForestClassifier {
+        self.forest.max_samples = Some(max_samples);
+        self
+    }
+
+    pub fn with_oob_score(mut self) -> RandomForestClassifier {
+        self.compute_oob = true;
+        self
+    }
+
+    pub fn with_n_jobs(mut self, n_jobs: usize) -> RandomForestClassifier {
+        self.forest.n_jobs = n_jobs;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomForestClassifier {
+        self.forest.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.params.validate(true)?;
+        self.forest.validate()?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        if self.compute_oob && !self.forest.bootstrap {
+            return Err("the out-of-bag score needs bootstrap sampling".to_string());
+        }
+
+        self.classes = unique_classes(y);
+        let labels = class_labels(y, &self.classes);
+        self.trees = build_trees(
+            x,
+            &Target::CLASSES(&labels, self.classes.len()),
+            &self.forest,
+            &self.params,
+        );
+
+        self.oob_score = None;
+        if self.compute_oob {
+            self.oob_score = Some(self.out_of_bag_accuracy(x, y));
+        }
+        Ok(())
+    }
+
+    /// Mean of the class probabilities of the trees, one column per class.
+    pub fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut probabilities = DMatrix::<f32>::zeros(x.nrows(), self.classes.len());
+        for bagged in self.trees.iter() {
+            for (i, leaf) in bagged.tree.apply(x).iter().enumerate() {
+                for (k, p) in bagged.tree.nodes[*leaf].value.iter().enumerate() {
+                    probabilities[(i, k)] += p / self.trees.len() as f32;
+                }
+            }
+        }
+
+        return probabilities;
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return argmax_classes(&self.predict_proba(x), &self.classes);
+    }
+
+    pub fn feature_importances(&self) -> Vec<f32> {
+        return mean_feature_importances(&self.trees);
+    }
+
+    fn out_of_bag_accuracy(&self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> f32 {
+        let mut votes = DMatrix::<f32>::zeros(x.nrows(), self.classes.len());
+        for bagged in self.trees.iter() {
+            for i in (0..x.nrows()).filter(|i| bagged.in_bag[*i] == 0.0) {
+                let leaf = bagged.tree.leaf(x, i);
+                for (k, p) in bagged.tree.nodes[leaf].value.iter().enumerate() {
+                    votes[(i, k)] += p;
+                }
+            }
+        }
+
+        let scored: Vec<usize> = (0..x.nrows())
+            .filter(|i| votes.row(*i).sum() > 0.0)
+            .collect();
+        let predictions = argmax_classes(&votes.select_rows(scored.iter()), &self.classes);
+        return accuracy(predictions, scored.iter().map(|i| y[(*i, 0)]).collect());
+    }
+}
@@ -0,0 +1,142 @@
+use nalgebra::DMatrix;
+
+use crate::ensembles::forest::{build_trees, mean_feature_importances, BaggedTree, ForestParams};
+use crate::trees::tree::{Target, TreeParams};
+use crate::utils::stats::r2_per_target;
+use crate::utils::types::{Criterion, MaxFeatures, Splitter};
+
+/// Bagged regression trees whose predictions are averaged. Every split considers all
+/// features by default.
+///
+/// With `compute_oob` and bootstrapping, `oob_score` is the R^2 of every training sample
+/// predicted by the trees that did not see it.
+pub struct RandomForestRegressor {
+    pub params: TreeParams,
+    pub forest: ForestParams,
+    pub compute_oob: bool,
+    pub oob_score: Option<f32>,
+    trees: Vec<BaggedTree>,
+}
+
+impl RandomForestRegressor {
+    pub fn new(n_estimators: usize, criterion: Criterion) -> RandomForestRegressor {
+        RandomForestRegressor {
+            params: TreeParams::new(criterion),
+            forest: ForestParams::new(n_estimators),
+            compute_oob: false,
+            oob_score: None,
+            trees: Vec::new(),
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> RandomForestRegressor {
+        self.params.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_min_samples_split(mut self, min_samples_split: usize) -> RandomForestRegressor {
+        self.params.min_samples_split = min_samples_split;
+        self
+    }
+
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> RandomForestRegressor {
+        self.params.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    pub fn with_max_features(mut self, max_features: MaxFeatures) -> RandomForestRegressor {
+        self.params.max_features = max_features;
+        self
+    }
+
+    pub fn with_splitter(mut self, splitter: Splitter) -> RandomForestRegressor {
+        self.params.splitter = splitter;
+        self
+    }
+
+    pub fn with_bootstrap(mut self, bootstrap: bool) -> RandomForestRegressor {
+        self.forest.bootstrap = bootstrap;
+        self
+    }
+
+    pub fn with_max_samples(mut self, max_samples: f32) -> RandomForestRegressor {
+        self.forest.max_samples = Some(max_samples);
+        self
+    }
+
+    pub fn with_oob_score(mut self) -> RandomForestRegressor {
+        self.compute_oob = true;
+        self
+    }
+
+    pub fn with_n_jobs(mut self, n_jobs: usize) -> RandomForestRegressor {
+        self.forest.n_jobs = n_jobs;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> RandomForestRegressor {
+        self.forest.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.params.validate(false)?;
+        self.forest.validate()?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        if self.compute_oob && !self.forest.bootstrap {
+            return Err("the out-of-bag score needs bootstrap sampling".to_string());
+        }
+
+        let values: Vec<f32> = y.column(0).iter().cloned().collect();
+        self.trees = build_trees(x, &Target::VALUES(&values), &self.forest, &self.params);
+
+        self.oob_score = None;
+        if self.compute_oob {
+            self.oob_score = Some(self.out_of_bag_r2(x, y));
+        }
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut y_hat = DMatrix::<f32>::zeros(x.nrows(), 1);
+        for bagged in self.trees.iter() {
+            for (i, leaf) in bagged.tree.apply(x).iter().enumerate() {
+                y_hat[(i, 0)] += bagged.tree.nodes[*leaf].value[0] / self.trees.len() as f32;
+            }
+        }
+
+        return y_hat;
+    }
+
+    pub fn feature_importances(&self) -> Vec<f32> {
+        return mean_feature_importances(&self.trees);
+    }
+
+    fn out_of_bag_r2(&self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> f32 {
+        let mut sums = vec![0.0; x.nrows()];
+        let mut counts = vec![0.0; x.nrows()];
+        for bagged in self.trees.iter() {
+            for i in (0..x.nrows()).filter(|i| bagged.in_bag[*i] == 0.0) {
+                sums[i] += bagged.tree.nodes[bagged.tree.leaf(x, i)].value[0];
+                counts[i] += 1.0;
+            }
+        }
+
+        let scored: Vec<usize> = (0..x.nrows()).filter(|i| counts[*i] > 0.0).collect();
+        let y_hat = DMatrix::from_iterator(
+            scored.len(),
+            1,
+            scored.iter().map(|i| sums[*i] / counts[*i]),
+        );
+        return r2_per_target(
+            &y.select_rows(scored.iter()).columns(0, 1).into_owned(),
+            &y_hat,
+        )[0];
+    }
+}
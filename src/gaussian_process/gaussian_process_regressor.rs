@@ -0,0 +1,235 @@
+use std::f32::consts::PI;
+
+use nalgebra::{Cholesky, DMatrix, Dynamic};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::gaussian_process::kernels::GPKernel;
+use crate::kernels::kernel::Kernel;
+use crate::optim::adam::Adam;
+use crate::optim::optimizer::Optimizer;
+
+/// Exact Gaussian process regression with a single target column.
+///
+/// The posterior is computed from the Cholesky factor of `K + alpha * I`, `alpha` being
+/// a small jitter (or the known noise variance) added to the diagonal. Unless `n_iter`
+/// is 0, `fit` first maximises the log marginal likelihood over the log hyperparameters
+/// of the kernel with Adam, restarting `n_restarts` times from random hyperparameters
+/// and keeping the best run. With `normalize_y` the targets are standardised before
+/// fitting, which makes a unit-variance prior sensible.
+pub struct GaussianProcessRegressor {
+    pub kernel: GPKernel,
+    pub alpha: f32,
+    pub normalize_y: bool,
+    pub n_iter: usize,
+    pub learning_rate: f32,
+    pub n_restarts: usize,
+    pub seed: Option<u64>,
+    pub log_marginal_likelihood_value: f32,
+    x_train: DMatrix<f32>,
+    y_train: DMatrix<f32>,
+    y_mean: f32,
+    y_std: f32,
+    cholesky: Option<Cholesky<f32, Dynamic>>,
+    dual_coef: DMatrix<f32>,
+}
+
+/// Bounds of the log hyperparameters during optimisation, `[1e-5, 1e5]`.
+const LOG_BOUND: f32 = 11.5;
+
+impl GaussianProcessRegressor {
+    pub fn new(kernel: GPKernel) -> GaussianProcessRegressor {
+        GaussianProcessRegressor {
+            kernel,
+            alpha: 1e-5,
+            normalize_y: false,
+            n_iter: 100,
+            learning_rate: 0.05,
+            n_restarts: 0,
+            seed: None,
+            log_marginal_likelihood_value: f32::NEG_INFINITY,
+            x_train: DMatrix::zeros(0, 0),
+            y_train: DMatrix::zeros(0, 1),
+            y_mean: 0.0,
+            y_std: 1.0,
+            cholesky: None,
+            dual_coef: DMatrix::zeros(0, 1),
+        }
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> GaussianProcessRegressor {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_normalize_y(mut self) -> GaussianProcessRegressor {
+        self.normalize_y = true;
+        self
+    }
+
+    /// Adam steps on the log marginal likelihood; 0 keeps the kernel as given.
+    pub fn with_optimizer(mut self, n_iter: usize, learning_rate: f32) -> GaussianProcessRegressor {
+        self.n_iter = n_iter;
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn with_restarts(mut self, n_restarts: usize) -> GaussianProcessRegressor {
+        self.n_restarts = n_restarts;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> GaussianProcessRegressor {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        if x.nrows() != y.nrows() || y.ncols() != 1 {
+            return Err(format!(
+                "y should be a single column with one row per sample. |x|: {}, |y|: {}x{}",
+                x.nrows(),
+                y.nrows(),
+                y.ncols()
+            ));
+        }
+        if self.alpha < 0.0 {
+            return Err(format!("alpha should be non-negative, got {}", self.alpha));
+        }
+        self.kernel.validate()?;
+
+        (self.y_mean, self.y_std) = (0.0, 1.0);
+        if self.normalize_y {
+            self.y_mean = y.mean();
+            self.y_std = y.map(|v| (v - self.y_mean).powi(2)).mean().sqrt();
+            if self.y_std <= f32::EPSILON {
+                self.y_std = 1.0;
+            }
+        }
+        self.y_train = y.map(|v| (v - self.y_mean) / self.y_std);
+        self.x_train = x.clone();
+
+        if self.n_iter > 0 {
+            let mut rng = match self.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let initial = self.kernel.theta();
+            let mut best = self.maximize(initial.clone());
+            for _ in 0..self.n_restarts {
+                let start = initial.iter().map(|_| rng.gen_range(-3.0..3.0)).collect();
+                let candidate = self.maximize(start);
+                if candidate.1 > best.1 {
+                    best = candidate;
+                }
+            }
+            self.kernel = self.kernel.with_theta(&best.0);
+        }
+
+        let (k, _) = self.kernel.covariance_with_gradient(x);
+        let cholesky = self.factorize(k)?;
+        self.dual_coef = cholesky.solve(&self.y_train);
+        self.log_marginal_likelihood_value =
+            log_likelihood(&cholesky, &self.y_train, &self.dual_coef);
+        self.cholesky = Some(cholesky);
+        Ok(())
+    }
+
+    /// Log marginal likelihood of the (normalised) training targets under the kernel
+    /// with log hyperparameters `theta`, or under the fitted kernel with `None`.
+    pub fn log_marginal_likelihood(&self, theta: Option<&[f32]>) -> f32 {
+        let kernel = theta.map_or(self.kernel.clone(), |theta| self.kernel.with_theta(theta));
+        let (k, _) = kernel.covariance_with_gradient(&self.x_train);
+        return match self.factorize(k) {
+            Ok(cholesky) => {
+                log_likelihood(&cholesky, &self.y_train, &cholesky.solve(&self.y_train))
+            }
+            Err(_) => f32::NEG_INFINITY,
+        };
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let k_star = self.kernel.gram(x, &self.x_train);
+        return (k_star * &self.dual_coef).map(|v| v * self.y_std + self.y_mean);
+    }
+
+    /// Posterior mean and standard deviation of the latent function at every row of
+    /// `x`; the `WHITE` noise of the kernel is included in the deviation.
+    pub fn predict_with_std(&self, x: &DMatrix<f32>) -> (DMatrix<f32>, DMatrix<f32>) {
+        let cholesky = self
+            .cholesky
+            .as_ref()
+            .expect("the model should be fitted first");
+        let k_star = self.kernel.gram(x, &self.x_train);
+        let mean = (&k_star * &self.dual_coef).map(|v| v * self.y_std + self.y_mean);
+
+        let v = cholesky
+            .l()
+            .solve_lower_triangular(&k_star.transpose())
+            .unwrap();
+        let prior = self.kernel.diagonal(x);
+        let std = DMatrix::from_fn(x.nrows(), 1, |i, _| {
+            (prior[i] - v.column(i).norm_squared()).max(0.0).sqrt() * self.y_std
+        });
+
+        return (mean, std);
+    }
+
+    fn factorize(&self, mut k: DMatrix<f32>) -> Result<Cholesky<f32, Dynamic>, String> {
+        for i in 0..k.nrows() {
+            k[(i, i)] += self.alpha;
+        }
+        return Cholesky::new(k).ok_or_else(|| {
+            format!(
+                "the covariance of {:?} is not positive definite, try a larger alpha",
+                self.kernel
+            )
+        });
+    }
+
+    /// Adam ascent of the log marginal likelihood from `theta`, returning the best
+    /// hyperparameters visited and their likelihood.
+    fn maximize(&self, mut theta: Vec<f32>) -> (Vec<f32>, f32) {
+        let y = &self.y_train;
+        let mut optimizer = Adam::new(self.learning_rate);
+        let mut best = (theta.clone(), f32::NEG_INFINITY);
+        for _ in 0..self.n_iter {
+            let kernel = self.kernel.with_theta(&theta);
+            let (k, gradients) = kernel.covariance_with_gradient(&self.x_train);
+            let Ok(cholesky) = self.factorize(k) else {
+                break;
+            };
+            let dual_coef = cholesky.solve(y);
+            let likelihood = log_likelihood(&cholesky, y, &dual_coef);
+            if likelihood > best.1 {
+                best = (theta.clone(), likelihood);
+            }
+
+            // d/dtheta = tr((a a^T - K^-1) dK/dtheta) / 2, averaged over the samples.
+            let w = &dual_coef * dual_coef.transpose() - cholesky.inverse();
+            let n = y.nrows() as f32;
+            let gradient = DMatrix::from_iterator(
+                theta.len(),
+                1,
+                gradients.iter().map(|g| -0.5 * w.dot(g) / n),
+            );
+            let mut parameters = DMatrix::from_column_slice(theta.len(), 1, &theta);
+            optimizer.step(&mut parameters, &gradient);
+            theta = parameters
+                .iter()
+                .map(|t| t.clamp(-LOG_BOUND, LOG_BOUND))
+                .collect();
+        }
+
+        return best;
+    }
+}
+
+fn log_likelihood(
+    cholesky: &Cholesky<f32, Dynamic>,
+    y: &DMatrix<f32>,
+    dual_coef: &DMatrix<f32>,
+) -> f32 {
+    let log_determinant: f32 = cholesky.l_dirty().diagonal().iter().map(|d| d.ln()).sum();
+    return -0.5 * y.dot(dual_coef) - log_determinant - 0.5 * y.nrows() as f32 * (2.0 * PI).ln();
+}
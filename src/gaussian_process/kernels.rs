@@ -0,0 +1,231 @@
+use std::ops::{Add, Mul};
+
+use nalgebra::DMatrix;
+
+use crate::kernels::kernel::{squared_distances, Kernel};
+
+/// Covariance function of a Gaussian process, composable with `+` and `*`.
+///
+/// `RBF(length_scale)`, `MATERN(length_scale, nu)` with `nu` in `{0.5, 1.5, 2.5}` and
+/// `RATIONAL_QUADRATIC(length_scale, alpha)` have unit variance; scale them by a
+/// `CONSTANT(value)` and add `WHITE(noise_level)` to model noise on the observations.
+/// The hyperparameters (all but `nu`) are optimised in log space.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum GPKernel {
+    RBF(f32),
+    MATERN(f32, f32),
+    RATIONAL_QUADRATIC(f32, f32),
+    WHITE(f32),
+    CONSTANT(f32),
+    SUM(Box<GPKernel>, Box<GPKernel>),
+    PRODUCT(Box<GPKernel>, Box<GPKernel>),
+}
+
+impl Add for GPKernel {
+    type Output = GPKernel;
+
+    fn add(self, other: GPKernel) -> GPKernel {
+        GPKernel::SUM(Box::new(self), Box::new(other))
+    }
+}
+
+impl Mul for GPKernel {
+    type Output = GPKernel;
+
+    fn mul(self, other: GPKernel) -> GPKernel {
+        GPKernel::PRODUCT(Box::new(self), Box::new(other))
+    }
+}
+
+/// Cross covariance between different samples, so `WHITE` contributes nothing.
+impl Kernel for GPKernel {
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+        match self {
+            GPKernel::WHITE(_) => DMatrix::zeros(a.nrows(), b.nrows()),
+            GPKernel::CONSTANT(value) => DMatrix::from_element(a.nrows(), b.nrows(), *value),
+            GPKernel::SUM(left, right) => left.gram(a, b) + right.gram(a, b),
+            GPKernel::PRODUCT(left, right) => left.gram(a, b).component_mul(&right.gram(a, b)),
+            stationary => squared_distances(a, b).map(|d| stationary.correlation(d).0),
+        }
+    }
+}
+
+impl GPKernel {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match self {
+            GPKernel::MATERN(_, nu) if ![0.5, 1.5, 2.5].contains(nu) => Err(format!(
+                "nu of the Matern kernel should be 0.5, 1.5 or 2.5, got {}",
+                nu
+            )),
+            GPKernel::SUM(left, right) | GPKernel::PRODUCT(left, right) => {
+                left.validate()?;
+                right.validate()
+            }
+            kernel => match kernel.theta().iter().all(|t| t.is_finite()) {
+                true => Ok(()),
+                false => Err(format!(
+                    "hyperparameters of {:?} should be positive",
+                    kernel
+                )),
+            },
+        }
+    }
+
+    /// Logarithms of the hyperparameters, depth first.
+    pub fn theta(&self) -> Vec<f32> {
+        match self {
+            GPKernel::RBF(length_scale) | GPKernel::MATERN(length_scale, _) => {
+                vec![length_scale.ln()]
+            }
+            GPKernel::RATIONAL_QUADRATIC(length_scale, alpha) => {
+                vec![length_scale.ln(), alpha.ln()]
+            }
+            GPKernel::WHITE(value) | GPKernel::CONSTANT(value) => vec![value.ln()],
+            GPKernel::SUM(left, right) | GPKernel::PRODUCT(left, right) => {
+                let mut theta = left.theta();
+                theta.extend(right.theta());
+                theta
+            }
+        }
+    }
+
+    /// The same kernel with the hyperparameters `exp(theta)`.
+    pub fn with_theta(&self, theta: &[f32]) -> GPKernel {
+        let mut values = theta.iter().map(|t| t.exp());
+        return self.rebuild(&mut values);
+    }
+
+    fn rebuild(&self, values: &mut dyn Iterator<Item = f32>) -> GPKernel {
+        let mut next = || {
+            values
+                .next()
+                .expect("theta should have one value per hyperparameter")
+        };
+        match self {
+            GPKernel::RBF(_) => GPKernel::RBF(next()),
+            GPKernel::MATERN(_, nu) => GPKernel::MATERN(next(), *nu),
+            GPKernel::RATIONAL_QUADRATIC(_, _) => GPKernel::RATIONAL_QUADRATIC(next(), next()),
+            GPKernel::WHITE(_) => GPKernel::WHITE(next()),
+            GPKernel::CONSTANT(_) => GPKernel::CONSTANT(next()),
+            GPKernel::SUM(left, right) => {
+                let left = left.rebuild(values);
+                left + right.rebuild(values)
+            }
+            GPKernel::PRODUCT(left, right) => {
+                let left = left.rebuild(values);
+                left * right.rebuild(values)
+            }
+        }
+    }
+
+    /// Prior variance of every row of `x`, noise included.
+    pub fn diagonal(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        match self {
+            GPKernel::WHITE(value) | GPKernel::CONSTANT(value) => vec![*value; x.nrows()],
+            GPKernel::SUM(left, right) => {
+                zip_with(left.diagonal(x), right.diagonal(x), |l, r| l + r)
+            }
+            GPKernel::PRODUCT(left, right) => {
+                zip_with(left.diagonal(x), right.diagonal(x), |l, r| l * r)
+            }
+            _ => vec![1.0; x.nrows()],
+        }
+    }
+
+    /// Covariance of the training samples `x` with each other, noise included, and its
+    /// derivative with respect to every entry of `theta`.
+    pub(crate) fn covariance_with_gradient(
+        &self,
+        x: &DMatrix<f32>,
+    ) -> (DMatrix<f32>, Vec<DMatrix<f32>>) {
+        let n = x.nrows();
+        match self {
+            GPKernel::WHITE(value) => {
+                let k = DMatrix::identity(n, n) * *value;
+                (k.clone(), vec![k])
+            }
+            GPKernel::CONSTANT(value) => {
+                let k = DMatrix::from_element(n, n, *value);
+                (k.clone(), vec![k])
+            }
+            GPKernel::SUM(left, right) => {
+                let (left_k, mut gradient) = left.covariance_with_gradient(x);
+                let (right_k, right_gradient) = right.covariance_with_gradient(x);
+                gradient.extend(right_gradient);
+                (left_k + right_k, gradient)
+            }
+            GPKernel::PRODUCT(left, right) => {
+                let (left_k, left_gradient) = left.covariance_with_gradient(x);
+                let (right_k, right_gradient) = right.covariance_with_gradient(x);
+                let mut gradient: Vec<DMatrix<f32>> = left_gradient
+                    .iter()
+                    .map(|g| g.component_mul(&right_k))
+                    .collect();
+                gradient.extend(right_gradient.iter().map(|g| g.component_mul(&left_k)));
+                (left_k.component_mul(&right_k), gradient)
+            }
+            stationary => {
+                let distances = squared_distances(x, x);
+                let n_theta = stationary.theta().len();
+                let mut k = DMatrix::zeros(n, n);
+                let mut gradient = vec![DMatrix::zeros(n, n); n_theta];
+                for j in 0..n {
+                    for i in 0..n {
+                        let (value, derivatives) = stationary.correlation(distances[(i, j)]);
+                        k[(i, j)] = value;
+                        for (t, derivative) in derivatives.iter().enumerate() {
+                            gradient[t][(i, j)] = *derivative;
+                        }
+                    }
+                }
+                (k, gradient)
+            }
+        }
+    }
+
+    /// Value of a stationary kernel at squared distance `d2` and its derivatives with
+    /// respect to the log hyperparameters.
+    fn correlation(&self, d2: f32) -> (f32, Vec<f32>) {
+        match *self {
+            GPKernel::RBF(l) => {
+                let k = (-0.5 * d2 / (l * l)).exp();
+                (k, vec![k * d2 / (l * l)])
+            }
+            GPKernel::MATERN(l, nu) => {
+                let d = d2.sqrt() / l;
+                if nu == 0.5 {
+                    let k = (-d).exp();
+                    (k, vec![k * d])
+                } else if nu == 1.5 {
+                    let r = 3f32.sqrt() * d;
+                    ((1.0 + r) * (-r).exp(), vec![r * r * (-r).exp()])
+                } else {
+                    let r = 5f32.sqrt() * d;
+                    let k = (1.0 + r + r * r / 3.0) * (-r).exp();
+                    (k, vec![r * r / 3.0 * (1.0 + r) * (-r).exp()])
+                }
+            }
+            GPKernel::RATIONAL_QUADRATIC(l, alpha) => {
+                let base = 1.0 + d2 / (2.0 * alpha * l * l);
+                let k = base.powf(-alpha);
+                (
+                    k,
+                    vec![
+                        k / base * d2 / (l * l),
+                        k * (-alpha * base.ln() + d2 / (2.0 * l * l * base)),
+                    ],
+                )
+            }
+            _ => unreachable!("{:?} is not a stationary kernel", self),
+        }
+    }
+}
+
+fn zip_with(left: Vec<f32>, right: Vec<f32>, f: fn(f32, f32) -> f32) -> Vec<f32> {
+    return left
+        .iter()
+        .zip(right.iter())
+        .map(|(l, r)| f(*l, *r))
+        .collect();
+}
@@ -0,0 +1,2 @@
+pub mod gaussian_process_regressor;
+pub mod kernels;
@@ -0,0 +1,142 @@
+use nalgebra::DMatrix;
+
+use crate::utils::types::TypeKernel;
+
+/// Positive semi-definite similarity between samples.
+///
+/// `gram(a, b)` has one row per row of `a` and one column per row of `b`. Implement
+/// this trait, or wrap a closure in [`CustomKernel`], to use a kernel of your own, and
+/// combine kernels with [`KernelSum`], [`KernelProduct`] and [`ScaledKernel`].
+pub trait Kernel: Send + Sync {
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32>;
+}
+
+impl Kernel for TypeKernel {
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+        match *self {
+            TypeKernel::LINEAR => a * b.transpose(),
+            TypeKernel::POLY(degree, gamma, coef0) => {
+                (a * b.transpose()).map(|v| (gamma * v + coef0).powi(degree))
+            }
+            TypeKernel::RBF(gamma) => squared_distances(a, b).map(|d| (-gamma * d).exp()),
+            TypeKernel::LAPLACIAN(gamma) => DMatrix::from_fn(a.nrows(), b.nrows(), |i, j| {
+                let distance: f32 = (a.row(i) - b.row(j)).iter().map(|v| v.abs()).sum();
+                (-gamma * distance).exp()
+            }),
+            TypeKernel::SIGMOID(gamma, coef0) => {
+                (a * b.transpose()).map(|v| (gamma * v + coef0).tanh())
+            }
+        }
+    }
+}
+
+/// Squared euclidean distances between the rows of `a` and `b`, expanded as
+/// `|a|^2 + |b|^2 - 2 <a, b>` so the bulk of the work is one matrix product.
+pub fn squared_distances(a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+    let a_norms: Vec<f32> = a.row_iter().map(|row| row.norm_squared()).collect();
+    let b_norms: Vec<f32> = b.row_iter().map(|row| row.norm_squared()).collect();
+    let mut distances = a * b.transpose();
+    for j in 0..b.nrows() {
+        for i in 0..a.nrows() {
+            distances[(i, j)] = (a_norms[i] + b_norms[j] - 2.0 * distances[(i, j)]).max(0.0);
+        }
+    }
+
+    return distances;
+}
+
+/// Kernel given by a function of two samples, evaluated on every pair of rows.
+pub struct CustomKernel<F> {
+    function: F,
+}
+
+impl<F> CustomKernel<F>
+where
+    F: Fn(&[f32], &[f32]) -> f32 + Send + Sync,
+{
+    pub fn new(function: F) -> CustomKernel<F> {
+        CustomKernel { function }
+    }
+}
+
+impl<F> Kernel for CustomKernel<F>
+where
+    F: Fn(&[f32], &[f32]) -> f32 + Send + Sync,
+{
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+        let a_rows: Vec<Vec<f32>> = a
+            .row_iter()
+            .map(|row| row.iter().cloned().collect())
+            .collect();
+        let b_rows: Vec<Vec<f32>> = b
+            .row_iter()
+            .map(|row| row.iter().cloned().collect())
+            .collect();
+        return DMatrix::from_fn(a.nrows(), b.nrows(), |i, j| {
+            (self.function)(&a_rows[i], &b_rows[j])
+        });
+    }
+}
+
+/// `K1(a, b) + K2(a, b)`.
+pub struct KernelSum {
+    pub left: Box<dyn Kernel>,
+    pub right: Box<dyn Kernel>,
+}
+
+impl KernelSum {
+    pub fn new<A: Kernel + 'static, B: Kernel + 'static>(left: A, right: B) -> KernelSum {
+        KernelSum {
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+}
+
+impl Kernel for KernelSum {
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+        self.left.gram(a, b) + self.right.gram(a, b)
+    }
+}
+
+/// `K1(a, b) * K2(a, b)`, element-wise.
+pub struct KernelProduct {
+    pub left: Box<dyn Kernel>,
+    pub right: Box<dyn Kernel>,
+}
+
+impl KernelProduct {
+    pub fn new<A: Kernel + 'static, B: Kernel + 'static>(left: A, right: B) -> KernelProduct {
+        KernelProduct {
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+}
+
+impl Kernel for KernelProduct {
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+        self.left.gram(a, b).component_mul(&self.right.gram(a, b))
+    }
+}
+
+/// `scale * K(a, b)` with a positive `scale`.
+pub struct ScaledKernel {
+    pub scale: f32,
+    pub kernel: Box<dyn Kernel>,
+}
+
+impl ScaledKernel {
+    pub fn new<K: Kernel + 'static>(scale: f32, kernel: K) -> ScaledKernel {
+        ScaledKernel {
+            scale,
+            kernel: Box::new(kernel),
+        }
+    }
+}
+
+impl Kernel for ScaledKernel {
+    fn gram(&self, a: &DMatrix<f32>, b: &DMatrix<f32>) -> DMatrix<f32> {
+        self.kernel.gram(a, b) * self.scale
+    }
+}
@@ -1,3 +1,15 @@
+pub mod classifications;
 pub mod clusters;
+pub mod decomposition;
+pub mod ensembles;
+pub mod gaussian_process;
+pub mod kernels;
+pub mod losses;
+pub mod naive_bayes;
+pub mod neural_network;
+pub mod online;
+pub mod optim;
 pub mod regressions;
+pub mod svm;
+pub mod trees;
 pub mod utils;
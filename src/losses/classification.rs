@@ -0,0 +1,151 @@
+use nalgebra::DMatrix;
+
+use crate::losses::loss::Loss;
+use crate::losses::regression::row_sums;
+
+pub fn sigmoid(z: f32) -> f32 {
+    if z >= 0.0 {
+        1.0 / (1.0 + (-z).exp())
+    } else {
+        let e = z.exp();
+        e / (1.0 + e)
+    }
+}
+
+/// Row-wise softmax of a matrix of scores.
+pub fn softmax(scores: &DMatrix<f32>) -> DMatrix<f32> {
+    let mut probabilities = scores.clone();
+    for i in 0..scores.nrows() {
+        let max = scores.row(i).max();
+        let mut total = 0.0;
+        for j in 0..scores.ncols() {
+            probabilities[(i, j)] = (scores[(i, j)] - max).exp();
+            total += probabilities[(i, j)];
+        }
+        for j in 0..scores.ncols() {
+            probabilities[(i, j)] /= total;
+        }
+    }
+
+    return probabilities;
+}
+
+/// Row-wise logarithm of [`softmax`], computed without overflow.
+pub fn log_softmax(scores: &DMatrix<f32>) -> DMatrix<f32> {
+    let mut log_probabilities = scores.clone();
+    for i in 0..scores.nrows() {
+        let max = scores.row(i).max();
+        let log_total = scores.row(i).map(|s| (s - max).exp()).sum().ln() + max;
+        for j in 0..scores.ncols() {
+            log_probabilities[(i, j)] -= log_total;
+        }
+    }
+
+    return log_probabilities;
+}
+
+/// Binary logistic loss on raw scores (logits), with targets in `{0, 1}`.
+pub struct LogLoss;
+
+impl Loss for LogLoss {
+    fn name(&self) -> &str {
+        "log_loss"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        // log(1 + exp(z)) - y * z, written to avoid overflowing exp.
+        row_sums(&y_hat.zip_map(y, |z, t| z.max(0.0) + (-z.abs()).exp().ln_1p() - t * z))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        y_hat.zip_map(y, |z, t| sigmoid(z) - t)
+    }
+
+    fn hessian(&self, _y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        Some(y_hat.map(|z| sigmoid(z) * (1.0 - sigmoid(z))))
+    }
+}
+
+/// Hinge loss on raw scores; targets `<= 0` are the negative class, the others positive.
+pub struct Hinge;
+
+impl Loss for Hinge {
+    fn name(&self) -> &str {
+        "hinge"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&y_hat.zip_map(y, |z, t| (1.0 - signed_target(t) * z).max(0.0)))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        y_hat.zip_map(y, |z, t| {
+            if signed_target(t) * z < 1.0 {
+                -signed_target(t)
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
+/// Modified Huber loss on raw scores: the squared hinge `max(0, 1 - z)^2` on the signed
+/// margin `z`, continued linearly as `-4 z` below `z = -1`. Targets as for `Hinge`.
+pub struct ModifiedHuber;
+
+impl Loss for ModifiedHuber {
+    fn name(&self) -> &str {
+        "modified_huber"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&y_hat.zip_map(y, |z, t| {
+            let margin = signed_target(t) * z;
+            if margin >= -1.0 {
+                (1.0 - margin).max(0.0).powi(2)
+            } else {
+                -4.0 * margin
+            }
+        }))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        y_hat.zip_map(y, |z, t| {
+            let margin = signed_target(t) * z;
+            if margin >= -1.0 {
+                -2.0 * signed_target(t) * (1.0 - margin).max(0.0)
+            } else {
+                -4.0 * signed_target(t)
+            }
+        })
+    }
+}
+
+/// Multiclass cross-entropy on raw scores, with one-hot targets and one column per class.
+pub struct CrossEntropy;
+
+impl Loss for CrossEntropy {
+    fn name(&self) -> &str {
+        "cross_entropy"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&softmax(y_hat).zip_map(y, |p, t| -t * p.max(f32::MIN_POSITIVE).ln()))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        softmax(y_hat) - y
+    }
+
+    fn hessian(&self, _y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        Some(softmax(y_hat).map(|p| p * (1.0 - p)))
+    }
+}
+
+pub(crate) fn signed_target(t: f32) -> f32 {
+    if t <= 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
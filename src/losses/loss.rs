@@ -0,0 +1,38 @@
+use nalgebra::DMatrix;
+
+/// Loss between targets `y` and predictions `y_hat`, both with one row per sample.
+///
+/// `per_sample` is the column of the losses of each sample, summed over its output
+/// columns, and `value` their (weighted) mean. `gradient` and `hessian` are taken per
+/// sample with respect to each prediction (not averaged) and have the shape of `y_hat`;
+/// `hessian` is the diagonal of the second derivative and `None` where it is not
+/// defined. Implement this trait to train models on a custom loss.
+pub trait Loss {
+    fn name(&self) -> &str;
+
+    /// Whether this is the squared error, which models can minimize in closed form.
+    fn is_squared_error(&self) -> bool {
+        false
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32>;
+
+    fn value(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> f32 {
+        self.per_sample(y, y_hat).mean()
+    }
+
+    fn weighted_value(
+        &self,
+        y: &DMatrix<f32>,
+        y_hat: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> f32 {
+        self.per_sample(y, y_hat).dot(sample_weight) / sample_weight.sum()
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32>;
+
+    fn hessian(&self, _y: &DMatrix<f32>, _y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        None
+    }
+}
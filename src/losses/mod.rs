@@ -0,0 +1,3 @@
+pub mod classification;
+pub mod loss;
+pub mod regression;
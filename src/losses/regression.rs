@@ -0,0 +1,243 @@
+use nalgebra::DMatrix;
+
+use crate::losses::loss::Loss;
+use crate::utils::types::TypeRegression;
+
+pub struct SquaredError;
+
+impl Loss for SquaredError {
+    fn name(&self) -> &str {
+        "squared_error"
+    }
+
+    fn is_squared_error(&self) -> bool {
+        true
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&(y_hat - y).map(|r| r * r))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y_hat - y) * 2.0
+    }
+
+    fn hessian(&self, _y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        Some(DMatrix::from_element(y_hat.nrows(), y_hat.ncols(), 2.0))
+    }
+}
+
+pub struct AbsoluteError;
+
+impl Loss for AbsoluteError {
+    fn name(&self) -> &str {
+        "absolute_error"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&(y_hat - y).abs())
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y_hat - y).map(sign)
+    }
+}
+
+pub struct Huber {
+    pub delta: f32,
+}
+
+impl Loss for Huber {
+    fn name(&self) -> &str {
+        "huber"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&(y_hat - y).map(|r| {
+            if r.abs() <= self.delta {
+                0.5 * r * r
+            } else {
+                self.delta * (r.abs() - 0.5 * self.delta)
+            }
+        }))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y_hat - y).map(|r| r.clamp(-self.delta, self.delta))
+    }
+
+    fn hessian(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        Some((y_hat - y).map(|r| if r.abs() <= self.delta { 1.0 } else { 0.0 }))
+    }
+}
+
+pub struct LogCosh;
+
+impl Loss for LogCosh {
+    fn name(&self) -> &str {
+        "log_cosh"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        // log(cosh(r)) = |r| + log(1 + exp(-2|r|)) - log(2), without overflowing cosh.
+        row_sums(&(y_hat - y).map(|r| r.abs() + (-2.0 * r.abs()).exp().ln_1p() - 2f32.ln()))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y_hat - y).map(|r| r.tanh())
+    }
+
+    fn hessian(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        Some((y_hat - y).map(|r| 1.0 - r.tanh().powi(2)))
+    }
+}
+
+/// Pinball loss, whose minimiser is the `quantile`-th conditional quantile.
+pub struct Quantile {
+    pub quantile: f32,
+}
+
+impl Loss for Quantile {
+    fn name(&self) -> &str {
+        "quantile"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&(y - y_hat).map(|u| (self.quantile * u).max((self.quantile - 1.0) * u)))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y - y_hat).map(|u| {
+            if u > 0.0 {
+                -self.quantile
+            } else if u < 0.0 {
+                1.0 - self.quantile
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
+pub struct EpsilonInsensitive {
+    pub epsilon: f32,
+}
+
+impl Loss for EpsilonInsensitive {
+    fn name(&self) -> &str {
+        "epsilon_insensitive"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        row_sums(&(y_hat - y).map(|r| (r.abs() - self.epsilon).max(0.0)))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y_hat - y).map(|r| if r.abs() > self.epsilon { sign(r) } else { 0.0 })
+    }
+}
+
+/// Tukey's biweight loss, which ignores residuals larger than `c`.
+pub struct Tukey {
+    pub c: f32,
+}
+
+impl Loss for Tukey {
+    fn name(&self) -> &str {
+        "tukey"
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        let c2 = self.c * self.c;
+        row_sums(&(y_hat - y).map(|r| {
+            if r.abs() <= self.c {
+                c2 / 6.0 * (1.0 - (1.0 - r * r / c2).powi(3))
+            } else {
+                c2 / 6.0
+            }
+        }))
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        (y_hat - y).map(|r| {
+            if r.abs() <= self.c {
+                r * (1.0 - (r / self.c).powi(2)).powi(2)
+            } else {
+                0.0
+            }
+        })
+    }
+
+    fn hessian(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        Some((y_hat - y).map(|r| {
+            let u = (r / self.c).powi(2);
+            if u <= 1.0 {
+                (1.0 - u) * (1.0 - 5.0 * u)
+            } else {
+                0.0
+            }
+        }))
+    }
+}
+
+impl TypeRegression {
+    fn as_loss(&self) -> Box<dyn Loss> {
+        match *self {
+            TypeRegression::MSE => Box::new(SquaredError),
+            TypeRegression::MAE => Box::new(AbsoluteError),
+            TypeRegression::HUBER(delta) => Box::new(Huber { delta }),
+            TypeRegression::LOGCOSH => Box::new(LogCosh),
+            TypeRegression::QUANTILE(quantile) => Box::new(Quantile { quantile }),
+            TypeRegression::EPSILON_INSENSITIVE(epsilon) => {
+                Box::new(EpsilonInsensitive { epsilon })
+            }
+            TypeRegression::TUKEY(c) => Box::new(Tukey { c }),
+        }
+    }
+}
+
+/// The enum stays as a shorthand for the built-in regression losses.
+impl Loss for TypeRegression {
+    fn name(&self) -> &str {
+        match self {
+            TypeRegression::MSE => "squared_error",
+            TypeRegression::MAE => "absolute_error",
+            TypeRegression::HUBER(_) => "huber",
+            TypeRegression::LOGCOSH => "log_cosh",
+            TypeRegression::QUANTILE(_) => "quantile",
+            TypeRegression::EPSILON_INSENSITIVE(_) => "epsilon_insensitive",
+            TypeRegression::TUKEY(_) => "tukey",
+        }
+    }
+
+    fn is_squared_error(&self) -> bool {
+        matches!(self, TypeRegression::MSE)
+    }
+
+    fn per_sample(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        self.as_loss().per_sample(y, y_hat)
+    }
+
+    fn gradient(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> DMatrix<f32> {
+        self.as_loss().gradient(y, y_hat)
+    }
+
+    fn hessian(&self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Option<DMatrix<f32>> {
+        self.as_loss().hessian(y, y_hat)
+    }
+}
+
+/// Column of the losses of each sample, summed over its output columns.
+pub(crate) fn row_sums(losses: &DMatrix<f32>) -> DMatrix<f32> {
+    DMatrix::from_fn(losses.nrows(), 1, |i, _| losses.row(i).sum())
+}
+
+pub(crate) fn sign(value: f32) -> f32 {
+    if value > 0.0 {
+        1.0
+    } else if value < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
@@ -1,18 +1,38 @@
 use nalgebra::DMatrix;
+use rust_regressions::classifications::linear_discriminant_analysis::LinearDiscriminantAnalysis;
+use rust_regressions::classifications::quadratic_discriminant_analysis::QuadraticDiscriminantAnalysis;
 use rust_regressions::clusters::knn::KNN;
+use rust_regressions::decomposition::pca::PCA;
+use rust_regressions::ensembles::gradient_boosting_classifier::GradientBoostingClassifier;
+use rust_regressions::ensembles::random_forest_classifier::RandomForestClassifier;
+use rust_regressions::gaussian_process::gaussian_process_regressor::GaussianProcessRegressor;
+use rust_regressions::gaussian_process::kernels::GPKernel;
+use rust_regressions::neural_network::mlp_regressor::MLPRegressor;
+use rust_regressions::optim::adam::Adam;
+use rust_regressions::optim::schedules::CosineAnnealing;
+use rust_regressions::optim::sgd::SGD;
+use rust_regressions::optim::training::FitConfig;
+use rust_regressions::regressions::kernel_ridge::KernelRidge;
 use rust_regressions::regressions::linear_regression::LinearRegression;
 use rust_regressions::regressions::polynomial_regression::PolynomialRegression;
 use rust_regressions::regressions::rbf_regression::RBFRegression;
 use rust_regressions::regressions::simple_linear_regression::SimpleLinearRegression;
-use rust_regressions::utils::io::{line_and_scatter_plot, parse_csv};
+use rust_regressions::trees::decision_tree_classifier::DecisionTreeClassifier;
+use rust_regressions::utils::io::{
+    line_and_scatter_plot, parse_csv, scatter_plot, split_target, CsvChunks,
+};
+use rust_regressions::utils::metrics::RegressionMetrics;
+use rust_regressions::utils::stats::mse;
 
-use rust_regressions::utils::types::{TypeFactoration, TypeRegression};
+use rust_regressions::utils::types::{
+    Activation, Criterion, TypeFactoration, TypeKernel, TypeRegression,
+};
 use rust_regressions::utils::utils::{accuracy, train_test_split};
 
 use std::env;
 use std::{fs::File, io::BufReader};
 
-static MSG: &str = "cargo run linear|simple|poly linear_regression|simple_linear_regression|polynomial_regression_data";
+static MSG: &str = "cargo run linear|simple|poly|rbf|knn|pca|lda|tree|gp|mlp|stream linear_regression|simple_linear_regression|polynomial_regression_data|knn_classification|buble";
 
 fn main() {
     env::set_var("RUST_BACKTRACE", "full");
@@ -50,7 +70,7 @@ fn main() {
         }
 
         let mut model = LinearRegression::new();
-        model.fit(&x, &y);
+        model.fit(&x, &y).unwrap();
 
         let y_predictions = model.predict(&x);
         let y_plot = vec![
@@ -77,19 +97,31 @@ fn main() {
         }
 
         let mut model_mse = PolynomialRegression::new(8, TypeRegression::MSE);
-        model_mse.fit(&x, &y, 1000, 0.7);
+        model_mse.fit(&x, &y, 1000, 0.3).unwrap();
         let y_hat_mse = model_mse.predict(&x);
 
         let mut model_mae = PolynomialRegression::new(8, TypeRegression::MAE);
-        model_mae.fit(&x, &y, 1000, 0.7);
+        model_mae.fit(&x, &y, 1000, 0.3).unwrap();
         let y_hat_mae = model_mae.predict(&x);
 
-        let mut model_huber = PolynomialRegression::new(8, TypeRegression::HUBER);
-        model_huber.fit(&x, &y, 3000, 0.7);
+        let mut model_huber = PolynomialRegression::new(8, TypeRegression::HUBER(1.0));
+        model_huber
+            .fit_with_config(
+                &x,
+                &y,
+                &mut SGD::new(0.4),
+                &mut FitConfig::new(1000).with_schedule(CosineAnnealing::new(1000, 0.01)),
+            )
+            .unwrap();
         let y_hat_huber = model_huber.predict(&x);
 
+        let mut model_closed_form =
+            PolynomialRegression::new(8, TypeRegression::MSE).with_feature_scaling();
+        model_closed_form.fit_closed_form(&x, &y).unwrap();
+        let y_hat_closed_form = model_closed_form.predict(&x);
+
         let mut model_rbf = RBFRegression::new(4.0, 22, 8, None);
-        model_rbf.fit(&x, &y);
+        model_rbf.fit(&x, &y).unwrap();
 
         let y_hat_rbf = model_rbf.predict(&x);
 
@@ -98,13 +130,14 @@ fn main() {
             y_hat_mse.data.as_vec().to_vec(),
             y_hat_mae.data.as_vec().to_vec(),
             y_hat_huber.data.as_vec().to_vec(),
+            y_hat_closed_form.data.as_vec().to_vec(),
             y_hat_rbf.data.as_vec().to_vec(),
         ];
 
         line_and_scatter_plot(
             (0..x.shape().0).map(|v| v as f32).collect(),
             y_plot,
-            vec!["original", "MSE", "MAE", "HUBER", "RBF"],
+            vec!["original", "MSE", "MAE", "HUBER", "CLOSED FORM", "RBF"],
         );
     }
     if type_regression == "rbf" {
@@ -120,27 +153,37 @@ fn main() {
         }
 
         let mut model_rbf_lu = RBFRegression::new(4.0, 24, 12, Some(TypeFactoration::LU));
-        model_rbf_lu.fit(&x, &y);
+        model_rbf_lu.fit(&x, &y).unwrap();
         let mut model_rbf_qr = RBFRegression::new(4.0, 24, 12, Some(TypeFactoration::QR));
-        model_rbf_qr.fit(&x, &y);
+        model_rbf_qr.fit(&x, &y).unwrap();
         let mut model_rbf_svd = RBFRegression::new(4.0, 24, 12, Some(TypeFactoration::SVD));
-        model_rbf_svd.fit(&x, &y);
+        model_rbf_svd.fit(&x, &y).unwrap();
 
         let y_hat_rbf_lu = model_rbf_lu.predict(&x);
         let y_hat_rbf_qr = model_rbf_qr.predict(&x);
         let y_hat_rbf_svd = model_rbf_svd.predict(&x);
 
+        let mut model_kernel_ridge = KernelRidge::new(0.1, TypeKernel::RBF(50.0));
+        model_kernel_ridge.fit(&x, &y).unwrap();
+        let y_hat_kernel_ridge = model_kernel_ridge.predict(&x);
+        println!(
+            "MSE RBF (SVD): {}, kernel ridge: {}",
+            mse(y.clone(), y_hat_rbf_svd.clone()),
+            mse(y.clone(), y_hat_kernel_ridge.clone())
+        );
+
         let y_plot = vec![
             y.data.as_vec().to_vec(),
             y_hat_rbf_lu.data.as_vec().to_vec(),
             y_hat_rbf_qr.data.as_vec().to_vec(),
             y_hat_rbf_svd.data.as_vec().to_vec(),
+            y_hat_kernel_ridge.data.as_vec().to_vec(),
         ];
 
         line_and_scatter_plot(
             (0..x.shape().0).map(|v| v as f32).collect(),
             y_plot,
-            vec!["original", "LU", "QR", "SVD"],
+            vec!["original", "LU", "QR", "SVD", "Kernel ridge"],
         );
     }
     if type_regression == "knn" {
@@ -175,4 +218,232 @@ fn main() {
             )
         );
     }
+    if type_regression == "gp" {
+        let file: File = File::open(format!("datasets/{}.csv", dataset_name_file)).unwrap();
+        let tuple_result: (usize, usize, Vec<f32>) = parse_csv(BufReader::new(file)).unwrap();
+        let dense_matrix = DMatrix::from_row_slice(tuple_result.0, tuple_result.1, &tuple_result.2);
+
+        // Exact inference is cubic in the number of samples: keep one sample in ten.
+        let rows: Vec<usize> = (0..dense_matrix.nrows()).step_by(10).collect();
+        let x = dense_matrix
+            .select_rows(rows.iter())
+            .columns(0, 1)
+            .into_owned();
+        let y = dense_matrix
+            .select_rows(rows.iter())
+            .columns(1, 1)
+            .into_owned();
+
+        let kernel = GPKernel::CONSTANT(1.0) * GPKernel::RBF(0.1) + GPKernel::WHITE(0.1);
+        let mut model = GaussianProcessRegressor::new(kernel).with_normalize_y();
+        model.fit(&x, &y).unwrap();
+        println!("Kernel: {:?}", model.kernel);
+        println!(
+            "Log marginal likelihood: {}",
+            model.log_marginal_likelihood_value
+        );
+
+        let (mean, std) = model.predict_with_std(&x);
+        line_and_scatter_plot(
+            x.iter().cloned().collect(),
+            vec![
+                y.iter().cloned().collect(),
+                mean.iter().cloned().collect(),
+                mean.zip_map(&std, |m, s| m - 2.0 * s)
+                    .iter()
+                    .cloned()
+                    .collect(),
+                mean.zip_map(&std, |m, s| m + 2.0 * s)
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ],
+            vec!["original", "mean", "mean - 2 std", "mean + 2 std"],
+        );
+    }
+    if type_regression == "mlp" {
+        let file: File = File::open(format!("datasets/{}.csv", dataset_name_file)).unwrap();
+        let tuple_result: (usize, usize, Vec<f32>) = parse_csv(BufReader::new(file)).unwrap();
+        let mut x = DMatrix::zeros(tuple_result.0, tuple_result.1 - 1);
+        let mut y = DMatrix::zeros(tuple_result.0, 1);
+        for i in 0..tuple_result.0 {
+            for j in 0..(tuple_result.1 - 1) {
+                x[(i, j)] = tuple_result.2[i * (tuple_result.1) + j];
+            }
+            y[(i, 0)] = tuple_result.2[tuple_result.1 * (i + 1) - 1]
+        }
+
+        let mut model_poly =
+            PolynomialRegression::new(8, TypeRegression::MSE).with_feature_scaling();
+        model_poly.fit_closed_form(&x, &y).unwrap();
+        let y_hat_poly = model_poly.predict(&x);
+
+        let mut model_rbf = RBFRegression::new(4.0, 22, 8, None);
+        model_rbf.fit(&x, &y).unwrap();
+        let y_hat_rbf = model_rbf.predict(&x);
+
+        let mut model_mlp = MLPRegressor::new(vec![32, 32])
+            .with_activation(Activation::TANH)
+            .with_seed(42);
+        model_mlp
+            .fit_with_config(
+                &x,
+                &y,
+                &mut Adam::new(0.01),
+                &mut FitConfig::mini_batch(200, 32).with_schedule(CosineAnnealing::new(200, 1e-4)),
+            )
+            .unwrap();
+        let y_hat_mlp = model_mlp.predict(&x);
+
+        println!(
+            "MSE polynomial: {}, RBF: {}, MLP: {}",
+            mse(y.clone(), y_hat_poly.clone()),
+            mse(y.clone(), y_hat_rbf.clone()),
+            mse(y.clone(), y_hat_mlp.clone())
+        );
+
+        line_and_scatter_plot(
+            x.iter().cloned().collect(),
+            vec![
+                y.data.as_vec().to_vec(),
+                y_hat_poly.data.as_vec().to_vec(),
+                y_hat_rbf.data.as_vec().to_vec(),
+                y_hat_mlp.data.as_vec().to_vec(),
+            ],
+            vec!["original", "polynomial", "RBF", "MLP"],
+        );
+    }
+    if type_regression == "stream" {
+        let file: File = File::open(format!("datasets/{}.csv", dataset_name_file)).unwrap();
+        let chunks = CsvChunks::new(BufReader::new(file), 100).unwrap();
+
+        // Prequential evaluation: every chunk is predicted before the model learns it.
+        let mut model = LinearRegression::new();
+        let mut metrics = RegressionMetrics::new();
+        for chunk in chunks {
+            let (x, y) = split_target(&chunk.unwrap());
+            if model.coefficients.is_some() {
+                metrics.update(&y, &model.predict(&x)).unwrap();
+            }
+            model.partial_fit(&x, &y).unwrap();
+        }
+        println!(
+            "Samples evaluated: {}, MSE: {:?}, MAE: {:?}, R2: {:?}",
+            metrics.n_samples,
+            metrics.mse(),
+            metrics.mae(),
+            metrics.r2()
+        );
+        println!(
+            "Coefficients: {:?}, bias: {:?}",
+            model.coefficients.unwrap().as_slice(),
+            model.bias.unwrap()
+        );
+    }
+    if type_regression == "pca" {
+        let file: File = File::open(format!("datasets/{}.csv", dataset_name_file)).unwrap();
+        let tuple_result: (usize, usize, Vec<f32>) = parse_csv(BufReader::new(file)).unwrap();
+
+        let mut x = DMatrix::zeros(tuple_result.0, tuple_result.1 - 1);
+        let mut y = DMatrix::zeros(tuple_result.0, 1);
+        for i in 0..tuple_result.0 {
+            for j in 0..(tuple_result.1 - 1) {
+                x[(i, j)] = tuple_result.2[i * (tuple_result.1) + j];
+            }
+            y[(i, 0)] = tuple_result.2[tuple_result.1 * (i + 1) - 1]
+        }
+
+        let mut model = PCA::new(2);
+        let projected = model.fit_transform(&x).unwrap();
+        println!(
+            "Explained variance ratio: {:?}",
+            model.explained_variance_ratio
+        );
+
+        scatter_plot(
+            projected.column(0).iter().cloned().collect(),
+            projected.column(1).iter().cloned().collect(),
+            y.iter().map(|v| *v as usize).collect(),
+        );
+    }
+    if type_regression == "lda" {
+        let file: File = File::open(format!("datasets/{}.csv", dataset_name_file)).unwrap();
+        let tuple_result: (usize, usize, Vec<f32>) = parse_csv(BufReader::new(file)).unwrap();
+
+        // Some datasets (e.g. buble.csv) start with the row index, which is not a feature.
+        let first_column =
+            if (0..tuple_result.0).all(|i| tuple_result.2[i * tuple_result.1] == i as f32) {
+                1
+            } else {
+                0
+            };
+        let mut x = DMatrix::zeros(tuple_result.0, tuple_result.1 - 1 - first_column);
+        let mut y = DMatrix::zeros(tuple_result.0, 1);
+        for i in 0..tuple_result.0 {
+            for j in first_column..(tuple_result.1 - 1) {
+                x[(i, j - first_column)] = tuple_result.2[i * (tuple_result.1) + j];
+            }
+            y[(i, 0)] = tuple_result.2[tuple_result.1 * (i + 1) - 1]
+        }
+
+        let (x_train, x_test, y_train, y_test) = train_test_split(x, y, 0.3, true);
+        let y_target: Vec<f32> = y_test.iter().cloned().collect();
+
+        let mut lda = LinearDiscriminantAnalysis::new().with_shrinkage(0.1);
+        lda.fit(&x_train, &y_train).unwrap();
+        println!(
+            "LDA accuracy: {}",
+            accuracy(lda.predict(&x_test), y_target.clone())
+        );
+
+        let mut qda = QuadraticDiscriminantAnalysis::new().with_regularization(0.01);
+        qda.fit(&x_train, &y_train).unwrap();
+        println!("QDA accuracy: {}", accuracy(qda.predict(&x_test), y_target));
+    }
+    if type_regression == "tree" {
+        let file: File = File::open(format!("datasets/{}.csv", dataset_name_file)).unwrap();
+        let tuple_result: (usize, usize, Vec<f32>) = parse_csv(BufReader::new(file)).unwrap();
+
+        let mut x = DMatrix::zeros(tuple_result.0, tuple_result.1 - 1);
+        let mut y = DMatrix::zeros(tuple_result.0, 1);
+        for i in 0..tuple_result.0 {
+            for j in 0..(tuple_result.1 - 1) {
+                x[(i, j)] = tuple_result.2[i * (tuple_result.1) + j];
+            }
+            y[(i, 0)] = tuple_result.2[tuple_result.1 * (i + 1) - 1]
+        }
+
+        let (x_train, x_test, y_train, y_test) = train_test_split(x, y, 0.3, true);
+
+        let mut model = DecisionTreeClassifier::new(Criterion::GINI)
+            .with_max_depth(3)
+            .with_ccp_alpha(0.005);
+        model.fit(&x_train, &y_train).unwrap();
+        println!("{}", model.export_text(None));
+        println!("Feature importances: {:?}", model.feature_importances());
+        println!(
+            "Accuracy: {}",
+            accuracy(model.predict(&x_test), y_test.iter().cloned().collect())
+        );
+
+        let mut forest = RandomForestClassifier::new(100, Criterion::GINI)
+            .with_oob_score()
+            .with_n_jobs(4);
+        forest.fit(&x_train, &y_train).unwrap();
+        println!("Random forest OOB score: {}", forest.oob_score.unwrap());
+        println!(
+            "Random forest accuracy: {}",
+            accuracy(forest.predict(&x_test), y_test.iter().cloned().collect())
+        );
+
+        let mut boosting = GradientBoostingClassifier::new(200)
+            .with_histogram(32)
+            .with_early_stopping(0.2, 10);
+        boosting.fit(&x_train, &y_train).unwrap();
+        println!(
+            "Gradient boosting stages: {}, accuracy: {}",
+            boosting.n_estimators(),
+            accuracy(boosting.predict(&x_test), y_test.iter().cloned().collect())
+        );
+    }
 }
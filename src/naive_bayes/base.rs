@@ -0,0 +1,101 @@
+use nalgebra::DMatrix;
+
+use crate::losses::classification::{log_softmax, softmax};
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Shared prediction logic of the naive Bayes classifiers, which only differ in how
+/// they model `log p(x | class)`.
+pub trait NaiveBayes {
+    fn classes(&self) -> &[f32];
+
+    /// `log p(x | class) + log p(class)` for every sample (rows) and class (columns).
+    fn joint_log_likelihood(&self, x: &DMatrix<f32>) -> DMatrix<f32>;
+
+    fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        argmax_classes(&self.joint_log_likelihood(x), self.classes())
+    }
+
+    fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        softmax(&self.joint_log_likelihood(x))
+    }
+
+    fn predict_log_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        log_softmax(&self.joint_log_likelihood(x))
+    }
+}
+
+/// Sets the classes on the first call of `partial_fit` (from `classes`, or from the
+/// labels of the first batch) and returns the class index of every sample of `y`.
+pub(crate) fn class_indices(
+    known_classes: &mut Vec<f32>,
+    y: &DMatrix<f32>,
+    classes: Option<&[f32]>,
+) -> Result<Vec<usize>, String> {
+    if known_classes.is_empty() {
+        *known_classes = match classes {
+            Some(classes) => {
+                let mut classes = classes.to_vec();
+                classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                classes.dedup();
+                classes
+            }
+            None => unique_classes(y),
+        };
+    }
+
+    return y
+        .column(0)
+        .iter()
+        .map(|label| {
+            known_classes.iter().position(|c| c == label).ok_or(format!(
+                "class {} was not seen in the first call of partial_fit; pass every class there",
+                label
+            ))
+        })
+        .collect();
+}
+
+/// Log of the given priors, or of the class frequencies counted so far.
+pub(crate) fn log_priors(
+    priors: &Option<Vec<f32>>,
+    class_count: &[f32],
+) -> Result<Vec<f32>, String> {
+    match priors {
+        Some(priors) => {
+            if priors.len() != class_count.len() {
+                return Err(format!(
+                    "{} priors were given for {} classes",
+                    priors.len(),
+                    class_count.len()
+                ));
+            }
+            if priors.iter().any(|p| *p <= 0.0) || (priors.iter().sum::<f32>() - 1.0).abs() > 1e-4 {
+                return Err("priors should be positive and sum to 1".to_string());
+            }
+            Ok(priors.iter().map(|p| p.ln()).collect())
+        }
+        None => {
+            let total: f32 = class_count.iter().sum();
+            Ok(class_count.iter().map(|c| (c / total).ln()).collect())
+        }
+    }
+}
+
+pub(crate) fn check_shapes(x: &DMatrix<f32>, y: &DMatrix<f32>, ncols: usize) -> Result<(), String> {
+    if x.nrows() != y.nrows() {
+        return Err(format!(
+            "x and y should have the same number of samples. |x|: {}, |y|: {}",
+            x.nrows(),
+            y.nrows()
+        ));
+    }
+    if ncols > 0 && x.ncols() != ncols {
+        return Err(format!(
+            "expected {} features as in previous calls, got {}",
+            ncols,
+            x.ncols()
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use nalgebra::DMatrix;
+
+use crate::naive_bayes::base::{check_shapes, class_indices, log_priors, NaiveBayes};
+use crate::online::incremental::IncrementalModel;
+
+/// Naive Bayes for binary features, with additive (Laplace) smoothing `alpha`. Features
+/// are binarized as `x > binarize` unless `binarize` is `None`, in which case they must
+/// already be 0 or 1.
+///
+/// Unlike the multinomial model, the absence of a feature counts as evidence.
+pub struct BernoulliNaiveBayes {
+    pub alpha: f32,
+    pub binarize: Option<f32>,
+    pub priors: Option<Vec<f32>>,
+    pub classes: Vec<f32>,
+    pub class_count: Vec<f32>,
+    pub feature_count: DMatrix<f32>,
+    pub feature_log_prob: DMatrix<f32>,
+    log_priors: Vec<f32>,
+}
+
+impl BernoulliNaiveBayes {
+    pub fn new() -> BernoulliNaiveBayes {
+        BernoulliNaiveBayes {
+            alpha: 1.0,
+            binarize: Some(0.0),
+            priors: None,
+            classes: Vec::new(),
+            class_count: Vec::new(),
+            feature_count: DMatrix::zeros(0, 0),
+            feature_log_prob: DMatrix::zeros(0, 0),
+            log_priors: Vec::new(),
+        }
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> BernoulliNaiveBayes {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_binarize(mut self, binarize: Option<f32>) -> BernoulliNaiveBayes {
+        self.binarize = binarize;
+        self
+    }
+
+    pub fn with_priors(mut self, priors: Vec<f32>) -> BernoulliNaiveBayes {
+        self.priors = Some(priors);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.classes.clear();
+        self.partial_fit(x, y, None)
+    }
+
+    /// Adds the counts of a new batch. `classes` lists every class of the stream and is
+    /// only read on the first call.
+    pub fn partial_fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<(), String> {
+        if self.alpha < 0.0 {
+            return Err(format!("alpha should be non-negative, got {}", self.alpha));
+        }
+        let x = self.binarized(x)?;
+        let first_call = self.classes.is_empty();
+        check_shapes(
+            &x,
+            y,
+            if first_call {
+                0
+            } else {
+                self.feature_count.ncols()
+            },
+        )?;
+        let indices = class_indices(&mut self.classes, y, classes)?;
+        if first_call {
+            self.class_count = vec![0.0; self.classes.len()];
+            self.feature_count = DMatrix::zeros(self.classes.len(), x.ncols());
+        }
+
+        for (i, k) in indices.iter().enumerate() {
+            self.class_count[*k] += 1.0;
+            let mut counts = self.feature_count.row_mut(*k);
+            counts += x.row(i);
+        }
+
+        self.feature_log_prob = DMatrix::from_fn(self.classes.len(), x.ncols(), |k, j| {
+            ((self.feature_count[(k, j)] + self.alpha) / (self.class_count[k] + 2.0 * self.alpha))
+                .ln()
+        });
+        self.log_priors = log_priors(&self.priors, &self.class_count)?;
+        Ok(())
+    }
+
+    fn binarized(&self, x: &DMatrix<f32>) -> Result<DMatrix<f32>, String> {
+        match self.binarize {
+            Some(threshold) => Ok(x.map(|v| if v > threshold { 1.0 } else { 0.0 })),
+            None if x.iter().all(|v| *v == 0.0 || *v == 1.0) => Ok(x.clone()),
+            None => Err("features should be 0 or 1 when binarize is None".to_string()),
+        }
+    }
+}
+
+impl Default for BernoulliNaiveBayes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NaiveBayes for BernoulliNaiveBayes {
+    fn classes(&self) -> &[f32] {
+        &self.classes
+    }
+
+    /// Panics when `binarize` is `None` and `x` is not binary.
+    fn joint_log_likelihood(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let x = self.binarized(x).unwrap();
+        let log_absent = self.feature_log_prob.map(|p| (1.0 - p.exp()).ln());
+
+        let mut scores = &x * (&self.feature_log_prob - &log_absent).transpose();
+        for mut row in scores.row_iter_mut() {
+            for k in 0..self.classes.len() {
+                row[k] += log_absent.row(k).sum() + self.log_priors[k];
+            }
+        }
+
+        return scores;
+    }
+}
+
+impl IncrementalModel for BernoulliNaiveBayes {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        BernoulliNaiveBayes::partial_fit(self, x, y, None)
+    }
+}
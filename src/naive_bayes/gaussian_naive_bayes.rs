@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+
+use nalgebra::DMatrix;
+
+use crate::naive_bayes::base::{check_shapes, class_indices, log_priors, NaiveBayes};
+use crate::online::incremental::IncrementalModel;
+
+/// Naive Bayes with a normal distribution per class and feature.
+///
+/// `var_smoothing` times the largest feature variance is added to every variance, which
+/// keeps constant features from producing infinite likelihoods.
+pub struct GaussianNaiveBayes {
+    pub var_smoothing: f32,
+    pub priors: Option<Vec<f32>>,
+    pub classes: Vec<f32>,
+    pub class_count: Vec<f32>,
+    pub means: DMatrix<f32>,
+    pub variances: DMatrix<f32>,
+    pub epsilon: f32,
+    log_priors: Vec<f32>,
+}
+
+impl GaussianNaiveBayes {
+    pub fn new() -> GaussianNaiveBayes {
+        GaussianNaiveBayes {
+            var_smoothing: 1e-9,
+            priors: None,
+            classes: Vec::new(),
+            class_count: Vec::new(),
+            means: DMatrix::zeros(0, 0),
+            variances: DMatrix::zeros(0, 0),
+            epsilon: 0.0,
+            log_priors: Vec::new(),
+        }
+    }
+
+    pub fn with_var_smoothing(mut self, var_smoothing: f32) -> GaussianNaiveBayes {
+        self.var_smoothing = var_smoothing;
+        self
+    }
+
+    pub fn with_priors(mut self, priors: Vec<f32>) -> GaussianNaiveBayes {
+        self.priors = Some(priors);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.classes.clear();
+        self.partial_fit(x, y, None)
+    }
+
+    /// Updates the per-class means and variances with a new batch. `classes` lists every
+    /// class of the stream and is only read on the first call.
+    pub fn partial_fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<(), String> {
+        let first_call = self.classes.is_empty();
+        check_shapes(x, y, if first_call { 0 } else { self.means.ncols() })?;
+        let indices = class_indices(&mut self.classes, y, classes)?;
+
+        let (n_classes, ncols) = (self.classes.len(), x.ncols());
+        if first_call {
+            self.class_count = vec![0.0; n_classes];
+            self.means = DMatrix::zeros(n_classes, ncols);
+            // Stored without smoothing while accumulating.
+            self.variances = DMatrix::zeros(n_classes, ncols);
+        } else {
+            self.variances.add_scalar_mut(-self.epsilon);
+        }
+
+        for k in 0..n_classes {
+            let rows: Vec<usize> = (0..x.nrows()).filter(|i| indices[*i] == k).collect();
+            if rows.is_empty() {
+                continue;
+            }
+            let n_new = rows.len() as f32;
+            let n_old = self.class_count[k];
+            let n_total = n_old + n_new;
+
+            for j in 0..ncols {
+                let batch_mean = rows.iter().map(|i| x[(*i, j)]).sum::<f32>() / n_new;
+                let batch_ssd = rows
+                    .iter()
+                    .map(|i| (x[(*i, j)] - batch_mean).powf(2.0))
+                    .sum::<f32>();
+
+                // Chan et al. merge of the running and batch sums of squared deviations.
+                let delta = batch_mean - self.means[(k, j)];
+                let ssd = self.variances[(k, j)] * n_old
+                    + batch_ssd
+                    + delta * delta * n_old * n_new / n_total;
+                self.means[(k, j)] += delta * n_new / n_total;
+                self.variances[(k, j)] = ssd / n_total;
+            }
+            self.class_count[k] = n_total;
+        }
+
+        self.epsilon = self.var_smoothing * self.largest_variance();
+        self.variances.add_scalar_mut(self.epsilon);
+        self.log_priors = log_priors(&self.priors, &self.class_count)?;
+        Ok(())
+    }
+
+    /// Largest variance of a feature over every sample seen, from the law of total
+    /// variance.
+    fn largest_variance(&self) -> f32 {
+        let total: f32 = self.class_count.iter().sum();
+        return (0..self.means.ncols())
+            .map(|j| {
+                let mean = (0..self.classes.len())
+                    .map(|k| self.class_count[k] * self.means[(k, j)])
+                    .sum::<f32>()
+                    / total;
+                (0..self.classes.len())
+                    .map(|k| {
+                        self.class_count[k]
+                            * (self.variances[(k, j)] + (self.means[(k, j)] - mean).powf(2.0))
+                    })
+                    .sum::<f32>()
+                    / total
+            })
+            .fold(0.0, f32::max);
+    }
+}
+
+impl Default for GaussianNaiveBayes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NaiveBayes for GaussianNaiveBayes {
+    fn classes(&self) -> &[f32] {
+        &self.classes
+    }
+
+    fn joint_log_likelihood(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return DMatrix::from_fn(x.nrows(), self.classes.len(), |i, k| {
+            let log_likelihood: f32 = (0..x.ncols())
+                .map(|j| {
+                    let variance = self.variances[(k, j)];
+                    -0.5 * ((2.0 * PI * variance).ln()
+                        + (x[(i, j)] - self.means[(k, j)]).powf(2.0) / variance)
+                })
+                .sum();
+            log_likelihood + self.log_priors[k]
+        });
+    }
+}
+
+impl IncrementalModel for GaussianNaiveBayes {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        GaussianNaiveBayes::partial_fit(self, x, y, None)
+    }
+}
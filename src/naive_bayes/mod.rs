@@ -0,0 +1,4 @@
+pub mod base;
+pub mod bernoulli_naive_bayes;
+pub mod gaussian_naive_bayes;
+pub mod multinomial_naive_bayes;
@@ -0,0 +1,119 @@
+use nalgebra::DMatrix;
+
+use crate::naive_bayes::base::{check_shapes, class_indices, log_priors, NaiveBayes};
+use crate::online::incremental::IncrementalModel;
+
+/// Naive Bayes for count features (e.g. word counts), with additive (Laplace) smoothing
+/// `alpha` of the per-class feature frequencies.
+pub struct MultinomialNaiveBayes {
+    pub alpha: f32,
+    pub priors: Option<Vec<f32>>,
+    pub classes: Vec<f32>,
+    pub class_count: Vec<f32>,
+    pub feature_count: DMatrix<f32>,
+    pub feature_log_prob: DMatrix<f32>,
+    log_priors: Vec<f32>,
+}
+
+impl MultinomialNaiveBayes {
+    pub fn new() -> MultinomialNaiveBayes {
+        MultinomialNaiveBayes {
+            alpha: 1.0,
+            priors: None,
+            classes: Vec::new(),
+            class_count: Vec::new(),
+            feature_count: DMatrix::zeros(0, 0),
+            feature_log_prob: DMatrix::zeros(0, 0),
+            log_priors: Vec::new(),
+        }
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> MultinomialNaiveBayes {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_priors(mut self, priors: Vec<f32>) -> MultinomialNaiveBayes {
+        self.priors = Some(priors);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.classes.clear();
+        self.partial_fit(x, y, None)
+    }
+
+    /// Adds the counts of a new batch. `classes` lists every class of the stream and is
+    /// only read on the first call.
+    pub fn partial_fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<(), String> {
+        if x.iter().any(|v| *v < 0.0) {
+            return Err("multinomial naive Bayes needs non-negative counts".to_string());
+        }
+        if self.alpha < 0.0 {
+            return Err(format!("alpha should be non-negative, got {}", self.alpha));
+        }
+        let first_call = self.classes.is_empty();
+        check_shapes(
+            x,
+            y,
+            if first_call {
+                0
+            } else {
+                self.feature_count.ncols()
+            },
+        )?;
+        let indices = class_indices(&mut self.classes, y, classes)?;
+        if first_call {
+            self.class_count = vec![0.0; self.classes.len()];
+            self.feature_count = DMatrix::zeros(self.classes.len(), x.ncols());
+        }
+
+        for (i, k) in indices.iter().enumerate() {
+            self.class_count[*k] += 1.0;
+            let mut counts = self.feature_count.row_mut(*k);
+            counts += x.row(i);
+        }
+
+        let ncols = self.feature_count.ncols() as f32;
+        self.feature_log_prob = DMatrix::from_fn(self.classes.len(), x.ncols(), |k, j| {
+            let total = self.feature_count.row(k).sum() + self.alpha * ncols;
+            ((self.feature_count[(k, j)] + self.alpha) / total).ln()
+        });
+        self.log_priors = log_priors(&self.priors, &self.class_count)?;
+        Ok(())
+    }
+}
+
+impl Default for MultinomialNaiveBayes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NaiveBayes for MultinomialNaiveBayes {
+    fn classes(&self) -> &[f32] {
+        &self.classes
+    }
+
+    fn joint_log_likelihood(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut scores = x * self.feature_log_prob.transpose();
+        for mut row in scores.row_iter_mut() {
+            for (k, log_prior) in self.log_priors.iter().enumerate() {
+                row[k] += log_prior;
+            }
+        }
+
+        return scores;
+    }
+}
+
+impl IncrementalModel for MultinomialNaiveBayes {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        MultinomialNaiveBayes::partial_fit(self, x, y, None)
+    }
+}
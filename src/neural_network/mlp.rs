@@ -0,0 +1,280 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::losses::classification::sigmoid;
+use crate::losses::loss::Loss;
+use crate::utils::types::{Activation, Initialization};
+use crate::utils::utils::random_normal_matrix;
+
+/// Architecture and regularisation shared by `MLPRegressor` and `MLPClassifier`.
+///
+/// Defaults to ReLU hidden units with He initialisation, no dropout and `alpha = 1e-4`.
+#[derive(Clone, Debug)]
+pub struct MLPParams {
+    pub hidden_layer_sizes: Vec<usize>,
+    pub activation: Activation,
+    pub initialization: Initialization,
+    pub dropout: f32,
+    pub alpha: f32,
+    pub seed: Option<u64>,
+}
+
+impl MLPParams {
+    pub fn new(hidden_layer_sizes: Vec<usize>) -> MLPParams {
+        MLPParams {
+            hidden_layer_sizes,
+            activation: Activation::RELU,
+            initialization: Initialization::HE,
+            dropout: 0.0,
+            alpha: 1e-4,
+            seed: None,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.hidden_layer_sizes.contains(&0) {
+            return Err("every hidden layer should have at least one unit".to_string());
+        }
+        if !(0.0..1.0).contains(&self.dropout) {
+            return Err(format!("dropout should be in [0, 1), got {}", self.dropout));
+        }
+        if self.alpha < 0.0 {
+            return Err(format!("alpha should be non-negative, got {}", self.alpha));
+        }
+        Ok(())
+    }
+
+    /// Freshly initialised network from `n_inputs` features to `n_outputs` values.
+    pub(crate) fn build(&self, n_inputs: usize, n_outputs: usize) -> DenseNetwork {
+        let mut sizes = vec![n_inputs];
+        sizes.extend(self.hidden_layer_sizes.iter());
+        sizes.push(n_outputs);
+        let mut network =
+            DenseNetwork::new(&sizes, self.activation, self.initialization, self.seed);
+        network.dropout = self.dropout;
+        network.alpha = self.alpha;
+        return network;
+    }
+}
+
+/// Dense feed-forward network shared by `MLPRegressor` and `MLPClassifier`.
+///
+/// Layer `l` maps its input through `weights[l]` (inputs x outputs) and `biases[l]`
+/// (a row), followed by `activation` on every hidden layer; the output layer is linear
+/// and the loss is applied to its raw values. During training each hidden unit is
+/// dropped with probability `dropout` (inverted dropout, so nothing changes at
+/// prediction time) and `alpha / 2 * sum(W^2)` is added to the loss.
+#[derive(Clone, Debug)]
+pub struct DenseNetwork {
+    pub weights: Vec<DMatrix<f32>>,
+    pub biases: Vec<DMatrix<f32>>,
+    pub activation: Activation,
+    pub dropout: f32,
+    pub alpha: f32,
+}
+
+impl DenseNetwork {
+    /// Network with the layer widths `sizes`, inputs first and outputs last.
+    pub fn new(
+        sizes: &[usize],
+        activation: Activation,
+        initialization: Initialization,
+        seed: Option<u64>,
+    ) -> DenseNetwork {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let weights = sizes
+            .windows(2)
+            .map(|pair| initial_weights(pair[0], pair[1], initialization, &mut rng))
+            .collect();
+        let biases = sizes
+            .iter()
+            .skip(1)
+            .map(|n| DMatrix::zeros(1, *n))
+            .collect();
+
+        DenseNetwork {
+            weights,
+            biases,
+            activation,
+            dropout: 0.0,
+            alpha: 0.0,
+        }
+    }
+
+    pub fn n_inputs(&self) -> usize {
+        self.weights.first().map_or(0, |w| w.nrows())
+    }
+
+    pub fn n_outputs(&self) -> usize {
+        self.weights.last().map_or(0, |w| w.ncols())
+    }
+
+    /// Raw values of the output layer, one row per sample.
+    pub fn forward(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut a = x.clone();
+        for l in 0..self.weights.len() {
+            a = self.linear(l, &a);
+            if l + 1 < self.weights.len() {
+                a = activate(self.activation, &a);
+            }
+        }
+
+        return a;
+    }
+
+    /// Every weight matrix then its bias, layer after layer, flattened into a column.
+    pub fn parameters(&self) -> DMatrix<f32> {
+        let values: Vec<f32> = self
+            .weights
+            .iter()
+            .zip(self.biases.iter())
+            .flat_map(|(w, b)| w.iter().chain(b.iter()).cloned().collect::<Vec<f32>>())
+            .collect();
+        return DMatrix::from_vec(values.len(), 1, values);
+    }
+
+    pub fn set_parameters(&mut self, parameters: &DMatrix<f32>) {
+        let mut offset = 0;
+        for (w, b) in self.weights.iter_mut().zip(self.biases.iter_mut()) {
+            w.copy_from_slice(&parameters.as_slice()[offset..offset + w.len()]);
+            offset += w.len();
+            b.copy_from_slice(&parameters.as_slice()[offset..offset + b.len()]);
+            offset += b.len();
+        }
+    }
+
+    pub fn loss(
+        &self,
+        loss: &dyn Loss,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> f32 {
+        let y_hat = self.forward(x);
+        let regularization: f32 = self.weights.iter().map(|w| w.norm_squared()).sum();
+
+        0.5 * self.alpha * regularization
+            + match sample_weight {
+                Some(w) => loss.weighted_value(y, &y_hat, w),
+                None => loss.value(y, &y_hat),
+            }
+    }
+
+    /// Backpropagation of `loss` with a fresh dropout mask, flattened like `parameters`.
+    pub fn gradient(
+        &self,
+        loss: &dyn Loss,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> DMatrix<f32> {
+        let n_layers = self.weights.len();
+        let mut rng = rand::thread_rng();
+        let keep = 1.0 - self.dropout;
+
+        // Activations of every layer, the input included, and the dropout masks.
+        let mut activations = vec![x.clone()];
+        let mut masks = Vec::with_capacity(n_layers - 1);
+        for l in 0..n_layers - 1 {
+            let mut a = activate(self.activation, &self.linear(l, &activations[l]));
+            let mask = DMatrix::from_fn(a.nrows(), a.ncols(), |_, _| {
+                match self.dropout > 0.0 && rng.gen::<f32>() >= keep {
+                    true => 0.0,
+                    false => 1.0 / keep,
+                }
+            });
+            a.component_mul_assign(&mask);
+            activations.push(a);
+            masks.push(mask);
+        }
+        let y_hat = self.linear(n_layers - 1, &activations[n_layers - 1]);
+
+        let mut delta = loss.gradient(y, &y_hat);
+        let total = match sample_weight {
+            Some(w) => {
+                for (i, mut row) in delta.row_iter_mut().enumerate() {
+                    row *= w[(i, 0)];
+                }
+                w.sum()
+            }
+            None => x.nrows() as f32,
+        };
+        delta /= total;
+
+        let mut gradients = vec![(DMatrix::zeros(0, 0), DMatrix::zeros(0, 0)); n_layers];
+        for l in (0..n_layers).rev() {
+            let dw = activations[l].transpose() * &delta + &self.weights[l] * self.alpha;
+            let db = DMatrix::from_iterator(1, delta.ncols(), delta.row_sum().iter().cloned());
+            if l > 0 {
+                let a = &activations[l];
+                // `a` holds the dropped-out activations, `a / m` the original ones.
+                delta = (&delta * self.weights[l].transpose()).zip_zip_map(
+                    a,
+                    &masks[l - 1],
+                    |d, a, m| match m > 0.0 {
+                        true => d * m * derivative(self.activation, a / m),
+                        false => 0.0,
+                    },
+                );
+            }
+            gradients[l] = (dw, db);
+        }
+
+        let values: Vec<f32> = gradients
+            .iter()
+            .flat_map(|(w, b)| w.iter().chain(b.iter()).cloned().collect::<Vec<f32>>())
+            .collect();
+        return DMatrix::from_vec(values.len(), 1, values);
+    }
+
+    fn linear(&self, l: usize, a: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut z = a * &self.weights[l];
+        for mut row in z.row_iter_mut() {
+            row += &self.biases[l];
+        }
+
+        return z;
+    }
+}
+
+fn initial_weights(
+    n_in: usize,
+    n_out: usize,
+    initialization: Initialization,
+    rng: &mut StdRng,
+) -> DMatrix<f32> {
+    match initialization {
+        Initialization::XAVIER => {
+            let bound = (6.0 / (n_in + n_out) as f32).sqrt();
+            DMatrix::from_fn(n_in, n_out, |_, _| rng.gen_range(-bound..bound))
+        }
+        Initialization::HE => random_normal_matrix(n_in, n_out, rng) * (2.0 / n_in as f32).sqrt(),
+        Initialization::NORMAL(std) => random_normal_matrix(n_in, n_out, rng) * std,
+    }
+}
+
+fn activate(activation: Activation, z: &DMatrix<f32>) -> DMatrix<f32> {
+    match activation {
+        Activation::IDENTITY => z.clone(),
+        Activation::RELU => z.map(|v| v.max(0.0)),
+        Activation::TANH => z.map(|v| v.tanh()),
+        Activation::SIGMOID => z.map(sigmoid),
+    }
+}
+
+/// Derivative of the activation, written in terms of its output `a`.
+fn derivative(activation: Activation, a: f32) -> f32 {
+    match activation {
+        Activation::IDENTITY => 1.0,
+        Activation::RELU => match a > 0.0 {
+            true => 1.0,
+            false => 0.0,
+        },
+        Activation::TANH => 1.0 - a * a,
+        Activation::SIGMOID => a * (1.0 - a),
+    }
+}
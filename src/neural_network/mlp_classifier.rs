@@ -0,0 +1,164 @@
+use nalgebra::DMatrix;
+
+use crate::losses::classification::{softmax, CrossEntropy};
+use crate::neural_network::mlp::{DenseNetwork, MLPParams};
+use crate::optim::{
+    adam::Adam,
+    optimizer::Optimizer,
+    training::{train, FitConfig, GradientModel, TrainingHistory},
+};
+use crate::utils::types::{Activation, Initialization};
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Multi-layer perceptron classifier with a softmax output over the classes found in
+/// the first column of `y`, trained on the cross-entropy.
+///
+/// As a `GradientModel` it expects one-hot targets with one column per class, which
+/// the `fit` methods build from the labels.
+pub struct MLPClassifier {
+    pub params: MLPParams,
+    pub classes: Vec<f32>,
+    pub network: DenseNetwork,
+}
+
+impl MLPClassifier {
+    pub fn new(hidden_layer_sizes: Vec<usize>) -> MLPClassifier {
+        MLPClassifier {
+            params: MLPParams::new(hidden_layer_sizes),
+            classes: Vec::new(),
+            network: DenseNetwork::new(&[], Activation::RELU, Initialization::HE, None),
+        }
+    }
+
+    pub fn with_activation(mut self, activation: Activation) -> MLPClassifier {
+        self.params.activation = activation;
+        self
+    }
+
+    pub fn with_initialization(mut self, initialization: Initialization) -> MLPClassifier {
+        self.params.initialization = initialization;
+        self
+    }
+
+    pub fn with_dropout(mut self, dropout: f32) -> MLPClassifier {
+        self.params.dropout = dropout;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> MLPClassifier {
+        self.params.alpha = alpha;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> MLPClassifier {
+        self.params.seed = Some(seed);
+        self
+    }
+
+    /// Raw scores of the output layer, one column per class.
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return self.network.forward(x);
+    }
+
+    pub fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return softmax(&self.decision_function(x));
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return argmax_classes(&self.decision_function(x), &self.classes);
+    }
+
+    /// Full-batch training with Adam.
+    pub fn fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        epochs: usize,
+        lr: f32,
+    ) -> Result<TrainingHistory, String> {
+        self.fit_with_config(x, y, &mut Adam::new(lr), &mut FitConfig::new(epochs))
+    }
+
+    pub fn fit_with_config(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        optimizer: &mut dyn Optimizer,
+        config: &mut FitConfig,
+    ) -> Result<TrainingHistory, String> {
+        let one_hot = self.init_network(x, y)?;
+        train(self, optimizer, x, &one_hot, None, config)
+    }
+
+    /// Gradient-based fit where each sample's loss counts `sample_weight[i]` times.
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+        optimizer: &mut dyn Optimizer,
+        config: &mut FitConfig,
+    ) -> Result<TrainingHistory, String> {
+        let one_hot = self.init_network(x, y)?;
+        train(self, optimizer, x, &one_hot, Some(sample_weight), config)
+    }
+
+    /// Learns the classes, (re)builds the network if its shape changed and returns the
+    /// one-hot encoding of `y`.
+    fn init_network(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<DMatrix<f32>, String> {
+        self.params.validate()?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        let classes = unique_classes(y);
+        if classes.len() < 2 {
+            return Err("y should contain at least two classes".to_string());
+        }
+        if classes != self.classes || self.network.n_inputs() != x.ncols() {
+            self.network = self.params.build(x.ncols(), classes.len());
+            self.classes = classes;
+        }
+        self.network.dropout = self.params.dropout;
+        self.network.alpha = self.params.alpha;
+
+        let one_hot = DMatrix::from_fn(y.nrows(), self.classes.len(), |i, k| {
+            match y[(i, 0)] == self.classes[k] {
+                true => 1.0,
+                false => 0.0,
+            }
+        });
+        return Ok(one_hot);
+    }
+}
+
+impl GradientModel for MLPClassifier {
+    fn parameters(&self) -> DMatrix<f32> {
+        return self.network.parameters();
+    }
+
+    fn set_parameters(&mut self, parameters: &DMatrix<f32>) {
+        self.network.set_parameters(parameters);
+    }
+
+    fn loss(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> f32 {
+        return self.network.loss(&CrossEntropy, x, y, sample_weight);
+    }
+
+    fn gradient(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> DMatrix<f32> {
+        return self.network.gradient(&CrossEntropy, x, y, sample_weight);
+    }
+}
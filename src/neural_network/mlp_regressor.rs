@@ -0,0 +1,141 @@
+use nalgebra::DMatrix;
+
+use crate::losses::loss::Loss;
+use crate::neural_network::mlp::{DenseNetwork, MLPParams};
+use crate::optim::{
+    adam::Adam,
+    optimizer::Optimizer,
+    training::{train, FitConfig, GradientModel, TrainingHistory},
+};
+use crate::utils::types::{Activation, Initialization, TypeRegression};
+
+/// Multi-layer perceptron trained by backpropagation on any regression [`Loss`] (the
+/// squared error by default), with one linear output per target column.
+///
+/// The network is initialised on the first fit and again whenever the number of
+/// features or targets changes, so later fits continue from the current weights.
+pub struct MLPRegressor {
+    pub params: MLPParams,
+    pub loss: Box<dyn Loss>,
+    pub network: DenseNetwork,
+}
+
+impl MLPRegressor {
+    pub fn new(hidden_layer_sizes: Vec<usize>) -> MLPRegressor {
+        MLPRegressor {
+            params: MLPParams::new(hidden_layer_sizes),
+            loss: Box::new(TypeRegression::MSE),
+            network: DenseNetwork::new(&[], Activation::RELU, Initialization::HE, None),
+        }
+    }
+
+    pub fn with_loss<L: Loss + 'static>(mut self, loss: L) -> MLPRegressor {
+        self.loss = Box::new(loss);
+        self
+    }
+
+    pub fn with_activation(mut self, activation: Activation) -> MLPRegressor {
+        self.params.activation = activation;
+        self
+    }
+
+    pub fn with_initialization(mut self, initialization: Initialization) -> MLPRegressor {
+        self.params.initialization = initialization;
+        self
+    }
+
+    pub fn with_dropout(mut self, dropout: f32) -> MLPRegressor {
+        self.params.dropout = dropout;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> MLPRegressor {
+        self.params.alpha = alpha;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> MLPRegressor {
+        self.params.seed = Some(seed);
+        self
+    }
+
+    /// Predictions with one column per target.
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return self.network.forward(x);
+    }
+
+    /// Full-batch training with Adam.
+    pub fn fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        epochs: usize,
+        lr: f32,
+    ) -> Result<TrainingHistory, String> {
+        self.fit_with_config(x, y, &mut Adam::new(lr), &mut FitConfig::new(epochs))
+    }
+
+    pub fn fit_with_config(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        optimizer: &mut dyn Optimizer,
+        config: &mut FitConfig,
+    ) -> Result<TrainingHistory, String> {
+        self.init_network(x.ncols(), y.ncols())?;
+        train(self, optimizer, x, y, None, config)
+    }
+
+    /// Gradient-based fit where each sample's loss counts `sample_weight[i]` times.
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+        optimizer: &mut dyn Optimizer,
+        config: &mut FitConfig,
+    ) -> Result<TrainingHistory, String> {
+        self.init_network(x.ncols(), y.ncols())?;
+        train(self, optimizer, x, y, Some(sample_weight), config)
+    }
+
+    fn init_network(&mut self, n_inputs: usize, n_outputs: usize) -> Result<(), String> {
+        self.params.validate()?;
+        if self.network.n_inputs() != n_inputs || self.network.n_outputs() != n_outputs {
+            self.network = self.params.build(n_inputs, n_outputs);
+        }
+        self.network.dropout = self.params.dropout;
+        self.network.alpha = self.params.alpha;
+        Ok(())
+    }
+}
+
+impl GradientModel for MLPRegressor {
+    fn parameters(&self) -> DMatrix<f32> {
+        return self.network.parameters();
+    }
+
+    fn set_parameters(&mut self, parameters: &DMatrix<f32>) {
+        self.network.set_parameters(parameters);
+    }
+
+    fn loss(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> f32 {
+        return self.network.loss(self.loss.as_ref(), x, y, sample_weight);
+    }
+
+    fn gradient(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> DMatrix<f32> {
+        return self
+            .network
+            .gradient(self.loss.as_ref(), x, y, sample_weight);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod mlp;
+pub mod mlp_classifier;
+pub mod mlp_regressor;
@@ -0,0 +1,58 @@
+use nalgebra::DMatrix;
+
+use crate::optim::optimizer::Optimizer;
+use crate::optim::training::{train, FitConfig, GradientModel};
+
+/// A model that learns from a stream of batches instead of one in-memory dataset.
+///
+/// Every call of `partial_fit` updates the model with a new batch, continuing from what
+/// it learnt so far. Classifiers learn their classes from the first batch unless they
+/// were announced through their own `partial_fit`, and unsupervised models ignore `y`.
+pub trait IncrementalModel {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String>;
+}
+
+/// Incremental training of any [`GradientModel`]: every batch runs `epochs` epochs of
+/// the training loop with an optimizer that keeps its state (such as Adam's moments)
+/// from one batch to the next.
+///
+/// The model should already have parameters of the right shape: a `PolynomialRegression`
+/// does after `new`, an `MLPRegressor` after `fit` with 0 epochs on the first batch.
+/// `batch_loss` records the training loss after every batch.
+pub struct IncrementalTrainer<M: GradientModel> {
+    pub model: M,
+    pub optimizer: Box<dyn Optimizer>,
+    pub epochs: usize,
+    pub batch_loss: Vec<f32>,
+}
+
+impl<M: GradientModel> IncrementalTrainer<M> {
+    pub fn new<O: Optimizer + 'static>(model: M, optimizer: O) -> IncrementalTrainer<M> {
+        IncrementalTrainer {
+            model,
+            optimizer: Box::new(optimizer),
+            epochs: 1,
+            batch_loss: Vec::new(),
+        }
+    }
+
+    pub fn with_epochs(mut self, epochs: usize) -> IncrementalTrainer<M> {
+        self.epochs = epochs;
+        self
+    }
+}
+
+impl<M: GradientModel> IncrementalModel for IncrementalTrainer<M> {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        let history = train(
+            &mut self.model,
+            self.optimizer.as_mut(),
+            x,
+            y,
+            None,
+            &mut FitConfig::new(self.epochs).with_restore_best_weights(false),
+        )?;
+        self.batch_loss.extend(history.train_loss.last());
+        Ok(())
+    }
+}
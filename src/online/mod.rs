@@ -0,0 +1,5 @@
+pub mod incremental;
+pub mod one_vs_rest;
+pub mod passive_aggressive;
+pub mod perceptron;
+pub mod sgd_classifier;
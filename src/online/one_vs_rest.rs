@@ -0,0 +1,131 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::naive_bayes::base::class_indices;
+use crate::utils::utils::argmax_classes;
+
+/// Linear scores of the online classifiers: a single machine separating `classes[1]`
+/// from `classes[0]` for two classes, one machine per class (one-vs-rest) otherwise.
+///
+/// `coefficients` has one column and `intercept` one entry per machine.
+#[derive(Clone, Debug)]
+pub struct OneVsRest {
+    pub classes: Vec<f32>,
+    pub coefficients: DMatrix<f32>,
+    pub intercept: Vec<f32>,
+}
+
+impl OneVsRest {
+    pub fn new() -> OneVsRest {
+        OneVsRest {
+            classes: Vec::new(),
+            coefficients: DMatrix::zeros(0, 0),
+            intercept: Vec::new(),
+        }
+    }
+
+    pub fn n_machines(&self) -> usize {
+        self.intercept.len()
+    }
+
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut scores = x * &self.coefficients;
+        for (k, b) in self.intercept.iter().enumerate() {
+            scores.column_mut(k).add_scalar_mut(*b);
+        }
+
+        return scores;
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        let scores = self.decision_function(x);
+        if self.classes.len() == 2 {
+            return scores
+                .column(0)
+                .iter()
+                .map(|s| self.classes[(*s > 0.0) as usize])
+                .collect();
+        }
+
+        return argmax_classes(&scores, &self.classes);
+    }
+
+    /// Sets the classes and zero weights on the first call (see `class_indices`) and
+    /// returns the class index of every sample of `y`.
+    pub(crate) fn prepare(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<Vec<usize>, String> {
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        let first_call = self.classes.is_empty();
+        if !first_call && x.ncols() != self.coefficients.nrows() {
+            return Err(format!(
+                "x has {} features but the model was trained on {}",
+                x.ncols(),
+                self.coefficients.nrows()
+            ));
+        }
+
+        let indices = class_indices(&mut self.classes, y, classes)?;
+        if first_call {
+            if self.classes.len() < 2 {
+                self.classes.clear();
+                return Err("y should contain at least two classes".to_string());
+            }
+            let n_machines = match self.classes.len() {
+                2 => 1,
+                n => n,
+            };
+            self.coefficients = DMatrix::zeros(x.ncols(), n_machines);
+            self.intercept = vec![0.0; n_machines];
+        }
+        return Ok(indices);
+    }
+
+    /// Target of every machine for a sample of class `class_index`: 1 when the machine
+    /// should score it positively, 0 otherwise.
+    pub(crate) fn targets(&self, class_index: usize) -> Vec<f32> {
+        if self.n_machines() == 1 {
+            return vec![class_index as f32];
+        }
+
+        return (0..self.n_machines())
+            .map(|k| (k == class_index) as usize as f32)
+            .collect();
+    }
+
+    /// Score of machine `k` on row `i` of `x`.
+    pub(crate) fn score(&self, x: &DMatrix<f32>, i: usize, k: usize) -> f32 {
+        return x.row(i).transpose().dot(&self.coefficients.column(k)) + self.intercept[k];
+    }
+
+    /// Moves machine `k` by `step` times row `i` of `x`, and its intercept by `step`.
+    pub(crate) fn step(&mut self, x: &DMatrix<f32>, i: usize, k: usize, step: f32) {
+        self.coefficients
+            .column_mut(k)
+            .axpy(step, &x.row(i).transpose(), 1.0);
+        self.intercept[k] += step;
+    }
+}
+
+impl Default for OneVsRest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
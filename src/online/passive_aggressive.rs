@@ -0,0 +1,234 @@
+use nalgebra::DMatrix;
+use rand::seq::SliceRandom;
+
+use crate::online::incremental::IncrementalModel;
+use crate::online::one_vs_rest::{seeded_rng, OneVsRest};
+
+/// Passive-aggressive classifier (PA-I, Crammer et al., 2006) on the hinge loss.
+///
+/// A sample scored with a margin of at least 1 leaves the weights untouched; otherwise
+/// the machine takes the smallest step that fixes the margin, capped at `c`. The
+/// intercept is updated as the weight of a constant feature. `fit` and `partial_fit`
+/// behave as for `Perceptron`.
+pub struct PassiveAggressiveClassifier {
+    pub c: f32,
+    pub max_iter: usize,
+    pub seed: Option<u64>,
+    pub model: OneVsRest,
+}
+
+/// Passive-aggressive regression (PA-I) on the `epsilon`-insensitive loss, with a
+/// single target column.
+pub struct PassiveAggressiveRegressor {
+    pub c: f32,
+    pub epsilon: f32,
+    pub max_iter: usize,
+    pub seed: Option<u64>,
+    pub coefficients: DMatrix<f32>,
+    pub intercept: f32,
+}
+
+impl PassiveAggressiveClassifier {
+    pub fn new() -> PassiveAggressiveClassifier {
+        PassiveAggressiveClassifier {
+            c: 1.0,
+            max_iter: 20,
+            seed: None,
+            model: OneVsRest::new(),
+        }
+    }
+
+    pub fn with_c(mut self, c: f32) -> PassiveAggressiveClassifier {
+        self.c = c;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> PassiveAggressiveClassifier {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> PassiveAggressiveClassifier {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.model = OneVsRest::new();
+        let indices = self.prepare(x, y, None)?;
+        let mut rng = seeded_rng(self.seed);
+        let mut order: Vec<usize> = (0..x.nrows()).collect();
+        for _ in 0..self.max_iter {
+            order.shuffle(&mut rng);
+            self.learn(x, &indices, &order);
+        }
+        Ok(())
+    }
+
+    /// One pass over a new batch. `classes` lists every class of the stream and is only
+    /// read on the first call.
+    pub fn partial_fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<(), String> {
+        let indices = self.prepare(x, y, classes)?;
+        self.learn(x, &indices, &(0..x.nrows()).collect::<Vec<usize>>());
+        Ok(())
+    }
+
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return self.model.decision_function(x);
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return self.model.predict(x);
+    }
+
+    fn prepare(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<Vec<usize>, String> {
+        if self.c <= 0.0 {
+            return Err(format!("c should be positive, got {}", self.c));
+        }
+        return self.model.prepare(x, y, classes);
+    }
+
+    fn learn(&mut self, x: &DMatrix<f32>, indices: &[usize], order: &[usize]) {
+        for &i in order {
+            let norm = x.row(i).norm_squared() + 1.0;
+            for (k, target) in self.model.targets(indices[i]).iter().enumerate() {
+                let sign = 2.0 * target - 1.0;
+                let loss = (1.0 - sign * self.model.score(x, i, k)).max(0.0);
+                if loss > 0.0 {
+                    self.model.step(x, i, k, sign * self.c.min(loss / norm));
+                }
+            }
+        }
+    }
+}
+
+impl PassiveAggressiveRegressor {
+    pub fn new() -> PassiveAggressiveRegressor {
+        PassiveAggressiveRegressor {
+            c: 1.0,
+            epsilon: 0.1,
+            max_iter: 20,
+            seed: None,
+            coefficients: DMatrix::zeros(0, 1),
+            intercept: 0.0,
+        }
+    }
+
+    pub fn with_c(mut self, c: f32) -> PassiveAggressiveRegressor {
+        self.c = c;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f32) -> PassiveAggressiveRegressor {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> PassiveAggressiveRegressor {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> PassiveAggressiveRegressor {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.coefficients = DMatrix::zeros(0, 1);
+        self.intercept = 0.0;
+        self.prepare(x, y)?;
+        let mut rng = seeded_rng(self.seed);
+        let mut order: Vec<usize> = (0..x.nrows()).collect();
+        for _ in 0..self.max_iter {
+            order.shuffle(&mut rng);
+            self.learn(x, y, &order);
+        }
+        Ok(())
+    }
+
+    /// One pass over a new batch, continuing from the current weights.
+    pub fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.prepare(x, y)?;
+        self.learn(x, y, &(0..x.nrows()).collect::<Vec<usize>>());
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return (x * &self.coefficients).add_scalar(self.intercept);
+    }
+
+    fn prepare(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        if x.nrows() != y.nrows() || y.ncols() != 1 {
+            return Err(format!(
+                "y should be a single column with one row per sample. |x|: {}, |y|: {}x{}",
+                x.nrows(),
+                y.nrows(),
+                y.ncols()
+            ));
+        }
+        if self.c <= 0.0 || self.epsilon < 0.0 {
+            return Err(format!(
+                "c should be positive and epsilon non-negative, got {} and {}",
+                self.c, self.epsilon
+            ));
+        }
+        if self.coefficients.nrows() == 0 {
+            self.coefficients = DMatrix::zeros(x.ncols(), 1);
+        } else if self.coefficients.nrows() != x.ncols() {
+            return Err(format!(
+                "x has {} features but the model was trained on {}",
+                x.ncols(),
+                self.coefficients.nrows()
+            ));
+        }
+        Ok(())
+    }
+
+    fn learn(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>, order: &[usize]) {
+        for &i in order {
+            let row = x.row(i).transpose();
+            let residual = y[(i, 0)] - row.dot(&self.coefficients) - self.intercept;
+            let loss = residual.abs() - self.epsilon;
+            if loss > 0.0 {
+                let step = residual.signum() * self.c.min(loss / (row.norm_squared() + 1.0));
+                self.coefficients += &row * step;
+                self.intercept += step;
+            }
+        }
+    }
+}
+
+impl Default for PassiveAggressiveClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for PassiveAggressiveRegressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IncrementalModel for PassiveAggressiveClassifier {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        PassiveAggressiveClassifier::partial_fit(self, x, y, None)
+    }
+}
+
+impl IncrementalModel for PassiveAggressiveRegressor {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        PassiveAggressiveRegressor::partial_fit(self, x, y)
+    }
+}
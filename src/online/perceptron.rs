@@ -0,0 +1,112 @@
+use nalgebra::DMatrix;
+use rand::seq::SliceRandom;
+
+use crate::online::incremental::IncrementalModel;
+use crate::online::one_vs_rest::{seeded_rng, OneVsRest};
+
+/// Rosenblatt's perceptron: every misclassified sample moves the weights of the
+/// offending machine by `eta0` times the sample, towards its class.
+///
+/// `fit` starts from zero weights and makes `max_iter` shuffled passes over the data;
+/// `partial_fit` makes one pass over a batch in order, continuing from the current
+/// weights, so the model can learn from data arriving over time.
+pub struct Perceptron {
+    pub eta0: f32,
+    pub max_iter: usize,
+    pub seed: Option<u64>,
+    pub model: OneVsRest,
+}
+
+impl Perceptron {
+    pub fn new() -> Perceptron {
+        Perceptron {
+            eta0: 1.0,
+            max_iter: 20,
+            seed: None,
+            model: OneVsRest::new(),
+        }
+    }
+
+    pub fn with_eta0(mut self, eta0: f32) -> Perceptron {
+        self.eta0 = eta0;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> Perceptron {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Perceptron {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.model = OneVsRest::new();
+        let indices = self.prepare(x, y, None)?;
+        let mut rng = seeded_rng(self.seed);
+        let mut order: Vec<usize> = (0..x.nrows()).collect();
+        for _ in 0..self.max_iter {
+            order.shuffle(&mut rng);
+            self.learn(x, &indices, &order);
+        }
+        Ok(())
+    }
+
+    /// One pass over a new batch. `classes` lists every class of the stream and is only
+    /// read on the first call.
+    pub fn partial_fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<(), String> {
+        let indices = self.prepare(x, y, classes)?;
+        self.learn(x, &indices, &(0..x.nrows()).collect::<Vec<usize>>());
+        Ok(())
+    }
+
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return self.model.decision_function(x);
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return self.model.predict(x);
+    }
+
+    fn prepare(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<Vec<usize>, String> {
+        if self.eta0 <= 0.0 {
+            return Err(format!("eta0 should be positive, got {}", self.eta0));
+        }
+        return self.model.prepare(x, y, classes);
+    }
+
+    fn learn(&mut self, x: &DMatrix<f32>, indices: &[usize], order: &[usize]) {
+        for &i in order {
+            for (k, target) in self.model.targets(indices[i]).iter().enumerate() {
+                let sign = 2.0 * target - 1.0;
+                if sign * self.model.score(x, i, k) <= 0.0 {
+                    self.model.step(x, i, k, self.eta0 * sign);
+                }
+            }
+        }
+    }
+}
+
+impl Default for Perceptron {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IncrementalModel for Perceptron {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        Perceptron::partial_fit(self, x, y, None)
+    }
+}
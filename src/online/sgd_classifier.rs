@@ -0,0 +1,141 @@
+use nalgebra::DMatrix;
+use rand::seq::SliceRandom;
+
+use crate::losses::classification::Hinge;
+use crate::losses::loss::Loss;
+use crate::online::incremental::IncrementalModel;
+use crate::online::one_vs_rest::{seeded_rng, OneVsRest};
+
+/// Linear classifier trained one sample at a time by stochastic gradient descent on a
+/// margin [`Loss`]: `Hinge` (a linear SVM, the default), `LogLoss` (logistic
+/// regression) or `ModifiedHuber`, plus `alpha / 2 * |w|^2`.
+///
+/// The step size after `t` samples is `eta0 / (1 + alpha * eta0 * t)`; `t` keeps
+/// counting across calls of `partial_fit` and is reset by `fit`.
+pub struct SGDClassifier {
+    pub loss: Box<dyn Loss>,
+    pub alpha: f32,
+    pub eta0: f32,
+    pub max_iter: usize,
+    pub seed: Option<u64>,
+    pub n_samples_seen: usize,
+    pub model: OneVsRest,
+}
+
+impl SGDClassifier {
+    pub fn new() -> SGDClassifier {
+        SGDClassifier {
+            loss: Box::new(Hinge),
+            alpha: 1e-4,
+            eta0: 0.01,
+            max_iter: 20,
+            seed: None,
+            n_samples_seen: 0,
+            model: OneVsRest::new(),
+        }
+    }
+
+    pub fn with_loss<L: Loss + 'static>(mut self, loss: L) -> SGDClassifier {
+        self.loss = Box::new(loss);
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> SGDClassifier {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_eta0(mut self, eta0: f32) -> SGDClassifier {
+        self.eta0 = eta0;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> SGDClassifier {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> SGDClassifier {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.model = OneVsRest::new();
+        self.n_samples_seen = 0;
+        let indices = self.prepare(x, y, None)?;
+        let mut rng = seeded_rng(self.seed);
+        let mut order: Vec<usize> = (0..x.nrows()).collect();
+        for _ in 0..self.max_iter {
+            order.shuffle(&mut rng);
+            self.learn(x, &indices, &order);
+        }
+        Ok(())
+    }
+
+    /// One pass over a new batch. `classes` lists every class of the stream and is only
+    /// read on the first call.
+    pub fn partial_fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<(), String> {
+        let indices = self.prepare(x, y, classes)?;
+        self.learn(x, &indices, &(0..x.nrows()).collect::<Vec<usize>>());
+        Ok(())
+    }
+
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return self.model.decision_function(x);
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return self.model.predict(x);
+    }
+
+    fn prepare(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        classes: Option<&[f32]>,
+    ) -> Result<Vec<usize>, String> {
+        if self.eta0 <= 0.0 || self.alpha < 0.0 {
+            return Err(format!(
+                "eta0 should be positive and alpha non-negative, got {} and {}",
+                self.eta0, self.alpha
+            ));
+        }
+        return self.model.prepare(x, y, classes);
+    }
+
+    fn learn(&mut self, x: &DMatrix<f32>, indices: &[usize], order: &[usize]) {
+        let n_machines = self.model.n_machines();
+        for &i in order {
+            let eta = self.eta0 / (1.0 + self.alpha * self.eta0 * self.n_samples_seen as f32);
+            let targets = DMatrix::from_vec(1, n_machines, self.model.targets(indices[i]));
+            let scores = DMatrix::from_fn(1, n_machines, |_, k| self.model.score(x, i, k));
+            let gradient = self.loss.gradient(&targets, &scores);
+
+            self.model.coefficients *= 1.0 - eta * self.alpha;
+            for k in 0..n_machines {
+                if gradient[(0, k)] != 0.0 {
+                    self.model.step(x, i, k, -eta * gradient[(0, k)]);
+                }
+            }
+            self.n_samples_seen += 1;
+        }
+    }
+}
+
+impl Default for SGDClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IncrementalModel for SGDClassifier {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        SGDClassifier::partial_fit(self, x, y, None)
+    }
+}
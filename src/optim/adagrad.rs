@@ -0,0 +1,44 @@
+use nalgebra::DMatrix;
+
+use crate::optim::optimizer::{state_for, Optimizer};
+
+/// Adagrad: per-parameter learning rates scaled by the accumulated squared gradients.
+pub struct Adagrad {
+    pub learning_rate: f32,
+    pub epsilon: f32,
+    accumulator: Option<DMatrix<f32>>,
+}
+
+impl Adagrad {
+    pub fn new(learning_rate: f32) -> Adagrad {
+        Adagrad {
+            learning_rate,
+            epsilon: 1e-8,
+            accumulator: None,
+        }
+    }
+}
+
+impl Optimizer for Adagrad {
+    fn step(&mut self, parameters: &mut DMatrix<f32>, gradient: &DMatrix<f32>) {
+        let accumulator = state_for(&mut self.accumulator, parameters.shape());
+        *accumulator += gradient.component_mul(gradient);
+
+        for i in 0..parameters.len() {
+            parameters[i] -=
+                self.learning_rate * gradient[i] / (accumulator[i].sqrt() + self.epsilon);
+        }
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn reset(&mut self) {
+        self.accumulator = None;
+    }
+}
@@ -0,0 +1,66 @@
+use nalgebra::DMatrix;
+
+use crate::optim::optimizer::{state_for, Optimizer};
+
+/// Adam: bias-corrected first and second moment estimates of the gradient.
+pub struct Adam {
+    pub learning_rate: f32,
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+    first_moment: Option<DMatrix<f32>>,
+    second_moment: Option<DMatrix<f32>>,
+    t: i32,
+}
+
+impl Adam {
+    pub fn new(learning_rate: f32) -> Adam {
+        Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            first_moment: None,
+            second_moment: None,
+            t: 0,
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, parameters: &mut DMatrix<f32>, gradient: &DMatrix<f32>) {
+        if self.first_moment.as_ref().map(|m| m.shape()) != Some(parameters.shape()) {
+            self.t = 0;
+        }
+        self.t += 1;
+
+        let first_moment = state_for(&mut self.first_moment, parameters.shape());
+        let second_moment = state_for(&mut self.second_moment, parameters.shape());
+        let correction1 = 1.0 - self.beta1.powi(self.t);
+        let correction2 = 1.0 - self.beta2.powi(self.t);
+
+        for i in 0..parameters.len() {
+            first_moment[i] = self.beta1 * first_moment[i] + (1.0 - self.beta1) * gradient[i];
+            second_moment[i] =
+                self.beta2 * second_moment[i] + (1.0 - self.beta2) * gradient[i].powf(2.0);
+
+            let m_hat = first_moment[i] / correction1;
+            let v_hat = second_moment[i] / correction2;
+            parameters[i] -= self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+        }
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn reset(&mut self) {
+        self.first_moment = None;
+        self.second_moment = None;
+        self.t = 0;
+    }
+}
@@ -0,0 +1,51 @@
+use nalgebra::DMatrix;
+
+use crate::optim::training::TrainingHistory;
+
+/// Hooks invoked by the training loop around every epoch.
+///
+/// `on_epoch_end` sees the history recorded so far, including the epoch that just
+/// finished, and the current parameters; returning `false` stops training.
+pub trait Callback {
+    fn on_epoch_start(&mut self, _epoch: usize) {}
+
+    fn on_epoch_end(
+        &mut self,
+        _epoch: usize,
+        _history: &TrainingHistory,
+        _parameters: &DMatrix<f32>,
+    ) -> bool {
+        true
+    }
+}
+
+/// Prints the training (and validation) loss every `every` epochs.
+pub struct LossLogger {
+    pub every: usize,
+}
+
+impl LossLogger {
+    pub fn new(every: usize) -> LossLogger {
+        LossLogger { every }
+    }
+}
+
+impl Callback for LossLogger {
+    fn on_epoch_end(
+        &mut self,
+        epoch: usize,
+        history: &TrainingHistory,
+        _parameters: &DMatrix<f32>,
+    ) -> bool {
+        if epoch.is_multiple_of(self.every.max(1)) {
+            match history.validation_loss.last() {
+                Some(validation_loss) => println!(
+                    "epoch {}: loss {} validation loss {}",
+                    epoch, history.train_loss[epoch], validation_loss
+                ),
+                None => println!("epoch {}: loss {}", epoch, history.train_loss[epoch]),
+            }
+        }
+        true
+    }
+}
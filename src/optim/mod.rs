@@ -0,0 +1,8 @@
+pub mod adagrad;
+pub mod adam;
+pub mod callbacks;
+pub mod optimizer;
+pub mod rmsprop;
+pub mod schedules;
+pub mod sgd;
+pub mod training;
@@ -0,0 +1,27 @@
+use nalgebra::DMatrix;
+
+/// First-order optimizer updating a flat column of model parameters in place.
+///
+/// `gradient` is the gradient of the loss being minimised and always has the same
+/// shape as `parameters`. Optimizers keeping per-parameter state (velocity, moment
+/// estimates) allocate it lazily on the first `step` and drop it on `reset`.
+pub trait Optimizer {
+    fn step(&mut self, parameters: &mut DMatrix<f32>, gradient: &DMatrix<f32>);
+
+    fn learning_rate(&self) -> f32;
+
+    fn set_learning_rate(&mut self, learning_rate: f32);
+
+    fn reset(&mut self);
+}
+
+pub(crate) fn state_for(
+    state: &mut Option<DMatrix<f32>>,
+    shape: (usize, usize),
+) -> &mut DMatrix<f32> {
+    if state.as_ref().map(|s| s.shape()) != Some(shape) {
+        *state = Some(DMatrix::zeros(shape.0, shape.1));
+    }
+
+    state.as_mut().unwrap()
+}
@@ -0,0 +1,46 @@
+use nalgebra::DMatrix;
+
+use crate::optim::optimizer::{state_for, Optimizer};
+
+/// RMSProp: like Adagrad but with an exponentially decaying average of squared gradients.
+pub struct RMSProp {
+    pub learning_rate: f32,
+    pub rho: f32,
+    pub epsilon: f32,
+    mean_square: Option<DMatrix<f32>>,
+}
+
+impl RMSProp {
+    pub fn new(learning_rate: f32) -> RMSProp {
+        RMSProp {
+            learning_rate,
+            rho: 0.9,
+            epsilon: 1e-8,
+            mean_square: None,
+        }
+    }
+}
+
+impl Optimizer for RMSProp {
+    fn step(&mut self, parameters: &mut DMatrix<f32>, gradient: &DMatrix<f32>) {
+        let mean_square = state_for(&mut self.mean_square, parameters.shape());
+
+        for i in 0..parameters.len() {
+            mean_square[i] = self.rho * mean_square[i] + (1.0 - self.rho) * gradient[i].powf(2.0);
+            parameters[i] -=
+                self.learning_rate * gradient[i] / (mean_square[i].sqrt() + self.epsilon);
+        }
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn reset(&mut self) {
+        self.mean_square = None;
+    }
+}
@@ -0,0 +1,174 @@
+use std::f32::consts::PI;
+
+/// Learning-rate schedule applied by the training loop at the start of every epoch.
+///
+/// `initial_learning_rate` is the optimizer's learning rate before training started
+/// and `loss` is the loss monitored at the end of the previous epoch, if any.
+pub trait LearningRateSchedule {
+    fn learning_rate(&mut self, epoch: usize, initial_learning_rate: f32, loss: Option<f32>)
+        -> f32;
+}
+
+/// Multiplies the learning rate by `gamma` every `step_size` epochs.
+pub struct StepDecay {
+    pub step_size: usize,
+    pub gamma: f32,
+}
+
+impl StepDecay {
+    pub fn new(step_size: usize, gamma: f32) -> StepDecay {
+        StepDecay { step_size, gamma }
+    }
+}
+
+impl LearningRateSchedule for StepDecay {
+    fn learning_rate(&mut self, epoch: usize, initial_learning_rate: f32, _: Option<f32>) -> f32 {
+        initial_learning_rate * self.gamma.powi((epoch / self.step_size.max(1)) as i32)
+    }
+}
+
+/// Multiplies the learning rate by `gamma` every epoch.
+pub struct ExponentialDecay {
+    pub gamma: f32,
+}
+
+impl ExponentialDecay {
+    pub fn new(gamma: f32) -> ExponentialDecay {
+        ExponentialDecay { gamma }
+    }
+}
+
+impl LearningRateSchedule for ExponentialDecay {
+    fn learning_rate(&mut self, epoch: usize, initial_learning_rate: f32, _: Option<f32>) -> f32 {
+        initial_learning_rate * self.gamma.powi(epoch as i32)
+    }
+}
+
+/// `initial_learning_rate / (epoch + 1)^power_t`.
+pub struct InverseScaling {
+    pub power_t: f32,
+}
+
+impl InverseScaling {
+    pub fn new(power_t: f32) -> InverseScaling {
+        InverseScaling { power_t }
+    }
+}
+
+impl LearningRateSchedule for InverseScaling {
+    fn learning_rate(&mut self, epoch: usize, initial_learning_rate: f32, _: Option<f32>) -> f32 {
+        initial_learning_rate / ((epoch + 1) as f32).powf(self.power_t)
+    }
+}
+
+/// Anneals the learning rate from its initial value to `min_learning_rate` along half a
+/// cosine period of `t_max` epochs, then stays at `min_learning_rate`.
+pub struct CosineAnnealing {
+    pub t_max: usize,
+    pub min_learning_rate: f32,
+}
+
+impl CosineAnnealing {
+    pub fn new(t_max: usize, min_learning_rate: f32) -> CosineAnnealing {
+        CosineAnnealing {
+            t_max,
+            min_learning_rate,
+        }
+    }
+}
+
+impl LearningRateSchedule for CosineAnnealing {
+    fn learning_rate(&mut self, epoch: usize, initial_learning_rate: f32, _: Option<f32>) -> f32 {
+        let progress = epoch.min(self.t_max) as f32 / self.t_max.max(1) as f32;
+        cosine(initial_learning_rate, self.min_learning_rate, progress)
+    }
+}
+
+/// Cosine annealing restarted every cycle (SGDR); the first cycle lasts `t_0` epochs and
+/// each following one is `t_mult` times longer.
+pub struct CosineWarmRestarts {
+    pub t_0: usize,
+    pub t_mult: usize,
+    pub min_learning_rate: f32,
+}
+
+impl CosineWarmRestarts {
+    pub fn new(t_0: usize, t_mult: usize, min_learning_rate: f32) -> CosineWarmRestarts {
+        CosineWarmRestarts {
+            t_0,
+            t_mult,
+            min_learning_rate,
+        }
+    }
+}
+
+impl LearningRateSchedule for CosineWarmRestarts {
+    fn learning_rate(&mut self, epoch: usize, initial_learning_rate: f32, _: Option<f32>) -> f32 {
+        let mut cycle_length = self.t_0.max(1);
+        let mut position = epoch;
+        while position >= cycle_length {
+            position -= cycle_length;
+            cycle_length *= self.t_mult.max(1);
+        }
+
+        cosine(
+            initial_learning_rate,
+            self.min_learning_rate,
+            position as f32 / cycle_length as f32,
+        )
+    }
+}
+
+/// Multiplies the learning rate by `factor` once the monitored loss has not improved by
+/// more than `threshold` for `patience` epochs, never going below `min_learning_rate`.
+pub struct ReduceOnPlateau {
+    pub factor: f32,
+    pub patience: usize,
+    pub threshold: f32,
+    pub min_learning_rate: f32,
+    current_learning_rate: Option<f32>,
+    best_loss: f32,
+    epochs_without_improvement: usize,
+}
+
+impl ReduceOnPlateau {
+    pub fn new(factor: f32, patience: usize) -> ReduceOnPlateau {
+        ReduceOnPlateau {
+            factor,
+            patience,
+            threshold: 1e-4,
+            min_learning_rate: 0.0,
+            current_learning_rate: None,
+            best_loss: f32::INFINITY,
+            epochs_without_improvement: 0,
+        }
+    }
+}
+
+impl LearningRateSchedule for ReduceOnPlateau {
+    fn learning_rate(&mut self, _: usize, initial_learning_rate: f32, loss: Option<f32>) -> f32 {
+        let mut learning_rate = self.current_learning_rate.unwrap_or(initial_learning_rate);
+
+        if let Some(loss) = loss {
+            if loss < self.best_loss - self.threshold {
+                self.best_loss = loss;
+                self.epochs_without_improvement = 0;
+            } else {
+                self.epochs_without_improvement += 1;
+            }
+
+            if self.epochs_without_improvement > self.patience {
+                learning_rate = (learning_rate * self.factor).max(self.min_learning_rate);
+                self.epochs_without_improvement = 0;
+            }
+        }
+
+        self.current_learning_rate = Some(learning_rate);
+        learning_rate
+    }
+}
+
+fn cosine(initial_learning_rate: f32, min_learning_rate: f32, progress: f32) -> f32 {
+    min_learning_rate
+        + (initial_learning_rate - min_learning_rate) * (1.0 + (PI * progress).cos()) / 2.0
+}
@@ -0,0 +1,67 @@
+use nalgebra::DMatrix;
+
+use crate::optim::optimizer::{state_for, Optimizer};
+
+/// Stochastic gradient descent, optionally with classical or Nesterov momentum.
+pub struct SGD {
+    pub learning_rate: f32,
+    pub momentum: f32,
+    pub nesterov: bool,
+    velocity: Option<DMatrix<f32>>,
+}
+
+impl SGD {
+    pub fn new(learning_rate: f32) -> SGD {
+        SGD {
+            learning_rate,
+            momentum: 0.0,
+            nesterov: false,
+            velocity: None,
+        }
+    }
+
+    pub fn with_momentum(learning_rate: f32, momentum: f32) -> SGD {
+        SGD {
+            momentum,
+            ..SGD::new(learning_rate)
+        }
+    }
+
+    pub fn nesterov(learning_rate: f32, momentum: f32) -> SGD {
+        SGD {
+            momentum,
+            nesterov: true,
+            ..SGD::new(learning_rate)
+        }
+    }
+}
+
+impl Optimizer for SGD {
+    fn step(&mut self, parameters: &mut DMatrix<f32>, gradient: &DMatrix<f32>) {
+        if self.momentum == 0.0 {
+            *parameters -= self.learning_rate * gradient;
+            return;
+        }
+
+        let velocity = state_for(&mut self.velocity, parameters.shape());
+        *velocity = self.momentum * &*velocity - self.learning_rate * gradient;
+
+        if self.nesterov {
+            *parameters += self.momentum * &*velocity - self.learning_rate * gradient;
+        } else {
+            *parameters += &*velocity;
+        }
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn reset(&mut self) {
+        self.velocity = None;
+    }
+}
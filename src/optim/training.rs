@@ -0,0 +1,294 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::optim::callbacks::Callback;
+use crate::optim::optimizer::Optimizer;
+use crate::optim::schedules::LearningRateSchedule;
+use crate::utils::utils::{batch_indices_with_rng, slice_by_row, validate_sample_weight};
+
+/// A model trained by first-order optimization over a matrix of parameters (a single
+/// column for single-output models); `gradient` has the shape of `parameters`.
+///
+/// With a `sample_weight` column the loss is the weighted mean of the per-sample losses.
+pub trait GradientModel {
+    fn parameters(&self) -> DMatrix<f32>;
+
+    fn set_parameters(&mut self, parameters: &DMatrix<f32>);
+
+    fn loss(&self, x: &DMatrix<f32>, y: &DMatrix<f32>, sample_weight: Option<&DMatrix<f32>>)
+        -> f32;
+
+    /// Gradient of `loss(x, y, sample_weight)` with respect to `parameters()`.
+    fn gradient(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> DMatrix<f32>;
+}
+
+/// Configuration of the training loop shared by every gradient-trained model.
+///
+/// `batch_size` of `None` trains on the full dataset each step, `Some(1)` is pure
+/// stochastic gradient descent and anything in between is mini-batch training.
+///
+/// Training stops before `epochs` once the monitored loss (the validation loss when
+/// `validation_fraction` is set, the training loss otherwise) has not improved by more
+/// than `tolerance` for `patience` consecutive epochs. Early stopping is disabled while
+/// both `tolerance` and `validation_fraction` are `None`. With `restore_best_weights`
+/// the model ends up with the parameters of its best epoch, but only when early stopping
+/// is enabled; otherwise it keeps the parameters of the last epoch.
+///
+/// An optional `schedule` sets the optimizer's learning rate at the start of each epoch,
+/// starting from the rate the optimizer was created with, and `callbacks` are invoked
+/// in order around every epoch. `seed` makes the validation split and the batch
+/// shuffling reproducible.
+pub struct FitConfig {
+    pub epochs: usize,
+    pub batch_size: Option<usize>,
+    pub shuffle: bool,
+    pub tolerance: Option<f32>,
+    pub validation_fraction: Option<f32>,
+    pub patience: usize,
+    pub restore_best_weights: bool,
+    pub schedule: Option<Box<dyn LearningRateSchedule>>,
+    pub callbacks: Vec<Box<dyn Callback>>,
+    pub seed: Option<u64>,
+}
+
+impl FitConfig {
+    pub fn new(epochs: usize) -> FitConfig {
+        FitConfig {
+            epochs,
+            batch_size: None,
+            shuffle: true,
+            tolerance: None,
+            validation_fraction: None,
+            patience: 5,
+            restore_best_weights: true,
+            schedule: None,
+            callbacks: Vec::new(),
+            seed: None,
+        }
+    }
+
+    pub fn mini_batch(epochs: usize, batch_size: usize) -> FitConfig {
+        FitConfig {
+            batch_size: Some(batch_size),
+            ..FitConfig::new(epochs)
+        }
+    }
+
+    pub fn stochastic(epochs: usize) -> FitConfig {
+        FitConfig::mini_batch(epochs, 1)
+    }
+
+    pub fn with_shuffle(mut self, shuffle: bool) -> FitConfig {
+        self.shuffle = shuffle;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> FitConfig {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn with_validation(mut self, validation_fraction: f32, patience: usize) -> FitConfig {
+        self.validation_fraction = Some(validation_fraction);
+        self.patience = patience;
+        self
+    }
+
+    pub fn with_restore_best_weights(mut self, restore_best_weights: bool) -> FitConfig {
+        self.restore_best_weights = restore_best_weights;
+        self
+    }
+
+    pub fn with_schedule<S: LearningRateSchedule + 'static>(mut self, schedule: S) -> FitConfig {
+        self.schedule = Some(Box::new(schedule));
+        self
+    }
+
+    pub fn with_callback<C: Callback + 'static>(mut self, callback: C) -> FitConfig {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> FitConfig {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Per-epoch record of a training run returned by [`train`].
+///
+/// `validation_loss` is empty unless a validation fraction was configured and
+/// `best_epoch` is the epoch whose parameters were kept when restoring the best weights.
+#[derive(Clone, Debug, Default)]
+pub struct TrainingHistory {
+    pub train_loss: Vec<f32>,
+    pub validation_loss: Vec<f32>,
+    pub learning_rate: Vec<f32>,
+    pub best_epoch: Option<usize>,
+    pub stopped_early: bool,
+}
+
+/// Runs the configured training loop, returning an error as soon as the training or
+/// validation loss stops being finite. A diverged model gets back the parameters it had
+/// before the call.
+pub fn train<M: GradientModel + ?Sized>(
+    model: &mut M,
+    optimizer: &mut dyn Optimizer,
+    x: &DMatrix<f32>,
+    y: &DMatrix<f32>,
+    sample_weight: Option<&DMatrix<f32>>,
+    config: &mut FitConfig,
+) -> Result<TrainingHistory, String> {
+    if x.nrows() != y.nrows() {
+        return Err(format!(
+            "x and y should have the same number of samples. |x|: {}, |y|: {}",
+            x.nrows(),
+            y.nrows()
+        ));
+    }
+    if let Some(sample_weight) = sample_weight {
+        validate_sample_weight(sample_weight, x.nrows())?;
+    }
+
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let (train_indices, validation_indices) = match config.validation_fraction {
+        Some(fraction) => {
+            let n_validation = ((x.nrows() as f32) * fraction) as usize;
+            if fraction <= 0.0 || fraction >= 1.0 || n_validation < 1 {
+                return Err(format!(
+                    "validation_fraction should leave at least one sample on each side, got {}",
+                    fraction
+                ));
+            }
+            let indices = batch_indices_with_rng(x.nrows(), x.nrows(), true, &mut rng).remove(0);
+            (
+                indices[n_validation..].to_vec(),
+                Some(indices[..n_validation].to_vec()),
+            )
+        }
+        None => ((0..x.nrows()).collect(), None),
+    };
+
+    let x_train = slice_by_row(x, &train_indices);
+    let y_train = slice_by_row(y, &train_indices);
+    let w_train = sample_weight.map(|w| slice_by_row(w, &train_indices));
+    let validation = validation_indices.map(|indices| {
+        (
+            slice_by_row(x, &indices),
+            slice_by_row(y, &indices),
+            sample_weight.map(|w| slice_by_row(w, &indices)),
+        )
+    });
+
+    let early_stopping = config.tolerance.is_some() || config.validation_fraction.is_some();
+    let tolerance = config.tolerance.unwrap_or(0.0);
+    let batch_size = config.batch_size.unwrap_or(x_train.nrows());
+
+    let initial_parameters = model.parameters();
+    let mut parameters = initial_parameters.clone();
+    let mut best_parameters = parameters.clone();
+    let mut best_loss = f32::INFINITY;
+    let mut epochs_without_improvement = 0;
+    let initial_learning_rate = optimizer.learning_rate();
+    let mut last_loss = None;
+    let mut history = TrainingHistory::default();
+
+    for epoch in 0..config.epochs {
+        if let Some(schedule) = config.schedule.as_mut() {
+            optimizer.set_learning_rate(schedule.learning_rate(
+                epoch,
+                initial_learning_rate,
+                last_loss,
+            ));
+        }
+        for callback in config.callbacks.iter_mut() {
+            callback.on_epoch_start(epoch);
+        }
+
+        if batch_size >= x_train.nrows() {
+            let gradient = model.gradient(&x_train, &y_train, w_train.as_ref());
+            optimizer.step(&mut parameters, &gradient);
+            model.set_parameters(&parameters);
+        } else {
+            for batch in
+                batch_indices_with_rng(x_train.nrows(), batch_size, config.shuffle, &mut rng)
+            {
+                let x_batch = slice_by_row(&x_train, &batch);
+                let y_batch = slice_by_row(&y_train, &batch);
+                let w_batch = w_train.as_ref().map(|w| slice_by_row(w, &batch));
+                if w_batch.as_ref().is_some_and(|w| w.sum() <= 0.0) {
+                    continue;
+                }
+
+                let gradient = model.gradient(&x_batch, &y_batch, w_batch.as_ref());
+                optimizer.step(&mut parameters, &gradient);
+                model.set_parameters(&parameters);
+            }
+        }
+
+        let train_loss = model.loss(&x_train, &y_train, w_train.as_ref());
+        if !train_loss.is_finite() {
+            model.set_parameters(&initial_parameters);
+            return Err(format!(
+                "training diverged at epoch {}: loss is {}; try a smaller learning rate",
+                epoch, train_loss
+            ));
+        }
+
+        let monitored_loss = match &validation {
+            Some((x_validation, y_validation, w_validation)) => {
+                let validation_loss = model.loss(x_validation, y_validation, w_validation.as_ref());
+                if !validation_loss.is_finite() {
+                    model.set_parameters(&initial_parameters);
+                    return Err(format!(
+                        "training diverged at epoch {}: validation loss is {}",
+                        epoch, validation_loss
+                    ));
+                }
+                history.validation_loss.push(validation_loss);
+                validation_loss
+            }
+            _ => train_loss,
+        };
+        last_loss = Some(monitored_loss);
+        history.train_loss.push(train_loss);
+        history.learning_rate.push(optimizer.learning_rate());
+
+        if monitored_loss < best_loss - tolerance {
+            epochs_without_improvement = 0;
+        } else {
+            epochs_without_improvement += 1;
+        }
+        if monitored_loss < best_loss {
+            best_loss = monitored_loss;
+            best_parameters = parameters.clone();
+            history.best_epoch = Some(epoch);
+        }
+
+        let mut keep_training = true;
+        for callback in config.callbacks.iter_mut() {
+            keep_training &= callback.on_epoch_end(epoch, &history, &parameters);
+        }
+
+        if !keep_training || (early_stopping && epochs_without_improvement >= config.patience) {
+            history.stopped_early = epoch + 1 < config.epochs;
+            break;
+        }
+    }
+
+    if early_stopping && config.restore_best_weights && best_loss.is_finite() {
+        model.set_parameters(&best_parameters);
+    }
+
+    Ok(history)
+}
@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+
+use nalgebra::DMatrix;
+
+use crate::utils::stats::{f_cdf, student_t_cdf, student_t_ppf};
+use crate::utils::utils::append_column;
+
+/// Inference for a single estimated parameter. The interval is at the summary's
+/// `confidence_level` and the p-value is two-sided, for the null hypothesis of a zero
+/// parameter.
+#[derive(Clone, Debug)]
+pub struct CoefficientSummary {
+    pub estimate: f32,
+    pub standard_error: f32,
+    pub t_statistic: f32,
+    pub p_value: f32,
+    pub confidence_interval: (f32, f32),
+}
+
+/// Ordinary least squares inference for a fitted linear model `y = x * w + b`, under the
+/// usual assumption of independent Gaussian errors with constant variance.
+#[derive(Clone, Debug)]
+pub struct RegressionSummary {
+    pub coefficients: Vec<CoefficientSummary>,
+    pub bias: CoefficientSummary,
+    pub confidence_level: f32,
+    pub degrees_of_freedom: usize,
+    pub residual_standard_error: f32,
+    pub r_squared: f32,
+    pub adjusted_r_squared: f32,
+    pub f_statistic: f32,
+    pub f_p_value: f32,
+    pub log_likelihood: f32,
+    pub aic: f32,
+    pub bic: f32,
+    /// `(A^T A)^-1` for the design `A = [x, 1]`, kept to compute intervals for new points.
+    pub unscaled_covariance: DMatrix<f32>,
+}
+
+impl RegressionSummary {
+    pub fn new(
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        coefficients: &[f32],
+        bias: f32,
+        confidence_level: f32,
+    ) -> Result<RegressionSummary, String> {
+        if y.ncols() != 1 {
+            return Err(format!(
+                "inference needs a single target column, got {}",
+                y.ncols()
+            ));
+        }
+        if confidence_level <= 0.0 || confidence_level >= 1.0 {
+            return Err("confidence_level should be between 0 and 1".to_string());
+        }
+
+        let nrows = x.nrows();
+        let num_parameters = x.ncols() + 1;
+        if nrows <= num_parameters {
+            return Err(format!(
+                "inference needs more samples ({}) than parameters ({})",
+                nrows, num_parameters
+            ));
+        }
+        let degrees_of_freedom = nrows - num_parameters;
+
+        let a = append_column(x, vec![1.; nrows]);
+        let unscaled_covariance = (a.transpose() * &a)
+            .try_inverse()
+            .ok_or("x^T x is singular; the coefficients are not identifiable")?;
+
+        let mut parameters: Vec<f32> = coefficients.to_vec();
+        parameters.push(bias);
+        let residuals = y - &a * DMatrix::from_vec(num_parameters, 1, parameters.clone());
+
+        let rss = residuals.norm_squared() as f64;
+        let y_mean = y.mean();
+        let tss = y.map(|v| (v - y_mean).powf(2.0)).sum() as f64;
+        let sigma_squared = rss / degrees_of_freedom as f64;
+
+        let t_critical = student_t_ppf(
+            1.0 - (1.0 - confidence_level as f64) / 2.0,
+            degrees_of_freedom as f64,
+        );
+        let mut summaries: Vec<CoefficientSummary> = (0..num_parameters)
+            .map(|j| {
+                let standard_error = (sigma_squared * unscaled_covariance[(j, j)] as f64).sqrt();
+                let t_statistic = parameters[j] as f64 / standard_error;
+                let p_value =
+                    2.0 * (1.0 - student_t_cdf(t_statistic.abs(), degrees_of_freedom as f64));
+
+                CoefficientSummary {
+                    estimate: parameters[j],
+                    standard_error: standard_error as f32,
+                    t_statistic: t_statistic as f32,
+                    p_value: p_value as f32,
+                    confidence_interval: (
+                        (parameters[j] as f64 - t_critical * standard_error) as f32,
+                        (parameters[j] as f64 + t_critical * standard_error) as f32,
+                    ),
+                }
+            })
+            .collect();
+        let bias_summary = summaries.pop().unwrap();
+
+        let r_squared = 1.0 - rss / tss;
+        let adjusted_r_squared =
+            1.0 - (1.0 - r_squared) * (nrows - 1) as f64 / degrees_of_freedom as f64;
+
+        let (f_statistic, f_p_value) = if x.ncols() > 0 {
+            let f_statistic = ((tss - rss) / x.ncols() as f64) / sigma_squared;
+            let p_value = 1.0 - f_cdf(f_statistic, x.ncols() as f64, degrees_of_freedom as f64);
+            (f_statistic, p_value)
+        } else {
+            (f64::NAN, f64::NAN)
+        };
+
+        let n = nrows as f64;
+        let log_likelihood = -n / 2.0 * ((2.0 * PI).ln() + (rss / n).ln() + 1.0);
+        let aic = 2.0 * num_parameters as f64 - 2.0 * log_likelihood;
+        let bic = num_parameters as f64 * n.ln() - 2.0 * log_likelihood;
+
+        Ok(RegressionSummary {
+            coefficients: summaries,
+            bias: bias_summary,
+            confidence_level,
+            degrees_of_freedom,
+            residual_standard_error: sigma_squared.sqrt() as f32,
+            r_squared: r_squared as f32,
+            adjusted_r_squared: adjusted_r_squared as f32,
+            f_statistic: f_statistic as f32,
+            f_p_value: f_p_value as f32,
+            log_likelihood: log_likelihood as f32,
+            aic: aic as f32,
+            bic: bic as f32,
+            unscaled_covariance,
+        })
+    }
+
+    /// Interval for the mean response at each row of `x`.
+    pub fn confidence_interval(&self, x: &DMatrix<f32>) -> Vec<(f32, f32)> {
+        self.intervals(x, 0.0)
+    }
+
+    /// Interval for a new observation at each row of `x`, which also accounts for the
+    /// noise around the mean response.
+    pub fn prediction_interval(&self, x: &DMatrix<f32>) -> Vec<(f32, f32)> {
+        self.intervals(x, 1.0)
+    }
+
+    fn intervals(&self, x: &DMatrix<f32>, noise: f32) -> Vec<(f32, f32)> {
+        let a = append_column(x, vec![1.; x.nrows()]);
+        let mut parameters: Vec<f32> = self.coefficients.iter().map(|c| c.estimate).collect();
+        parameters.push(self.bias.estimate);
+        let y_hat = &a * DMatrix::from_vec(parameters.len(), 1, parameters);
+
+        let t_critical = student_t_ppf(
+            1.0 - (1.0 - self.confidence_level as f64) / 2.0,
+            self.degrees_of_freedom as f64,
+        ) as f32;
+
+        return (0..a.nrows())
+            .map(|i| {
+                let row = a.row(i);
+                let leverage = (row * &self.unscaled_covariance * row.transpose())[(0, 0)];
+                let margin = t_critical * self.residual_standard_error * (noise + leverage).sqrt();
+                (y_hat[(i, 0)] - margin, y_hat[(i, 0)] + margin)
+            })
+            .collect();
+    }
+}
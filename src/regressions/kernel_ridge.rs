@@ -0,0 +1,72 @@
+use nalgebra::DMatrix;
+
+use crate::kernels::kernel::Kernel;
+use crate::utils::utils::validate_sample_weight;
+
+/// Ridge regression in the feature space of a kernel: the dual coefficients solve
+/// `(K + alpha * W^-1) c = y` and predictions are `K(x, x_fit) c`.
+///
+/// Unlike `RBFRegression`, every training sample acts as a center and the fit is
+/// regularised by `alpha` instead of being a plain least squares projection.
+/// `dual_coef` has one row per training sample and one column per target.
+pub struct KernelRidge {
+    pub alpha: f32,
+    pub kernel: Box<dyn Kernel>,
+    pub x_fit: DMatrix<f32>,
+    pub dual_coef: DMatrix<f32>,
+}
+
+impl KernelRidge {
+    pub fn new<K: Kernel + 'static>(alpha: f32, kernel: K) -> KernelRidge {
+        KernelRidge {
+            alpha,
+            kernel: Box::new(kernel),
+            x_fit: DMatrix::zeros(0, 0),
+            dual_coef: DMatrix::zeros(0, 0),
+        }
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        return self.fit_weighted(x, y, &DMatrix::from_element(x.nrows(), 1, 1.0));
+    }
+
+    /// Samples with a larger weight are fitted more closely; zero weights are not
+    /// allowed since their regularisation term would be infinite.
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> Result<(), String> {
+        validate_sample_weight(sample_weight, x.nrows())?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        if self.alpha <= 0.0 || sample_weight.iter().any(|w| *w <= 0.0) {
+            return Err("alpha and every sample weight should be positive".to_string());
+        }
+
+        let mut system = self.kernel.gram(x, x);
+        for i in 0..x.nrows() {
+            system[(i, i)] += self.alpha / sample_weight[(i, 0)];
+        }
+        self.dual_coef = match system.clone().cholesky() {
+            Some(cholesky) => cholesky.solve(y),
+            // Kernels such as the sigmoid one are not always positive definite.
+            None => system
+                .lu()
+                .solve(y)
+                .ok_or("the regularised kernel matrix is singular".to_string())?,
+        };
+        self.x_fit = x.clone();
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return self.kernel.gram(x, &self.x_fit) * &self.dual_coef;
+    }
+}
@@ -1,41 +1,190 @@
-use crate::utils::utils::append_column;
+use crate::online::incremental::IncrementalModel;
+use crate::regressions::inference::RegressionSummary;
+use crate::utils::solvers::{LeastSquaresSolver, SolverDiagnostics};
+use crate::utils::utils::{append_column, scale_rows_by_sqrt_weight, validate_sample_weight};
 use nalgebra::DMatrix;
 
+/// Ordinary (or weighted) least squares. `coefficients` has one row per feature and one
+/// column per target column of `y`, and `bias` one entry per target.
+///
+/// `partial_fit` updates the fit sample by sample with recursive least squares, which
+/// tracks the inverse of the (weighted) Gram matrix `[x 1]^T [x 1]`. With a
+/// `forgetting_factor` below 1 older samples weigh exponentially less, letting the model
+/// follow a drifting relationship.
 pub struct LinearRegression {
-    pub coefficients: Option<Vec<f32>>,
-    pub bias: Option<f32>,
+    pub coefficients: Option<DMatrix<f32>>,
+    pub bias: Option<Vec<f32>>,
+    pub solver: LeastSquaresSolver,
+    pub diagnostics: Option<SolverDiagnostics>,
+    pub forgetting_factor: f32,
+    inverse_gram: Option<DMatrix<f32>>,
+    weighted: bool,
 }
 
+/// Ridge added to the Gram matrix of the first batch of `partial_fit`, so that a batch
+/// with fewer samples than features still starts the recursion.
+const RLS_REGULARIZATION: f32 = 1e-4;
+
 impl LinearRegression {
     pub fn new() -> LinearRegression {
+        LinearRegression::with_solver(LeastSquaresSolver::default())
+    }
+
+    pub fn with_solver(solver: LeastSquaresSolver) -> LinearRegression {
         LinearRegression {
             coefficients: None,
             bias: None,
+            solver,
+            diagnostics: None,
+            forgetting_factor: 1.0,
+            inverse_gram: None,
+            weighted: false,
         }
     }
 
-    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) {
+    pub fn with_forgetting_factor(mut self, forgetting_factor: f32) -> LinearRegression {
+        self.forgetting_factor = forgetting_factor;
+        self
+    }
+
+    /// Least squares fit with `self.solver`, whose errors (e.g. `CHOLESKY` on collinear
+    /// features) are returned as is.
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        return self.fit_weighted(x, y, &DMatrix::from_element(x.nrows(), 1, 1.0));
+    }
+
+    /// Weighted least squares: minimises `sum(w_i * (y_i - x_i * w - b)^2)`.
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> Result<(), String> {
         let (nrows, _num_attributes) = x.shape();
+        validate_sample_weight(sample_weight, nrows)?;
         let a: DMatrix<f32> = append_column(x, vec![1.; nrows]);
 
-        let r = a.svd(true, true).solve(y, 1.0).unwrap();
+        let weighted_a = scale_rows_by_sqrt_weight(&a, sample_weight);
+        let (r, diagnostics) = self
+            .solver
+            .solve(&weighted_a, &scale_rows_by_sqrt_weight(y, sample_weight))?;
+        self.inverse_gram = (weighted_a.transpose() * &weighted_a).try_inverse();
+
+        self.coefficients = Some(r.rows(0, r.nrows() - 1).into_owned());
+        self.bias = Some(r.row(r.nrows() - 1).iter().cloned().collect());
+        self.diagnostics = Some(diagnostics);
+        self.weighted = sample_weight.max() != sample_weight.min();
+        Ok(())
+    }
+
+    /// Recursive least squares update with a new batch. The first call (or a call after
+    /// a `fit` whose Gram matrix was singular) starts from a slightly regularised least
+    /// squares fit of the batch.
+    pub fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        if self.forgetting_factor <= 0.0 || self.forgetting_factor > 1.0 {
+            return Err(format!(
+                "forgetting_factor should be in (0, 1], got {}",
+                self.forgetting_factor
+            ));
+        }
+        let a: DMatrix<f32> = append_column(x, vec![1.; x.nrows()]);
+        if self.forgetting_factor < 1.0 {
+            self.weighted = true;
+        }
+
+        let (mut weights, mut inverse_gram) = match (&self.coefficients, &self.inverse_gram) {
+            (Some(coefficients), Some(inverse_gram)) => {
+                if coefficients.nrows() != x.ncols() || coefficients.ncols() != y.ncols() {
+                    return Err(format!(
+                        "the model was fitted on {} features and {} targets, got {} and {}",
+                        coefficients.nrows(),
+                        coefficients.ncols(),
+                        x.ncols(),
+                        y.ncols()
+                    ));
+                }
+                let mut weights = coefficients.clone().insert_row(x.ncols(), 0.0);
+                for (j, b) in self.bias.as_ref().unwrap().iter().enumerate() {
+                    weights[(x.ncols(), j)] = *b;
+                }
+                (weights, inverse_gram.clone())
+            }
+            _ => {
+                let mut gram = a.transpose() * &a;
+                for i in 0..gram.nrows() {
+                    gram[(i, i)] += RLS_REGULARIZATION;
+                }
+                let inverse_gram = gram
+                    .try_inverse()
+                    .ok_or("the Gram matrix of the first batch is singular".to_string())?;
+                let weights = &inverse_gram * a.transpose() * y;
+                self.set_weights(&weights);
+                self.inverse_gram = Some(inverse_gram);
+                return Ok(());
+            }
+        };
 
-        let mut coef = DMatrix::<f32>::zeros(r.nrows(), 1);
-        for i in 0..coef.nrows() - 1 {
-            coef[(i, 0)] = r[(i, 0)];
+        let lambda = self.forgetting_factor;
+        for i in 0..a.nrows() {
+            let row = a.row(i);
+            let projected = &inverse_gram * row.transpose();
+            let gain = &projected / (lambda + (row * &projected)[(0, 0)]);
+            let error = y.row(i) - row * &weights;
+            weights += &gain * error;
+            inverse_gram = (inverse_gram - &gain * projected.transpose()) / lambda;
         }
-        self.coefficients = Some((0..r.nrows() - 1).map(|idx| r[(idx, 0)] as f32).collect());
-        self.bias = Some(r[(r.nrows() - 1, 0)]);
+        self.set_weights(&weights);
+        self.inverse_gram = Some(inverse_gram);
+        Ok(())
+    }
+
+    /// Splits weights with the bias as their last row into coefficients and bias.
+    fn set_weights(&mut self, weights: &DMatrix<f32>) {
+        let n = weights.nrows() - 1;
+        self.coefficients = Some(weights.rows(0, n).into_owned());
+        self.bias = Some(weights.row(n).iter().cloned().collect());
     }
 
+    /// Predictions with one column per target.
     pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
-        let values = DMatrix::from_vec(
-            self.coefficients.as_ref().unwrap().len(),
-            1,
-            self.coefficients.as_ref().unwrap().clone(),
-        );
+        let mut y_hat = x * self.coefficients.as_ref().unwrap();
+        let bias = self.bias.as_ref().unwrap();
+        for (mut column, b) in y_hat.column_iter_mut().zip(bias.iter()) {
+            column.add_scalar_mut(*b);
+        }
+
+        return y_hat;
+    }
 
-        return (x * values).add_scalar(self.bias.as_ref().unwrap().clone());
+    /// Standard errors, tests and goodness of fit of the fitted model on `(x, y)`. Only
+    /// available for a single target and an unweighted fit: the ordinary least squares
+    /// formulas do not hold for non-uniform sample weights or a forgetting factor.
+    pub fn summary(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        confidence_level: f32,
+    ) -> Result<RegressionSummary, String> {
+        if self.weighted {
+            return Err("inference is not available for a weighted fit".to_string());
+        }
+        match (&self.coefficients, &self.bias) {
+            (Some(coefficients), Some(bias)) if coefficients.ncols() == 1 => {
+                RegressionSummary::new(x, y, coefficients.as_slice(), bias[0], confidence_level)
+            }
+            (Some(coefficients), Some(_)) => Err(format!(
+                "inference needs a single target, the model has {}",
+                coefficients.ncols()
+            )),
+            _ => Err("fit(..) must be called first".to_string()),
+        }
     }
 }
 
@@ -44,3 +193,9 @@ impl Default for LinearRegression {
         Self::new()
     }
 }
+
+impl IncrementalModel for LinearRegression {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        LinearRegression::partial_fit(self, x, y)
+    }
+}
@@ -1,3 +1,5 @@
+pub mod inference;
+pub mod kernel_ridge;
 pub mod linear_regression;
 pub mod polynomial_regression;
 pub mod rbf_regression;
@@ -1,82 +1,295 @@
+use crate::losses::loss::Loss;
+use crate::optim::{
+    optimizer::Optimizer,
+    sgd::SGD,
+    training::{train, FitConfig, GradientModel, TrainingHistory},
+};
 use crate::utils::{
-    stats::{update_weights_huber, update_weights_mae, update_weights_mse},
-    types::TypeRegression,
-    utils::expand_matrix,
+    preprocessing::StandardScaler,
+    solvers::{LeastSquaresSolver, SolverDiagnostics},
+    stats::{gradient_from_derivatives_per_target, gradient_penalty, penalty},
+    types::Penalty,
+    utils::{expand_matrix, scale_rows_by_sqrt_weight, validate_sample_weight},
 };
 use nalgebra::DMatrix;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
+/// Regression on the powers `x^1 .. x^(degree - 1)` plus a constant column, trained on
+/// any [`Loss`] (a `TypeRegression` or a user-defined loss).
+///
+/// With `scale_features` the powered columns are standardized using statistics learned
+/// at fit time, which keeps high degrees well conditioned; `coefficients` then refer to
+/// the standardized columns. `penalty` regularises every coefficient except the one of
+/// the constant column.
+///
+/// `y` may have several target columns: `coefficients` then holds one column and `bias`
+/// one entry per target, and they are re-initialised when the number of targets changes.
+/// The random initial coefficients are reproducible once `seed` is set.
 pub struct PolynomialRegression {
     pub coefficients: DMatrix<f32>,
-    pub bias: f32,
+    pub bias: DMatrix<f32>,
     pub degree: usize,
-    pub type_regression: TypeRegression,
+    pub loss: Box<dyn Loss>,
+    pub penalty: Option<Penalty>,
+    pub scale_features: bool,
+    pub scaler: Option<StandardScaler>,
+    pub solver: LeastSquaresSolver,
+    pub seed: Option<u64>,
 }
 
 impl PolynomialRegression {
-    pub fn new(degree: usize, type_regression: TypeRegression) -> PolynomialRegression {
+    pub fn new<L: Loss + 'static>(degree: usize, loss: L) -> PolynomialRegression {
         PolynomialRegression {
-            coefficients: DMatrix::from_vec(
-                degree,
-                1,
-                (0..degree)
-                    .map(|_| rand::thread_rng().gen::<f32>())
-                    .collect(),
-            ),
-            bias: 0.0,
+            coefficients: random_coefficients(degree, 1, None),
+            bias: DMatrix::zeros(1, 1),
             degree,
-            type_regression,
+            loss: Box::new(loss),
+            penalty: None,
+            scale_features: false,
+            scaler: None,
+            solver: LeastSquaresSolver::default(),
+            seed: None,
         }
     }
 
+    pub fn with_penalty(mut self, penalty: Penalty) -> PolynomialRegression {
+        self.penalty = Some(penalty);
+        self
+    }
+
+    pub fn with_feature_scaling(mut self) -> PolynomialRegression {
+        self.scale_features = true;
+        self
+    }
+
+    /// Seeds the initial coefficients, drawing them again from the seeded generator.
+    pub fn with_seed(mut self, seed: u64) -> PolynomialRegression {
+        self.seed = Some(seed);
+        self.coefficients = random_coefficients(self.degree, self.coefficients.ncols(), self.seed);
+        self
+    }
+
+    /// Predictions with one column per target.
     pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
-        let expanded_matrix = if x.shape().1 != self.degree {
-            expand_matrix(x, self.degree)
+        let mut y_hat = self.expand(x) * &self.coefficients;
+        for mut row in y_hat.row_iter_mut() {
+            row += &self.bias;
+        }
+
+        return y_hat;
+    }
+
+    pub fn fit(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        epochs: usize,
+        lr: f32,
+    ) -> Result<TrainingHistory, String> {
+        self.fit_with_config(x, y, &mut SGD::new(lr), &mut FitConfig::new(epochs))
+    }
+
+    pub fn fit_with_optimizer(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        epochs: usize,
+        optimizer: &mut dyn Optimizer,
+    ) -> Result<TrainingHistory, String> {
+        self.fit_with_config(x, y, optimizer, &mut FitConfig::new(epochs))
+    }
+
+    pub fn fit_with_config(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        optimizer: &mut dyn Optimizer,
+        config: &mut FitConfig,
+    ) -> Result<TrainingHistory, String> {
+        self.fit_scaler(x);
+        self.init_targets(y.ncols());
+        let expanded_matrix = self.expand(x);
+        train(self, optimizer, &expanded_matrix, y, None, config)
+    }
+
+    /// Gradient-based fit where each sample's loss counts `sample_weight[i]` times.
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+        optimizer: &mut dyn Optimizer,
+        config: &mut FitConfig,
+    ) -> Result<TrainingHistory, String> {
+        self.fit_scaler(x);
+        self.init_targets(y.ncols());
+        let expanded_matrix = self.expand(x);
+        train(
+            self,
+            optimizer,
+            &expanded_matrix,
+            y,
+            Some(sample_weight),
+            config,
+        )
+    }
+
+    /// Exact least squares fit, or ridge regression with an `L2` penalty, solved with
+    /// `self.solver`. Only available for the squared error and without L1 terms.
+    pub fn fit_closed_form(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+    ) -> Result<SolverDiagnostics, String> {
+        self.fit_closed_form_weighted(x, y, &DMatrix::from_element(x.nrows(), 1, 1.0))
+    }
+
+    /// Weighted least squares (or weighted ridge) version of [`Self::fit_closed_form`].
+    pub fn fit_closed_form_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> Result<SolverDiagnostics, String> {
+        validate_sample_weight(sample_weight, x.nrows())?;
+        if !self.loss.is_squared_error() {
+            return Err(format!(
+                "closed-form fitting needs the squared error, got {}",
+                self.loss.name()
+            ));
+        }
+        let alpha = match self.penalty {
+            None => 0.0,
+            Some(Penalty::L2(alpha)) => alpha,
+            Some(penalty) => {
+                return Err(format!(
+                    "{:?} has no closed form; use a gradient-based fit",
+                    penalty
+                ))
+            }
+        };
+
+        self.fit_scaler(x);
+        // Rescaling the weights to sum to nrows keeps alpha on the same scale as the
+        // unweighted fit.
+        let sample_weight = sample_weight * (x.nrows() as f32 / sample_weight.sum());
+        let expanded_matrix = scale_rows_by_sqrt_weight(&self.expand(x), &sample_weight);
+        let y = scale_rows_by_sqrt_weight(y, &sample_weight);
+        let nrows = expanded_matrix.nrows();
+
+        // Ridge as an augmented least squares problem: minimising
+        // mse + alpha / 2 * |w|^2 adds sqrt(nrows * alpha / 2) * I below the design.
+        let (a, b) = if alpha > 0.0 {
+            let penalized = self.degree - 1;
+            let mut a = expanded_matrix.resize_vertically(nrows + penalized, 0.0);
+            for j in 0..penalized {
+                a[(nrows + j, j)] = (nrows as f32 * alpha / 2.0).sqrt();
+            }
+            (a, y.resize_vertically(nrows + penalized, 0.0))
         } else {
-            x.clone()
+            (expanded_matrix, y)
         };
-        let mut y_hat: Vec<f32> = Vec::new();
-        for i in 0..expanded_matrix.nrows() {
-            y_hat.push(
-                (0..expanded_matrix.ncols())
-                    .map(|j| expanded_matrix[(i, j)] * self.coefficients[(j, 0)])
-                    .sum(),
-            );
-
-            y_hat[i] += self.bias;
+
+        let (coefficients, diagnostics) = self.solver.solve(&a, &b)?;
+        self.bias = DMatrix::zeros(1, coefficients.ncols());
+        self.coefficients = coefficients;
+        Ok(diagnostics)
+    }
+
+    fn init_targets(&mut self, num_targets: usize) {
+        if self.coefficients.ncols() != num_targets {
+            self.coefficients = random_coefficients(self.degree, num_targets, self.seed);
+            self.bias = DMatrix::zeros(1, num_targets);
         }
+    }
 
-        return DMatrix::from_vec(x.nrows(), 1, y_hat);
+    fn fit_scaler(&mut self, x: &DMatrix<f32>) {
+        self.scaler = None;
+        if self.scale_features {
+            self.scaler = Some(StandardScaler::fit(&self.expand(x)));
+        }
     }
 
-    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>, epochs: usize, lr: f32) {
+    fn expand(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        if x.shape().1 == self.degree {
+            return x.clone();
+        }
+
         let expanded_matrix = expand_matrix(x, self.degree);
+        match &self.scaler {
+            Some(scaler) => scaler.transform(&expanded_matrix),
+            None => expanded_matrix,
+        }
+    }
 
-        match self.type_regression {
-            TypeRegression::MSE => {
-                for _ in 0..epochs {
-                    let y_hat = self.predict(&x);
-                    let (dw, db) = update_weights_mse(&expanded_matrix, &y, &y_hat, lr);
-                    self.coefficients += dw;
-                    self.bias += db;
-                }
-            }
-            TypeRegression::MAE => {
-                for _ in 0..epochs {
-                    let y_hat = self.predict(&x);
-                    let (dw, db) = update_weights_mae(&expanded_matrix, &y, &y_hat, lr);
-                    self.coefficients += dw;
-                    self.bias += db;
-                }
-            }
-            TypeRegression::HUBER => {
-                for _ in 0..epochs {
-                    let y_hat = self.predict(&x);
-                    let (dw, db) = update_weights_huber(&expanded_matrix, &y, &y_hat, lr, 1.0);
-                    self.coefficients += dw;
-                    self.bias += db;
-                }
+    /// Coefficients subject to the penalty, with the constant column's coefficient zeroed.
+    fn penalized_coefficients(&self) -> DMatrix<f32> {
+        let mut coefficients = self.coefficients.clone();
+        coefficients.row_mut(self.degree - 1).fill(0.0);
+        return coefficients;
+    }
+}
+
+impl GradientModel for PolynomialRegression {
+    /// Coefficients with the bias as an extra row, one column per target.
+    fn parameters(&self) -> DMatrix<f32> {
+        let mut parameters = self.coefficients.clone().insert_row(self.degree, 0.0);
+        parameters.row_mut(self.degree).copy_from(&self.bias);
+        return parameters;
+    }
+
+    fn set_parameters(&mut self, parameters: &DMatrix<f32>) {
+        self.coefficients = parameters.rows(0, self.degree).into_owned();
+        self.bias = parameters.rows(self.degree, 1).into_owned();
+    }
+
+    fn loss(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> f32 {
+        let y_hat = self.predict(x);
+        let regularization = match &self.penalty {
+            Some(p) => penalty(&self.penalized_coefficients(), p),
+            None => 0.0,
+        };
+
+        regularization
+            + match sample_weight {
+                Some(w) => self.loss.weighted_value(y, &y_hat, w),
+                None => self.loss.value(y, &y_hat),
             }
-        }
     }
+
+    fn gradient(
+        &self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: Option<&DMatrix<f32>>,
+    ) -> DMatrix<f32> {
+        let expanded_matrix = self.expand(x);
+        let y_hat = self.predict(&expanded_matrix);
+        let derivatives = self.loss.gradient(y, &y_hat);
+        let (dw, db) =
+            gradient_from_derivatives_per_target(&expanded_matrix, &derivatives, sample_weight);
+
+        let dw = match &self.penalty {
+            Some(p) => dw + gradient_penalty(&self.penalized_coefficients(), p),
+            None => dw,
+        };
+
+        let mut gradient = dw.insert_row(self.degree, 0.0);
+        gradient.row_mut(self.degree).copy_from(&db);
+        return gradient;
+    }
+}
+
+fn random_coefficients(degree: usize, num_targets: usize, seed: Option<u64>) -> DMatrix<f32> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    DMatrix::from_fn(degree, num_targets, |_, _| rng.gen::<f32>())
 }
@@ -2,15 +2,22 @@ use nalgebra::DMatrix;
 use rand::prelude::SliceRandom;
 use rand::thread_rng;
 
-use crate::utils::types::TypeFactoration;
-use crate::utils::utils::{expand_matrix, matmul};
+use crate::kernels::kernel::Kernel;
+use crate::utils::solvers::{LeastSquaresSolver, SolverDiagnostics};
+use crate::utils::types::{TypeFactoration, TypeKernel};
+use crate::utils::utils::{
+    expand_matrix, matmul, scale_rows_by_sqrt_weight, validate_sample_weight,
+};
 
+/// Gaussian radial basis function network. `weight` has one row per center and one
+/// column per target column of `y`.
 pub struct RBFRegression {
     pub num_center: usize,
     pub centers: DMatrix<f32>,
     pub beta: f32,
     pub weight: DMatrix<f32>,
-    pub type_factoration: Option<TypeFactoration>,
+    pub solver: LeastSquaresSolver,
+    pub diagnostics: Option<SolverDiagnostics>,
 }
 
 impl RBFRegression {
@@ -34,17 +41,32 @@ impl RBFRegression {
             centers: DMatrix::from_vec(num_center, num_cols, coefficients_centers),
             beta,
             weight: DMatrix::from_vec(num_center, 1, coefficients_weight),
-            type_factoration,
+            solver: LeastSquaresSolver::new(type_factoration.unwrap_or(TypeFactoration::SVD)),
+            diagnostics: None,
         }
     }
 
-    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) {
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        return self.fit_weighted(x, y, &DMatrix::from_element(x.nrows(), 1, 1.0));
+    }
+
+    /// Fits the output weights by weighted least squares. Centers are still drawn
+    /// uniformly from the samples with a non-zero weight.
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> Result<(), String> {
+        validate_sample_weight(sample_weight, x.nrows())?;
         let (_, n_columns) = self.centers.shape();
         let x = expand_matrix(&x, n_columns);
 
         let (num_rows, num_cols) = x.shape();
 
-        let mut index: Vec<usize> = (0..num_rows).collect();
+        let mut index: Vec<usize> = (0..num_rows)
+            .filter(|i| sample_weight[(*i, 0)] > 0.0)
+            .collect();
 
         index.shuffle(&mut thread_rng());
         let mut count = 0;
@@ -59,34 +81,15 @@ impl RBFRegression {
             }
         }
 
-        let gradient = calculate_gradient(&x, &self.centers, &self.beta);
+        let gram = TypeKernel::RBF(self.beta).gram(&x, &self.centers);
 
-        match self.type_factoration {
-            Some(TypeFactoration::SVD) => {
-                self.weight = matmul(&gradient.transpose(), &gradient)
-                    .svd(true, true)
-                    .solve(&matmul(&gradient.transpose(), &y), 1.0)
-                    .unwrap();
-            }
-            Some(TypeFactoration::QR) => {
-                self.weight = matmul(&gradient.transpose(), &gradient)
-                    .qr()
-                    .solve(&matmul(&gradient.transpose(), &y))
-                    .unwrap();
-            }
-            Some(TypeFactoration::LU) => {
-                self.weight = matmul(&gradient.transpose(), &gradient)
-                    .lu()
-                    .solve(&matmul(&gradient.transpose(), &y))
-                    .unwrap();
-            }
-            _ => {
-                self.weight = matmul(&gradient.transpose(), &gradient)
-                    .svd(true, true)
-                    .solve(&matmul(&gradient.transpose(), &y), 1.0)
-                    .unwrap();
-            }
-        }
+        let (weight, diagnostics) = self.solver.solve(
+            &scale_rows_by_sqrt_weight(&gram, sample_weight),
+            &scale_rows_by_sqrt_weight(y, sample_weight),
+        )?;
+        self.weight = weight;
+        self.diagnostics = Some(diagnostics);
+        Ok(())
     }
 
     pub fn predict(&mut self, x: &DMatrix<f32>) -> DMatrix<f32> {
@@ -94,23 +97,8 @@ impl RBFRegression {
         let x = expand_matrix(&x, n_columns);
 
         return matmul(
-            &calculate_gradient(&x, &self.centers, &self.beta),
+            &TypeKernel::RBF(self.beta).gram(&x, &self.centers),
             &self.weight,
         );
     }
 }
-
-pub fn calculate_gradient(x: &DMatrix<f32>, centers: &DMatrix<f32>, beta: &f32) -> DMatrix<f32> {
-    let mut gradient_vector: Vec<f32> = vec![0.0; x.nrows() * centers.nrows()];
-    for row in 0..x.nrows() {
-        for col in 0..centers.nrows() {
-            let norm = (centers.row(col) - x.row(row))
-                .iter()
-                .map(|value| value.powf(2.0))
-                .sum::<f32>();
-            gradient_vector[col * x.nrows() + row] = (-beta * norm).exp();
-        }
-    }
-
-    return DMatrix::from_vec(x.nrows(), centers.nrows(), gradient_vector);
-}
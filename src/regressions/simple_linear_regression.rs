@@ -1,8 +1,24 @@
+use nalgebra::DMatrix;
+
+use crate::online::incremental::IncrementalModel;
+use crate::regressions::inference::RegressionSummary;
 use crate::utils::stats;
+use crate::utils::utils::validate_sample_weight;
 
+/// Least squares line `y = bias + coefficient * x`.
+///
+/// Besides the batch `fit`, the model keeps running means and centered sums of squares
+/// and cross-products (Chan et al.), so `partial_fit` can refine it batch after batch
+/// and gives the same line as a single `fit` on all the samples seen so far.
 pub struct SimpleLinearRegression {
     pub coefficient: Option<f32>,
     pub bias: Option<f32>,
+    pub n_samples_seen: usize,
+    mean_x: f32,
+    mean_y: f32,
+    sum_xx: f32,
+    sum_xy: f32,
+    weighted: bool,
 }
 
 impl SimpleLinearRegression {
@@ -10,12 +26,119 @@ impl SimpleLinearRegression {
         let b1 = stats::covariance(x_values, y_values) / stats::variance(x_values);
         self.bias = Some(stats::mean(y_values) - b1 * stats::mean(x_values));
         self.coefficient = Some(b1);
+        self.weighted = false;
+
+        self.reset_moments();
+        self.update_moments(x_values, y_values);
+    }
+
+    /// Adds a batch to the running sums and refits the line on every sample seen so far.
+    /// A batch after which every `x` seen is still the same value is rejected and leaves
+    /// the model untouched.
+    pub fn partial_fit(&mut self, x_values: &Vec<f32>, y_values: &Vec<f32>) -> Result<(), String> {
+        if x_values.len() != y_values.len() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x_values.len(),
+                y_values.len()
+            ));
+        }
+        let previous = (
+            self.n_samples_seen,
+            self.mean_x,
+            self.mean_y,
+            self.sum_xx,
+            self.sum_xy,
+        );
+        self.update_moments(x_values, y_values);
+        if self.sum_xx <= 0.0 {
+            (
+                self.n_samples_seen,
+                self.mean_x,
+                self.mean_y,
+                self.sum_xx,
+                self.sum_xy,
+            ) = previous;
+            return Err("x should take at least two distinct values".to_string());
+        }
+
+        let b1 = self.sum_xy / self.sum_xx;
+        self.bias = Some(self.mean_y - b1 * self.mean_x);
+        self.coefficient = Some(b1);
+        self.weighted = false;
+        Ok(())
+    }
+
+    fn reset_moments(&mut self) {
+        self.n_samples_seen = 0;
+        (self.mean_x, self.mean_y, self.sum_xx, self.sum_xy) = (0.0, 0.0, 0.0, 0.0);
+    }
+
+    /// Merges the means and centered sums of a batch into the running ones.
+    fn update_moments(&mut self, x_values: &Vec<f32>, y_values: &Vec<f32>) {
+        let n = x_values.len();
+        if n == 0 {
+            return;
+        }
+        let mean_x = stats::mean(x_values);
+        let mean_y = stats::mean(y_values);
+        let (mut sum_xx, mut sum_xy) = (0.0, 0.0);
+        for (x, y) in x_values.iter().zip(y_values.iter()) {
+            sum_xx += (x - mean_x).powi(2);
+            sum_xy += (x - mean_x) * (y - mean_y);
+        }
+
+        let (n_a, n_b) = (self.n_samples_seen as f32, n as f32);
+        let total = n_a + n_b;
+        let (delta_x, delta_y) = (mean_x - self.mean_x, mean_y - self.mean_y);
+        self.sum_xx += sum_xx + delta_x * delta_x * n_a * n_b / total;
+        self.sum_xy += sum_xy + delta_x * delta_y * n_a * n_b / total;
+        self.mean_x += delta_x * n_b / total;
+        self.mean_y += delta_y * n_b / total;
+        self.n_samples_seen += n;
+    }
+
+    /// Weighted least squares fit, where `weights[i]` is the weight of the i-th sample.
+    /// The running sums are cleared, so a later `partial_fit` starts a new stream.
+    pub fn fit_weighted(
+        &mut self,
+        x_values: &Vec<f32>,
+        y_values: &Vec<f32>,
+        weights: &Vec<f32>,
+    ) -> Result<(), String> {
+        if x_values.len() != y_values.len() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x_values.len(),
+                y_values.len()
+            ));
+        }
+        validate_sample_weight(
+            &DMatrix::from_vec(weights.len(), 1, weights.clone()),
+            x_values.len(),
+        )?;
+
+        let b1 = stats::weighted_covariance(x_values, y_values, weights)
+            / stats::weighted_variance(x_values, weights);
+        self.bias = Some(
+            stats::weighted_mean(y_values, weights) - b1 * stats::weighted_mean(x_values, weights),
+        );
+        self.coefficient = Some(b1);
+        self.weighted = weights.iter().any(|w| *w != weights[0]);
+        self.reset_moments();
+        Ok(())
     }
 
     pub fn new() -> SimpleLinearRegression {
         SimpleLinearRegression {
             coefficient: None,
             bias: None,
+            n_samples_seen: 0,
+            mean_x: 0.0,
+            mean_y: 0.0,
+            sum_xx: 0.0,
+            sum_xy: 0.0,
+            weighted: false,
         }
     }
 
@@ -26,7 +149,7 @@ impl SimpleLinearRegression {
 
         let b0 = self.bias.unwrap();
         let b1 = self.coefficient.unwrap();
-        
+
         return b0 + b1 * x;
     }
 
@@ -39,4 +162,44 @@ impl SimpleLinearRegression {
 
         return predictions;
     }
-}
\ No newline at end of file
+
+    /// Standard errors, tests and goodness of fit of the fitted line on `(x, y)`. Not
+    /// available after `fit_weighted` with non-uniform weights.
+    pub fn summary(
+        &self,
+        x_values: &Vec<f32>,
+        y_values: &Vec<f32>,
+        confidence_level: f32,
+    ) -> Result<RegressionSummary, String> {
+        if self.coefficient.is_none() || self.bias.is_none() {
+            return Err("fit(..) must be called first".to_string());
+        }
+        if self.weighted {
+            return Err("inference is not available for a weighted fit".to_string());
+        }
+
+        RegressionSummary::new(
+            &DMatrix::from_vec(x_values.len(), 1, x_values.clone()),
+            &DMatrix::from_vec(y_values.len(), 1, y_values.clone()),
+            &[self.coefficient.unwrap()],
+            self.bias.unwrap(),
+            confidence_level,
+        )
+    }
+}
+
+/// `x` and `y` should both be a single column.
+impl IncrementalModel for SimpleLinearRegression {
+    fn partial_fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        if x.ncols() != 1 || y.ncols() != 1 {
+            return Err(format!(
+                "x and y should be single columns, got {} and {} columns",
+                x.ncols(),
+                y.ncols()
+            ));
+        }
+        let x_values = x.iter().cloned().collect();
+        let y_values = y.iter().cloned().collect();
+        SimpleLinearRegression::partial_fit(self, &x_values, &y_values)
+    }
+}
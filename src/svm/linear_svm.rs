@@ -0,0 +1,280 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Linear support vector classifier trained by dual coordinate descent (Hsieh et al.,
+/// 2008) on the hinge loss, one-vs-rest for more than two classes.
+///
+/// The intercept is learnt as the weight of an extra constant feature, so it is
+/// regularised like the coefficients. `coefficients` has one column per machine.
+pub struct LinearSVC {
+    pub c: f32,
+    pub tolerance: f32,
+    pub max_iter: usize,
+    pub seed: Option<u64>,
+    pub classes: Vec<f32>,
+    pub coefficients: DMatrix<f32>,
+    pub intercept: Vec<f32>,
+}
+
+/// Linear support vector regression trained by dual coordinate descent on the
+/// `epsilon`-insensitive loss.
+pub struct LinearSVR {
+    pub c: f32,
+    pub epsilon: f32,
+    pub tolerance: f32,
+    pub max_iter: usize,
+    pub seed: Option<u64>,
+    pub coefficients: DMatrix<f32>,
+    pub intercept: f32,
+}
+
+impl LinearSVC {
+    pub fn new() -> LinearSVC {
+        LinearSVC {
+            c: 1.0,
+            tolerance: 1e-3,
+            max_iter: 1000,
+            seed: None,
+            classes: Vec::new(),
+            coefficients: DMatrix::zeros(0, 0),
+            intercept: Vec::new(),
+        }
+    }
+
+    pub fn with_c(mut self, c: f32) -> LinearSVC {
+        self.c = c;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> LinearSVC {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> LinearSVC {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> LinearSVC {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        check_inputs(x, y, self.c)?;
+        self.classes = unique_classes(y);
+        if self.classes.len() < 2 {
+            return Err("LinearSVC needs at least two classes".to_string());
+        }
+
+        let positives: Vec<f32> = match self.classes.len() {
+            2 => vec![self.classes[1]],
+            _ => self.classes.clone(),
+        };
+        let mut rng = seeded_rng(self.seed);
+        self.coefficients = DMatrix::zeros(x.ncols(), positives.len());
+        self.intercept = vec![0.0; positives.len()];
+        for (k, positive) in positives.iter().enumerate() {
+            let signs: Vec<f32> = y
+                .column(0)
+                .iter()
+                .map(|label| if label == positive { 1.0 } else { -1.0 })
+                .collect();
+            let mut alpha = vec![0.0; x.nrows()];
+            let (w, b) = coordinate_descent(
+                x,
+                self.tolerance,
+                self.max_iter,
+                &mut rng,
+                |i, margin, q| {
+                    // Dual of the hinge loss: `0 <= alpha_i <= c`.
+                    let gradient = signs[i] * margin - 1.0;
+                    let projected = match alpha[i] {
+                        a if a <= 0.0 => gradient.min(0.0),
+                        a if a >= self.c => gradient.max(0.0),
+                        _ => gradient,
+                    };
+                    let old = alpha[i];
+                    alpha[i] = (old - gradient / q).clamp(0.0, self.c);
+                    (projected.abs(), (alpha[i] - old) * signs[i])
+                },
+            );
+            self.coefficients.set_column(k, &w.column(0));
+            self.intercept[k] = b;
+        }
+        Ok(())
+    }
+
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut scores = x * &self.coefficients;
+        for (k, b) in self.intercept.iter().enumerate() {
+            scores.column_mut(k).add_scalar_mut(*b);
+        }
+
+        return scores;
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        let scores = self.decision_function(x);
+        if self.classes.len() == 2 {
+            return scores
+                .column(0)
+                .iter()
+                .map(|s| self.classes[(*s > 0.0) as usize])
+                .collect();
+        }
+
+        return argmax_classes(&scores, &self.classes);
+    }
+}
+
+impl LinearSVR {
+    pub fn new() -> LinearSVR {
+        LinearSVR {
+            c: 1.0,
+            epsilon: 0.0,
+            tolerance: 1e-3,
+            max_iter: 1000,
+            seed: None,
+            coefficients: DMatrix::zeros(0, 1),
+            intercept: 0.0,
+        }
+    }
+
+    pub fn with_c(mut self, c: f32) -> LinearSVR {
+        self.c = c;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f32) -> LinearSVR {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> LinearSVR {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> LinearSVR {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> LinearSVR {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        check_inputs(x, y, self.c)?;
+        if self.epsilon < 0.0 {
+            return Err(format!(
+                "epsilon should be non-negative, got {}",
+                self.epsilon
+            ));
+        }
+
+        let mut rng = seeded_rng(self.seed);
+        let mut beta = vec![0.0; x.nrows()];
+        let (w, b) = coordinate_descent(
+            x,
+            self.tolerance,
+            self.max_iter,
+            &mut rng,
+            |i, prediction, q| {
+                // Dual of the epsilon-insensitive loss: `-c <= beta_i <= c` plus an L1 term.
+                let old = beta[i];
+                let z = old - (prediction - y[(i, 0)]) / q;
+                beta[i] =
+                    (z.signum() * (z.abs() - self.epsilon / q).max(0.0)).clamp(-self.c, self.c);
+                ((beta[i] - old).abs() * q, beta[i] - old)
+            },
+        );
+        self.coefficients = w;
+        self.intercept = b;
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        return (x * &self.coefficients).add_scalar(self.intercept);
+    }
+}
+
+impl Default for LinearSVC {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for LinearSVR {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_inputs(x: &DMatrix<f32>, y: &DMatrix<f32>, c: f32) -> Result<(), String> {
+    if x.nrows() != y.nrows() {
+        return Err(format!(
+            "x and y should have the same number of samples. |x|: {}, |y|: {}",
+            x.nrows(),
+            y.nrows()
+        ));
+    }
+    if c <= 0.0 {
+        return Err(format!("c should be positive, got {}", c));
+    }
+    Ok(())
+}
+
+fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Cycles over the samples in random order, letting `update(i, w.x_i + b, |x_i|^2 + 1)`
+/// move the dual variable of sample `i` and return its optimality violation and the
+/// change of its coefficient. Stops once a whole pass violates less than `tolerance`.
+fn coordinate_descent<F>(
+    x: &DMatrix<f32>,
+    tolerance: f32,
+    max_iter: usize,
+    rng: &mut StdRng,
+    mut update: F,
+) -> (DMatrix<f32>, f32)
+where
+    F: FnMut(usize, f32, f32) -> (f32, f32),
+{
+    let mut w = DMatrix::<f32>::zeros(x.ncols(), 1);
+    let mut b = 0.0;
+    let norms: Vec<f32> = (0..x.nrows())
+        .map(|i| x.row(i).norm_squared() + 1.0)
+        .collect();
+    let mut order: Vec<usize> = (0..x.nrows()).collect();
+
+    for _ in 0..max_iter {
+        order.shuffle(rng);
+        let mut max_violation: f32 = 0.0;
+        for i in order.iter() {
+            let output = (x.row(*i) * &w)[(0, 0)] + b;
+            let (violation, delta) = update(*i, output, norms[*i]);
+            max_violation = max_violation.max(violation);
+            if delta != 0.0 {
+                w += x.row(*i).transpose() * delta;
+                b += delta;
+            }
+        }
+        if max_violation < tolerance {
+            break;
+        }
+    }
+
+    return (w, b);
+}
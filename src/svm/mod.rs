@@ -0,0 +1,5 @@
+pub mod linear_svm;
+pub mod smo;
+pub mod support_vectors;
+pub mod svc;
+pub mod svr;
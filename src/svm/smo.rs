@@ -0,0 +1,167 @@
+/// Sequential minimal optimization of the dual problem shared by SVC and SVR,
+///
+/// `min 1/2 a^T Q a + p^T a  s.t.  y^T a = const, 0 <= a_i <= c`,
+///
+/// with `y_i` in `{-1, 1}` and `Q_ij = y_i y_j K_ij` given by `q`. Working pairs are
+/// picked with the second order rule of Fan, Chen and Lin (2005), starting from `a = 0`.
+pub(crate) struct SmoSolver<'a> {
+    pub q: &'a dyn Fn(usize, usize) -> f32,
+    pub p: Vec<f32>,
+    pub y: Vec<f32>,
+    pub c: f32,
+    pub tolerance: f32,
+    pub max_iter: usize,
+}
+
+/// Dual variables and the offset `rho` of the decision function `sum y_i a_i K_i - rho`.
+pub(crate) struct SmoSolution {
+    pub alpha: Vec<f32>,
+    pub rho: f32,
+}
+
+const TAU: f32 = 1e-12;
+
+impl<'a> SmoSolver<'a> {
+    pub(crate) fn solve(&self) -> SmoSolution {
+        let n = self.p.len();
+        let diagonal: Vec<f32> = (0..n).map(|i| (self.q)(i, i)).collect();
+        let mut alpha = vec![0.0; n];
+        let mut gradient = self.p.clone();
+
+        for _ in 0..self.max_iter {
+            let Some((i, j)) = self.working_set(&alpha, &gradient, &diagonal) else {
+                break;
+            };
+
+            let (old_i, old_j) = (alpha[i], alpha[j]);
+            let q_ij = (self.q)(i, j);
+            let c = self.c;
+            if self.y[i] != self.y[j] {
+                let quad = (diagonal[i] + diagonal[j] + 2.0 * q_ij).max(TAU);
+                let delta = (-gradient[i] - gradient[j]) / quad;
+                let diff = alpha[i] - alpha[j];
+                alpha[i] += delta;
+                alpha[j] += delta;
+                if diff > 0.0 && alpha[j] < 0.0 {
+                    alpha[j] = 0.0;
+                    alpha[i] = diff;
+                } else if diff <= 0.0 && alpha[i] < 0.0 {
+                    alpha[i] = 0.0;
+                    alpha[j] = -diff;
+                }
+                if diff > 0.0 && alpha[i] > c {
+                    alpha[i] = c;
+                    alpha[j] = c - diff;
+                } else if diff <= 0.0 && alpha[j] > c {
+                    alpha[j] = c;
+                    alpha[i] = c + diff;
+                }
+            } else {
+                let quad = (diagonal[i] + diagonal[j] - 2.0 * q_ij).max(TAU);
+                let delta = (gradient[i] - gradient[j]) / quad;
+                let sum = alpha[i] + alpha[j];
+                alpha[i] -= delta;
+                alpha[j] += delta;
+                if sum > c && alpha[i] > c {
+                    alpha[i] = c;
+                    alpha[j] = sum - c;
+                } else if sum <= c && alpha[j] < 0.0 {
+                    alpha[j] = 0.0;
+                    alpha[i] = sum;
+                }
+                if sum > c && alpha[j] > c {
+                    alpha[j] = c;
+                    alpha[i] = sum - c;
+                } else if sum <= c && alpha[i] < 0.0 {
+                    alpha[i] = 0.0;
+                    alpha[j] = sum;
+                }
+            }
+
+            let (delta_i, delta_j) = (alpha[i] - old_i, alpha[j] - old_j);
+            for (t, g) in gradient.iter_mut().enumerate() {
+                *g += (self.q)(t, i) * delta_i + (self.q)(t, j) * delta_j;
+            }
+        }
+
+        SmoSolution {
+            rho: self.rho(&alpha, &gradient),
+            alpha,
+        }
+    }
+
+    fn is_upper(&self, alpha: &[f32], t: usize) -> bool {
+        (self.y[t] > 0.0 && alpha[t] < self.c) || (self.y[t] < 0.0 && alpha[t] > 0.0)
+    }
+
+    fn is_lower(&self, alpha: &[f32], t: usize) -> bool {
+        (self.y[t] > 0.0 && alpha[t] > 0.0) || (self.y[t] < 0.0 && alpha[t] < self.c)
+    }
+
+    /// Maximal violating `i` and the `j` decreasing the objective the most with it, or
+    /// `None` once the KKT conditions hold within `tolerance`.
+    fn working_set(
+        &self,
+        alpha: &[f32],
+        gradient: &[f32],
+        diagonal: &[f32],
+    ) -> Option<(usize, usize)> {
+        let n = alpha.len();
+        let mut g_max = f32::NEG_INFINITY;
+        let mut i = None;
+        for t in (0..n).filter(|t| self.is_upper(alpha, *t)) {
+            if -self.y[t] * gradient[t] >= g_max {
+                g_max = -self.y[t] * gradient[t];
+                i = Some(t);
+            }
+        }
+        let i = i?;
+
+        let mut g_min = f32::INFINITY;
+        let mut best_objective = f32::INFINITY;
+        let mut j = None;
+        for t in (0..n).filter(|t| self.is_lower(alpha, *t)) {
+            let y_gradient = self.y[t] * gradient[t];
+            g_min = g_min.min(-y_gradient);
+            let gradient_difference = g_max + y_gradient;
+            if gradient_difference > 0.0 {
+                let quad = diagonal[i] + diagonal[t] - 2.0 * self.y[i] * self.y[t] * (self.q)(i, t);
+                let objective = -gradient_difference * gradient_difference / quad.max(TAU);
+                if objective <= best_objective {
+                    best_objective = objective;
+                    j = Some(t);
+                }
+            }
+        }
+
+        if g_max - g_min < self.tolerance {
+            return None;
+        }
+        return j.map(|j| (i, j));
+    }
+
+    /// Mean of `y_i * G_i` over the free variables, or the middle of its feasible
+    /// interval when every variable is at a bound.
+    fn rho(&self, alpha: &[f32], gradient: &[f32]) -> f32 {
+        let (mut upper, mut lower) = (f32::INFINITY, f32::NEG_INFINITY);
+        let (mut sum, mut n_free) = (0.0, 0);
+        for t in 0..alpha.len() {
+            let y_gradient = self.y[t] * gradient[t];
+            let at_upper = alpha[t] >= self.c;
+            let at_lower = alpha[t] <= 0.0;
+            if (at_upper && self.y[t] < 0.0) || (at_lower && self.y[t] > 0.0) {
+                upper = upper.min(y_gradient);
+            } else if at_upper || at_lower {
+                lower = lower.max(y_gradient);
+            } else {
+                sum += y_gradient;
+                n_free += 1;
+            }
+        }
+
+        if n_free > 0 {
+            return sum / n_free as f32;
+        }
+        return (upper + lower) / 2.0;
+    }
+}
@@ -0,0 +1,43 @@
+use nalgebra::DMatrix;
+
+use crate::kernels::kernel::Kernel;
+use crate::utils::types::TypeKernel;
+
+/// Support vectors of one binary machine with their coefficients `y_i * alpha_i`.
+///
+/// `indices` are the rows of the training set the vectors come from; the decision
+/// function is `sum_i dual_coef_i * K(vector_i, x) + intercept`.
+#[derive(Clone, Debug)]
+pub struct SupportVectors {
+    pub indices: Vec<usize>,
+    pub vectors: DMatrix<f32>,
+    pub dual_coef: Vec<f32>,
+    pub intercept: f32,
+}
+
+impl SupportVectors {
+    pub(crate) fn from_coefficients(
+        x: &DMatrix<f32>,
+        coefficients: &[f32],
+        intercept: f32,
+    ) -> SupportVectors {
+        let indices: Vec<usize> = (0..coefficients.len())
+            .filter(|i| coefficients[*i] != 0.0)
+            .collect();
+        SupportVectors {
+            vectors: x.select_rows(indices.iter()),
+            dual_coef: indices.iter().map(|i| coefficients[*i]).collect(),
+            indices,
+            intercept,
+        }
+    }
+
+    pub fn decision_function(&self, kernel: TypeKernel, x: &DMatrix<f32>) -> DMatrix<f32> {
+        if self.indices.is_empty() {
+            return DMatrix::from_element(x.nrows(), 1, self.intercept);
+        }
+        let k = kernel.gram(x, &self.vectors);
+        return (k * DMatrix::from_column_slice(self.dual_coef.len(), 1, &self.dual_coef))
+            .add_scalar(self.intercept);
+    }
+}
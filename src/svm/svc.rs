@@ -0,0 +1,131 @@
+use nalgebra::DMatrix;
+
+use crate::kernels::kernel::Kernel;
+use crate::svm::smo::SmoSolver;
+use crate::svm::support_vectors::SupportVectors;
+use crate::utils::types::TypeKernel;
+use crate::utils::utils::{argmax_classes, unique_classes};
+
+/// Kernel support vector classifier trained by SMO.
+///
+/// Two classes are separated by one machine whose positive side is the second class;
+/// with more classes, one machine per class separates it from the others (one-vs-rest)
+/// and the highest decision value wins. `c` bounds the dual coefficients: smaller
+/// values allow more margin violations.
+pub struct SVC {
+    pub kernel: TypeKernel,
+    pub c: f32,
+    pub tolerance: f32,
+    pub max_iter: usize,
+    pub classes: Vec<f32>,
+    pub support: Vec<SupportVectors>,
+}
+
+impl SVC {
+    pub fn new(kernel: TypeKernel) -> SVC {
+        SVC {
+            kernel,
+            c: 1.0,
+            tolerance: 1e-3,
+            max_iter: 100_000,
+            classes: Vec::new(),
+            support: Vec::new(),
+        }
+    }
+
+    pub fn with_c(mut self, c: f32) -> SVC {
+        self.c = c;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> SVC {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> SVC {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        if self.c <= 0.0 {
+            return Err(format!("c should be positive, got {}", self.c));
+        }
+        self.classes = unique_classes(y);
+        if self.classes.len() < 2 {
+            return Err("SVC needs at least two classes".to_string());
+        }
+
+        let gram = self.kernel.gram(x, x);
+        let positives: Vec<f32> = match self.classes.len() {
+            2 => vec![self.classes[1]],
+            _ => self.classes.clone(),
+        };
+        self.support = positives
+            .iter()
+            .map(|positive| {
+                let signs: Vec<f32> = y
+                    .column(0)
+                    .iter()
+                    .map(|label| if label == positive { 1.0 } else { -1.0 })
+                    .collect();
+                let q = |i: usize, j: usize| signs[i] * signs[j] * gram[(i, j)];
+                let solution = SmoSolver {
+                    q: &q,
+                    p: vec![-1.0; x.nrows()],
+                    y: signs.clone(),
+                    c: self.c,
+                    tolerance: self.tolerance,
+                    max_iter: self.max_iter,
+                }
+                .solve();
+
+                let coefficients: Vec<f32> = solution
+                    .alpha
+                    .iter()
+                    .zip(signs.iter())
+                    .map(|(a, s)| a * s)
+                    .collect();
+                SupportVectors::from_coefficients(x, &coefficients, -solution.rho)
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// One column per machine: the signed distance to the margin of the second class
+    /// for two classes, of every class against the rest otherwise.
+    pub fn decision_function(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut scores = DMatrix::<f32>::zeros(x.nrows(), self.support.len());
+        for (k, support) in self.support.iter().enumerate() {
+            scores.set_column(k, &support.decision_function(self.kernel, x).column(0));
+        }
+
+        return scores;
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        let scores = self.decision_function(x);
+        if self.classes.len() == 2 {
+            return scores
+                .column(0)
+                .iter()
+                .map(|s| self.classes[(*s > 0.0) as usize])
+                .collect();
+        }
+
+        return argmax_classes(&scores, &self.classes);
+    }
+
+    /// Number of support vectors of every machine.
+    pub fn n_support(&self) -> Vec<usize> {
+        return self.support.iter().map(|s| s.indices.len()).collect();
+    }
+}
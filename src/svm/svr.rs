@@ -0,0 +1,101 @@
+use nalgebra::DMatrix;
+
+use crate::kernels::kernel::Kernel;
+use crate::svm::smo::SmoSolver;
+use crate::svm::support_vectors::SupportVectors;
+use crate::utils::types::TypeKernel;
+
+/// Kernel support vector regression trained by SMO: residuals inside the `epsilon`
+/// tube are free and the others cost `c` per unit, so only the samples on or outside
+/// the tube become support vectors.
+pub struct SVR {
+    pub kernel: TypeKernel,
+    pub c: f32,
+    pub epsilon: f32,
+    pub tolerance: f32,
+    pub max_iter: usize,
+    pub support: Option<SupportVectors>,
+}
+
+impl SVR {
+    pub fn new(kernel: TypeKernel) -> SVR {
+        SVR {
+            kernel,
+            c: 1.0,
+            epsilon: 0.1,
+            tolerance: 1e-3,
+            max_iter: 100_000,
+            support: None,
+        }
+    }
+
+    pub fn with_c(mut self, c: f32) -> SVR {
+        self.c = c;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f32) -> SVR {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> SVR {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_max_iter(mut self, max_iter: usize) -> SVR {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        if x.nrows() != y.nrows() || y.ncols() != 1 {
+            return Err(format!(
+                "y should be a single column with one row per sample. |x|: {}, |y|: {}x{}",
+                x.nrows(),
+                y.nrows(),
+                y.ncols()
+            ));
+        }
+        if self.c <= 0.0 || self.epsilon < 0.0 {
+            return Err(format!(
+                "c should be positive and epsilon non-negative, got {} and {}",
+                self.c, self.epsilon
+            ));
+        }
+
+        // Variables `0..n` are the coefficients above the tube and `n..2n` below it.
+        let n = x.nrows();
+        let gram = self.kernel.gram(x, x);
+        let signs: Vec<f32> = (0..2 * n).map(|t| if t < n { 1.0 } else { -1.0 }).collect();
+        let q = |i: usize, j: usize| signs[i] * signs[j] * gram[(i % n, j % n)];
+        let p: Vec<f32> = (0..2 * n)
+            .map(|t| self.epsilon - signs[t] * y[(t % n, 0)])
+            .collect();
+        let solution = SmoSolver {
+            q: &q,
+            p,
+            y: signs.clone(),
+            c: self.c,
+            tolerance: self.tolerance,
+            max_iter: self.max_iter,
+        }
+        .solve();
+
+        let coefficients: Vec<f32> = (0..n)
+            .map(|i| solution.alpha[i] - solution.alpha[i + n])
+            .collect();
+        self.support = Some(SupportVectors::from_coefficients(
+            x,
+            &coefficients,
+            -solution.rho,
+        ));
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let support = self.support.as_ref().expect("SVR should be fitted first");
+        return support.decision_function(self.kernel, x);
+    }
+}
@@ -0,0 +1,142 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::trees::tree::{Target, Tree, TreeParams};
+use crate::utils::types::{Criterion, MaxFeatures, Splitter};
+use crate::utils::utils::{argmax_classes, unique_classes, validate_sample_weight};
+
+/// Classification tree splitting on the Gini impurity or the entropy. Labels are the
+/// values of the first column of `y`.
+pub struct DecisionTreeClassifier {
+    pub params: TreeParams,
+    pub seed: Option<u64>,
+    pub classes: Vec<f32>,
+    pub tree: Option<Tree>,
+}
+
+impl DecisionTreeClassifier {
+    pub fn new(criterion: Criterion) -> DecisionTreeClassifier {
+        DecisionTreeClassifier {
+            params: TreeParams::new(criterion),
+            seed: None,
+            classes: Vec::new(),
+            tree: None,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> DecisionTreeClassifier {
+        self.params.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_min_samples_split(mut self, min_samples_split: usize) -> DecisionTreeClassifier {
+        self.params.min_samples_split = min_samples_split;
+        self
+    }
+
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> DecisionTreeClassifier {
+        self.params.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    pub fn with_max_features(mut self, max_features: MaxFeatures) -> DecisionTreeClassifier {
+        self.params.max_features = max_features;
+        self
+    }
+
+    pub fn with_splitter(mut self, splitter: Splitter) -> DecisionTreeClassifier {
+        self.params.splitter = splitter;
+        self
+    }
+
+    pub fn with_ccp_alpha(mut self, ccp_alpha: f32) -> DecisionTreeClassifier {
+        self.params.ccp_alpha = ccp_alpha;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> DecisionTreeClassifier {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.fit_weighted(x, y, &DMatrix::from_element(x.nrows(), 1, 1.0))
+    }
+
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> Result<(), String> {
+        self.params.validate(true)?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        validate_sample_weight(sample_weight, x.nrows())?;
+
+        self.classes = unique_classes(y);
+        let labels = class_labels(y, &self.classes);
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        self.tree = Some(Tree::build(
+            x,
+            &Target::CLASSES(&labels, self.classes.len()),
+            sample_weight.as_slice(),
+            &self.params,
+            &mut rng,
+        ));
+        Ok(())
+    }
+
+    /// Class proportions of the leaf reached by every sample, one column per class.
+    pub fn predict_proba(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let tree = self.tree.as_ref().expect("fit(..) must be called first");
+        let leaves = tree.apply(x);
+        return DMatrix::from_fn(x.nrows(), self.classes.len(), |i, k| {
+            tree.nodes[leaves[i]].value[k]
+        });
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> Vec<f32> {
+        return argmax_classes(&self.predict_proba(x), &self.classes);
+    }
+
+    pub fn feature_importances(&self) -> Vec<f32> {
+        return self
+            .tree
+            .as_ref()
+            .expect("fit(..) must be called first")
+            .feature_importances();
+    }
+
+    /// The learned rules, naming features `feature_<j>` unless `feature_names` is given.
+    pub fn export_text(&self, feature_names: Option<&[&str]>) -> String {
+        let tree = self.tree.as_ref().expect("fit(..) must be called first");
+        return tree.export_text(feature_names, &|node| {
+            let k =
+                node.value.iter().enumerate().fold(
+                    0,
+                    |best, (k, p)| if *p > node.value[best] { k } else { best },
+                );
+            format!("class: {}", self.classes[k])
+        });
+    }
+}
+
+/// Index in `classes` of the label of every sample.
+pub(crate) fn class_labels(y: &DMatrix<f32>, classes: &[f32]) -> Vec<usize> {
+    return y
+        .column(0)
+        .iter()
+        .map(|label| classes.iter().position(|c| c == label).unwrap())
+        .collect();
+}
@@ -0,0 +1,118 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::trees::tree::{Target, Tree, TreeParams};
+use crate::utils::types::{Criterion, MaxFeatures, Splitter};
+use crate::utils::utils::validate_sample_weight;
+
+/// Regression tree on the first column of `y`. Leaves predict the mean of their samples
+/// with the `MSE` criterion and the median with `MAE`.
+pub struct DecisionTreeRegressor {
+    pub params: TreeParams,
+    pub seed: Option<u64>,
+    pub tree: Option<Tree>,
+}
+
+impl DecisionTreeRegressor {
+    pub fn new(criterion: Criterion) -> DecisionTreeRegressor {
+        DecisionTreeRegressor {
+            params: TreeParams::new(criterion),
+            seed: None,
+            tree: None,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> DecisionTreeRegressor {
+        self.params.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_min_samples_split(mut self, min_samples_split: usize) -> DecisionTreeRegressor {
+        self.params.min_samples_split = min_samples_split;
+        self
+    }
+
+    pub fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> DecisionTreeRegressor {
+        self.params.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    pub fn with_max_features(mut self, max_features: MaxFeatures) -> DecisionTreeRegressor {
+        self.params.max_features = max_features;
+        self
+    }
+
+    pub fn with_splitter(mut self, splitter: Splitter) -> DecisionTreeRegressor {
+        self.params.splitter = splitter;
+        self
+    }
+
+    pub fn with_ccp_alpha(mut self, ccp_alpha: f32) -> DecisionTreeRegressor {
+        self.params.ccp_alpha = ccp_alpha;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> DecisionTreeRegressor {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn fit(&mut self, x: &DMatrix<f32>, y: &DMatrix<f32>) -> Result<(), String> {
+        self.fit_weighted(x, y, &DMatrix::from_element(x.nrows(), 1, 1.0))
+    }
+
+    pub fn fit_weighted(
+        &mut self,
+        x: &DMatrix<f32>,
+        y: &DMatrix<f32>,
+        sample_weight: &DMatrix<f32>,
+    ) -> Result<(), String> {
+        self.params.validate(false)?;
+        if x.nrows() != y.nrows() {
+            return Err(format!(
+                "x and y should have the same number of samples. |x|: {}, |y|: {}",
+                x.nrows(),
+                y.nrows()
+            ));
+        }
+        validate_sample_weight(sample_weight, x.nrows())?;
+
+        let values: Vec<f32> = y.column(0).iter().cloned().collect();
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        self.tree = Some(Tree::build(
+            x,
+            &Target::VALUES(&values),
+            sample_weight.as_slice(),
+            &self.params,
+            &mut rng,
+        ));
+        Ok(())
+    }
+
+    pub fn predict(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let tree = self.tree.as_ref().expect("fit(..) must be called first");
+        let leaves = tree.apply(x);
+        return DMatrix::from_fn(x.nrows(), 1, |i, _| tree.nodes[leaves[i]].value[0]);
+    }
+
+    pub fn feature_importances(&self) -> Vec<f32> {
+        return self
+            .tree
+            .as_ref()
+            .expect("fit(..) must be called first")
+            .feature_importances();
+    }
+
+    /// The learned rules, naming features `feature_<j>` unless `feature_names` is given.
+    pub fn export_text(&self, feature_names: Option<&[&str]>) -> String {
+        let tree = self.tree.as_ref().expect("fit(..) must be called first");
+        return tree.export_text(feature_names, &|node| {
+            format!("value: {:.4}", node.value[0])
+        });
+    }
+}
@@ -0,0 +1,3 @@
+pub mod decision_tree_classifier;
+pub mod decision_tree_regressor;
+pub mod tree;
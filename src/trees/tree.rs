@@ -0,0 +1,758 @@
+use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::utils::types::{Criterion, MaxFeatures, Splitter};
+
+/// A node of a fitted [`Tree`]. Leaves have no `feature`; internal nodes send samples
+/// with `x[feature] <= threshold` to `left` and the others to `right`.
+///
+/// `value` holds the class proportions for classification trees and the single
+/// prediction for regression trees; it is set on internal nodes too.
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub feature: Option<usize>,
+    pub threshold: f32,
+    pub left: usize,
+    pub right: usize,
+    pub value: Vec<f32>,
+    pub impurity: f32,
+    pub n_samples: usize,
+    pub weighted_n_samples: f32,
+}
+
+/// Growth and pruning parameters shared by every tree learner.
+///
+/// Nodes with fewer than `min_samples_split` samples, or at `max_depth`, become leaves
+/// and no split may leave fewer than `min_samples_leaf` samples on either side. After
+/// growing, the tree is pruned by minimal cost-complexity pruning with `ccp_alpha`.
+#[derive(Clone, Debug)]
+pub struct TreeParams {
+    pub criterion: Criterion,
+    pub splitter: Splitter,
+    pub max_depth: Option<usize>,
+    pub min_samples_split: usize,
+    pub min_samples_leaf: usize,
+    pub max_features: MaxFeatures,
+    pub ccp_alpha: f32,
+}
+
+impl TreeParams {
+    pub fn new(criterion: Criterion) -> TreeParams {
+        TreeParams {
+            criterion,
+            splitter: Splitter::BEST,
+            max_depth: None,
+            min_samples_split: 2,
+            min_samples_leaf: 1,
+            max_features: MaxFeatures::ALL,
+            ccp_alpha: 0.0,
+        }
+    }
+
+    pub(crate) fn validate(&self, classification: bool) -> Result<(), String> {
+        let criterion_matches = match self.criterion {
+            Criterion::GINI | Criterion::ENTROPY => classification,
+            Criterion::MSE | Criterion::MAE => !classification,
+        };
+        if !criterion_matches {
+            return Err(format!(
+                "{:?} is not a {} criterion",
+                self.criterion,
+                if classification {
+                    "classification"
+                } else {
+                    "regression"
+                }
+            ));
+        }
+        if self.min_samples_split < 2 || self.min_samples_leaf < 1 {
+            return Err(
+                "min_samples_split should be at least 2 and min_samples_leaf at least 1"
+                    .to_string(),
+            );
+        }
+        if let Splitter::HISTOGRAM(max_bins) = self.splitter {
+            if max_bins < 2 {
+                return Err(format!("max_bins should be at least 2, got {}", max_bins));
+            }
+        }
+        if self.ccp_alpha < 0.0 {
+            return Err(format!(
+                "ccp_alpha should be non-negative, got {}",
+                self.ccp_alpha
+            ));
+        }
+        Ok(())
+    }
+
+    fn n_candidate_features(&self, n_features: usize) -> usize {
+        let n = match self.max_features {
+            MaxFeatures::ALL => n_features,
+            MaxFeatures::SQRT => (n_features as f32).sqrt() as usize,
+            MaxFeatures::LOG2 => (n_features as f32).log2() as usize,
+            MaxFeatures::N(n) => n,
+            MaxFeatures::FRACTION(fraction) => (fraction * n_features as f32) as usize,
+        };
+        return n.clamp(1, n_features);
+    }
+}
+
+/// Targets a tree is grown on: class indices in `0..n_classes`, or real values.
+#[allow(clippy::upper_case_acronyms)]
+pub(crate) enum Target<'a> {
+    CLASSES(&'a [usize], usize),
+    VALUES(&'a [f32]),
+}
+
+/// A binary decision tree stored as a flat list of nodes, the root being `nodes[0]`.
+#[derive(Clone, Debug)]
+pub struct Tree {
+    pub nodes: Vec<Node>,
+    pub n_features: usize,
+}
+
+struct Split {
+    feature: usize,
+    threshold: f32,
+    score: f32,
+}
+
+struct Builder<'a> {
+    x: &'a DMatrix<f32>,
+    target: &'a Target<'a>,
+    sample_weight: &'a [f32],
+    params: &'a TreeParams,
+    rng: &'a mut StdRng,
+    nodes: Vec<Node>,
+    bin_edges: Vec<Vec<f32>>,
+}
+
+impl Tree {
+    /// Grows a tree on the samples with a positive `sample_weight`, then prunes it.
+    pub(crate) fn build(
+        x: &DMatrix<f32>,
+        target: &Target,
+        sample_weight: &[f32],
+        params: &TreeParams,
+        rng: &mut StdRng,
+    ) -> Tree {
+        let indices: Vec<usize> = (0..x.nrows()).filter(|i| sample_weight[*i] > 0.0).collect();
+        let bin_edges = match params.splitter {
+            Splitter::HISTOGRAM(max_bins) => (0..x.ncols())
+                .map(|feature| bin_edges(x, &indices, feature, max_bins))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut builder = Builder {
+            x,
+            target,
+            sample_weight,
+            params,
+            rng,
+            nodes: Vec::new(),
+            bin_edges,
+        };
+        builder.grow(indices, 0);
+
+        let mut tree = Tree {
+            nodes: builder.nodes,
+            n_features: x.ncols(),
+        };
+        if params.ccp_alpha > 0.0 {
+            tree.prune(params.ccp_alpha);
+        }
+
+        return tree;
+    }
+
+    /// Index of the leaf reached by every row of `x`.
+    pub fn apply(&self, x: &DMatrix<f32>) -> Vec<usize> {
+        return (0..x.nrows()).map(|i| self.leaf(x, i)).collect();
+    }
+
+    pub(crate) fn leaf(&self, x: &DMatrix<f32>, row: usize) -> usize {
+        let mut node = 0;
+        while let Some(feature) = self.nodes[node].feature {
+            node = if x[(row, feature)] <= self.nodes[node].threshold {
+                self.nodes[node].left
+            } else {
+                self.nodes[node].right
+            };
+        }
+
+        return node;
+    }
+
+    pub fn depth(&self) -> usize {
+        fn depth(nodes: &[Node], node: usize) -> usize {
+            match nodes[node].feature {
+                Some(_) => 1 + depth(nodes, nodes[node].left).max(depth(nodes, nodes[node].right)),
+                None => 0,
+            }
+        }
+
+        return depth(&self.nodes, 0);
+    }
+
+    pub fn n_leaves(&self) -> usize {
+        return self
+            .nodes
+            .iter()
+            .filter(|node| node.feature.is_none())
+            .count();
+    }
+
+    /// Weighted impurity decrease brought by the splits on each feature, normalised to
+    /// sum to 1 (all zeros for a single leaf).
+    pub fn feature_importances(&self) -> Vec<f32> {
+        let mut importances = vec![0.0; self.n_features];
+        for node in self.nodes.iter() {
+            if let Some(feature) = node.feature {
+                let (left, right) = (&self.nodes[node.left], &self.nodes[node.right]);
+                importances[feature] += node.weighted_n_samples * node.impurity
+                    - left.weighted_n_samples * left.impurity
+                    - right.weighted_n_samples * right.impurity;
+            }
+        }
+
+        let total: f32 = importances.iter().sum();
+        if total > 0.0 {
+            importances.iter_mut().for_each(|v| *v /= total);
+        }
+        return importances;
+    }
+
+    /// Minimal cost-complexity pruning: repeatedly collapses the internal node whose
+    /// subtree reduces the total weighted impurity the least per extra leaf, while that
+    /// reduction is at most `ccp_alpha`.
+    pub fn prune(&mut self, ccp_alpha: f32) {
+        let total_weight = self.nodes[0].weighted_n_samples;
+
+        loop {
+            let mut weakest: Option<(usize, f32)> = None;
+            self.weakest_link(0, total_weight, &mut weakest);
+            match weakest {
+                Some((node, alpha)) if alpha <= ccp_alpha => self.nodes[node].feature = None,
+                _ => break,
+            }
+        }
+
+        self.compact();
+    }
+
+    /// Returns the cost and number of leaves of the subtree at `node`, recording the
+    /// internal node with the smallest effective alpha in `weakest`.
+    fn weakest_link(
+        &self,
+        node: usize,
+        total_weight: f32,
+        weakest: &mut Option<(usize, f32)>,
+    ) -> (f32, usize) {
+        let own_cost =
+            self.nodes[node].impurity * self.nodes[node].weighted_n_samples / total_weight;
+        if self.nodes[node].feature.is_none() {
+            return (own_cost, 1);
+        }
+
+        let (left_cost, left_leaves) =
+            self.weakest_link(self.nodes[node].left, total_weight, weakest);
+        let (right_cost, right_leaves) =
+            self.weakest_link(self.nodes[node].right, total_weight, weakest);
+        let (cost, leaves) = (left_cost + right_cost, left_leaves + right_leaves);
+
+        let alpha = (own_cost - cost) / (leaves - 1) as f32;
+        if weakest.is_none_or(|(_, weakest_alpha)| alpha < weakest_alpha) {
+            *weakest = Some((node, alpha));
+        }
+
+        return (cost, leaves);
+    }
+
+    /// Drops the nodes no longer reachable from the root.
+    fn compact(&mut self) {
+        fn copy(old: &[Node], node: usize, new: &mut Vec<Node>) -> usize {
+            let id = new.len();
+            new.push(old[node].clone());
+            if old[node].feature.is_some() {
+                new[id].left = copy(old, old[node].left, new);
+                new[id].right = copy(old, old[node].right, new);
+            } else {
+                new[id].left = 0;
+                new[id].right = 0;
+            }
+            id
+        }
+
+        let mut nodes = Vec::new();
+        copy(&self.nodes, 0, &mut nodes);
+        self.nodes = nodes;
+    }
+
+    /// The learned rules as indented text, one line per branch and leaf. Leaves are
+    /// described by `describe_leaf`.
+    pub(crate) fn export_text(
+        &self,
+        feature_names: Option<&[&str]>,
+        describe_leaf: &dyn Fn(&Node) -> String,
+    ) -> String {
+        fn write(
+            tree: &Tree,
+            node: usize,
+            depth: usize,
+            feature_names: Option<&[&str]>,
+            describe_leaf: &dyn Fn(&Node) -> String,
+            text: &mut String,
+        ) {
+            let indent = "|   ".repeat(depth);
+            let current = &tree.nodes[node];
+            match current.feature {
+                Some(feature) => {
+                    let name = match feature_names {
+                        Some(names) => names[feature].to_string(),
+                        None => format!("feature_{}", feature),
+                    };
+                    text.push_str(&format!(
+                        "{}|--- {} <= {:.2}\n",
+                        indent, name, current.threshold
+                    ));
+                    write(
+                        tree,
+                        current.left,
+                        depth + 1,
+                        feature_names,
+                        describe_leaf,
+                        text,
+                    );
+                    text.push_str(&format!(
+                        "{}|--- {} >  {:.2}\n",
+                        indent, name, current.threshold
+                    ));
+                    write(
+                        tree,
+                        current.right,
+                        depth + 1,
+                        feature_names,
+                        describe_leaf,
+                        text,
+                    );
+                }
+                None => text.push_str(&format!("{}|--- {}\n", indent, describe_leaf(current))),
+            }
+        }
+
+        let mut text = String::new();
+        write(self, 0, 0, feature_names, describe_leaf, &mut text);
+        return text;
+    }
+}
+
+impl<'a> Builder<'a> {
+    fn grow(&mut self, indices: Vec<usize>, depth: usize) -> usize {
+        let (value, impurity, weighted_n_samples) = self.node_statistics(&indices);
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            feature: None,
+            threshold: 0.0,
+            left: 0,
+            right: 0,
+            value,
+            impurity,
+            n_samples: indices.len(),
+            weighted_n_samples,
+        });
+
+        let can_split = self
+            .params
+            .max_depth
+            .is_none_or(|max_depth| depth < max_depth)
+            && indices.len() >= self.params.min_samples_split
+            && indices.len() >= 2 * self.params.min_samples_leaf
+            && impurity > 0.0;
+        if !can_split {
+            return id;
+        }
+
+        if let Some(split) = self.best_split(&indices) {
+            let (left, right): (Vec<usize>, Vec<usize>) = indices
+                .iter()
+                .partition(|i| self.x[(**i, split.feature)] <= split.threshold);
+
+            let left = self.grow(left, depth + 1);
+            let right = self.grow(right, depth + 1);
+            let node = &mut self.nodes[id];
+            node.feature = Some(split.feature);
+            node.threshold = split.threshold;
+            node.left = left;
+            node.right = right;
+        }
+
+        return id;
+    }
+
+    /// Prediction, impurity and total weight of a set of samples.
+    fn node_statistics(&self, indices: &[usize]) -> (Vec<f32>, f32, f32) {
+        let weight: f32 = indices.iter().map(|i| self.sample_weight[*i]).sum();
+        match self.target {
+            Target::CLASSES(labels, n_classes) => {
+                let mut counts = vec![0.0; *n_classes];
+                for i in indices {
+                    counts[labels[*i]] += self.sample_weight[*i];
+                }
+                let impurity = class_impurity(&counts, weight, self.params.criterion);
+                (
+                    counts.iter().map(|c| c / weight).collect(),
+                    impurity,
+                    weight,
+                )
+            }
+            Target::VALUES(values) => {
+                let (value, impurity) =
+                    value_impurity(values, self.sample_weight, indices, self.params.criterion);
+                (vec![value], impurity, weight)
+            }
+        }
+    }
+
+    /// Best split among the candidate features, visiting features in random order until
+    /// `max_features` non-constant ones have been tried.
+    fn best_split(&mut self, indices: &[usize]) -> Option<Split> {
+        let mut features: Vec<usize> = (0..self.x.ncols()).collect();
+        features.shuffle(&mut *self.rng);
+        let n_candidates = self.params.n_candidate_features(self.x.ncols());
+
+        let mut best: Option<Split> = None;
+        let mut visited = 0;
+        for feature in features {
+            if visited >= n_candidates && best.is_some() {
+                break;
+            }
+
+            let (min, max) = indices.iter().fold((f32::MAX, f32::MIN), |(min, max), i| {
+                (
+                    min.min(self.x[(*i, feature)]),
+                    max.max(self.x[(*i, feature)]),
+                )
+            });
+            if min >= max {
+                continue;
+            }
+            visited += 1;
+
+            let split = match self.params.splitter {
+                Splitter::BEST => self.best_threshold(indices, feature),
+                Splitter::RANDOM => {
+                    let threshold = self.rng.gen_range(min..max);
+                    self.score_threshold(indices, feature, threshold)
+                }
+                Splitter::HISTOGRAM(_) => self.best_bin_edge(indices, feature),
+            };
+            if let Some(split) = split {
+                if best.as_ref().is_none_or(|best| split.score < best.score) {
+                    best = Some(split);
+                }
+            }
+        }
+
+        return best;
+    }
+
+    /// Scans every threshold between consecutive distinct values of `feature`. The score
+    /// is the weighted sum of the children impurities.
+    fn best_threshold(&self, indices: &[usize], feature: usize) -> Option<Split> {
+        let mut sorted = indices.to_vec();
+        sorted.sort_by(|a, b| {
+            self.x[(*a, feature)]
+                .partial_cmp(&self.x[(*b, feature)])
+                .unwrap()
+        });
+        let min_leaf = self.params.min_samples_leaf;
+        let mut accumulator = Accumulator::new(self.target, self.sample_weight, &sorted);
+
+        let mut best: Option<Split> = None;
+        for position in 1..sorted.len() {
+            accumulator.move_left(sorted[position - 1]);
+            let (previous, current) = (
+                self.x[(sorted[position - 1], feature)],
+                self.x[(sorted[position], feature)],
+            );
+            if position < min_leaf || sorted.len() - position < min_leaf || previous == current {
+                continue;
+            }
+
+            let score = match (self.target, self.params.criterion) {
+                (Target::VALUES(values), Criterion::MAE) => {
+                    let (left, right) = sorted.split_at(position);
+                    weighted_mae(values, self.sample_weight, left)
+                        + weighted_mae(values, self.sample_weight, right)
+                }
+                _ => accumulator.score(self.params.criterion),
+            };
+            if best.as_ref().is_none_or(|best| score < best.score) {
+                best = Some(Split {
+                    feature,
+                    threshold: previous + (current - previous) / 2.0,
+                    score,
+                });
+            }
+        }
+
+        return best;
+    }
+
+    /// Like `best_threshold` but only scans the precomputed bin edges of `feature`,
+    /// gathering the samples of each bin instead of sorting them.
+    fn best_bin_edge(&self, indices: &[usize], feature: usize) -> Option<Split> {
+        let edges = &self.bin_edges[feature];
+        let mut bins: Vec<Vec<usize>> = vec![Vec::new(); edges.len() + 1];
+        for i in indices {
+            let value = self.x[(*i, feature)];
+            bins[edges.partition_point(|edge| *edge < value)].push(*i);
+        }
+
+        let min_leaf = self.params.min_samples_leaf;
+        let mut accumulator = Accumulator::new(self.target, self.sample_weight, indices);
+        let mut n_left = 0;
+        let mut best: Option<Split> = None;
+        for (b, edge) in edges.iter().enumerate() {
+            for i in bins[b].iter() {
+                accumulator.move_left(*i);
+            }
+            n_left += bins[b].len();
+            if bins[b].is_empty() || n_left < min_leaf || indices.len() - n_left < min_leaf {
+                continue;
+            }
+
+            let split = match (self.target, self.params.criterion) {
+                (Target::VALUES(_), Criterion::MAE) => {
+                    self.score_threshold(indices, feature, *edge)
+                }
+                _ => Some(Split {
+                    feature,
+                    threshold: *edge,
+                    score: accumulator.score(self.params.criterion),
+                }),
+            };
+            if let Some(split) = split {
+                if best.as_ref().is_none_or(|best| split.score < best.score) {
+                    best = Some(split);
+                }
+            }
+        }
+
+        return best;
+    }
+
+    fn score_threshold(&self, indices: &[usize], feature: usize, threshold: f32) -> Option<Split> {
+        let (left, right): (Vec<usize>, Vec<usize>) = indices
+            .iter()
+            .partition(|i| self.x[(**i, feature)] <= threshold);
+        if left.len() < self.params.min_samples_leaf || right.len() < self.params.min_samples_leaf {
+            return None;
+        }
+
+        let (_, left_impurity, left_weight) = self.node_statistics(&left);
+        let (_, right_impurity, right_weight) = self.node_statistics(&right);
+        Some(Split {
+            feature,
+            threshold,
+            score: left_weight * left_impurity + right_weight * right_impurity,
+        })
+    }
+}
+
+/// Running sufficient statistics of the left and right children while sweeping sorted
+/// samples from left to right.
+struct Accumulator<'a> {
+    target: &'a Target<'a>,
+    sample_weight: &'a [f32],
+    left_counts: Vec<f32>,
+    total_counts: Vec<f32>,
+    // weight, weighted sum and weighted sum of squares of the values minus `offset`
+    left_sums: [f32; 3],
+    total_sums: [f32; 3],
+    offset: f32,
+}
+
+impl<'a> Accumulator<'a> {
+    fn new(target: &'a Target<'a>, sample_weight: &'a [f32], indices: &[usize]) -> Accumulator<'a> {
+        let mut accumulator = Accumulator {
+            target,
+            sample_weight,
+            left_counts: Vec::new(),
+            total_counts: Vec::new(),
+            left_sums: [0.0; 3],
+            total_sums: [0.0; 3],
+            offset: 0.0,
+        };
+        match target {
+            Target::CLASSES(labels, n_classes) => {
+                accumulator.left_counts = vec![0.0; *n_classes];
+                accumulator.total_counts = vec![0.0; *n_classes];
+                for i in indices {
+                    accumulator.total_counts[labels[*i]] += sample_weight[*i];
+                }
+            }
+            Target::VALUES(values) => {
+                // Centering keeps the single-pass variance accurate in f32.
+                accumulator.offset =
+                    indices.iter().map(|i| values[*i]).sum::<f32>() / indices.len() as f32;
+                for i in indices {
+                    let (w, value) = (sample_weight[*i], values[*i] - accumulator.offset);
+                    accumulator.total_sums[0] += w;
+                    accumulator.total_sums[1] += w * value;
+                    accumulator.total_sums[2] += w * value * value;
+                }
+            }
+        }
+
+        return accumulator;
+    }
+
+    fn move_left(&mut self, i: usize) {
+        let w = self.sample_weight[i];
+        match self.target {
+            Target::CLASSES(labels, _) => self.left_counts[labels[i]] += w,
+            Target::VALUES(values) => {
+                let value = values[i] - self.offset;
+                self.left_sums[0] += w;
+                self.left_sums[1] += w * value;
+                self.left_sums[2] += w * value * value;
+            }
+        }
+    }
+
+    fn score(&self, criterion: Criterion) -> f32 {
+        match self.target {
+            Target::CLASSES(_, _) => {
+                let right_counts: Vec<f32> = self
+                    .total_counts
+                    .iter()
+                    .zip(self.left_counts.iter())
+                    .map(|(total, left)| total - left)
+                    .collect();
+                let left_weight: f32 = self.left_counts.iter().sum();
+                let right_weight: f32 = right_counts.iter().sum();
+                left_weight * class_impurity(&self.left_counts, left_weight, criterion)
+                    + right_weight * class_impurity(&right_counts, right_weight, criterion)
+            }
+            Target::VALUES(_) => {
+                let right: Vec<f32> = (0..3)
+                    .map(|k| self.total_sums[k] - self.left_sums[k])
+                    .collect();
+                sum_squared_deviations(&self.left_sums) + sum_squared_deviations(&right)
+            }
+        }
+    }
+}
+
+/// Thresholds between at most `max_bins` quantile bins of `feature` over `indices`.
+fn bin_edges(x: &DMatrix<f32>, indices: &[usize], feature: usize, max_bins: usize) -> Vec<f32> {
+    let mut values: Vec<f32> = indices.iter().map(|i| x[(*i, feature)]).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut edges: Vec<f32> = (1..max_bins.max(2))
+        .map(|k| values[(k * values.len() / max_bins.max(2)).min(values.len() - 1)])
+        .collect();
+    edges.dedup();
+    // Splitting at the largest value would send every sample to the left.
+    edges.retain(|edge| *edge < values[values.len() - 1]);
+    return edges;
+}
+
+fn sum_squared_deviations(sums: &[f32]) -> f32 {
+    if sums[0] <= 0.0 {
+        return 0.0;
+    }
+    return (sums[2] - sums[1] * sums[1] / sums[0]).max(0.0);
+}
+
+fn class_impurity(counts: &[f32], weight: f32, criterion: Criterion) -> f32 {
+    if weight <= 0.0 {
+        return 0.0;
+    }
+    let proportions = counts.iter().map(|c| c / weight);
+    return match criterion {
+        Criterion::ENTROPY => proportions
+            .filter(|p| *p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum(),
+        _ => 1.0 - proportions.map(|p| p * p).sum::<f32>(),
+    };
+}
+
+/// Prediction and impurity of a regression node: the weighted mean and variance for
+/// `MSE`, the weighted median and mean absolute deviation for `MAE`.
+fn value_impurity(
+    values: &[f32],
+    sample_weight: &[f32],
+    indices: &[usize],
+    criterion: Criterion,
+) -> (f32, f32) {
+    let weight: f32 = indices.iter().map(|i| sample_weight[*i]).sum();
+    if weight <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    match criterion {
+        Criterion::MAE => {
+            let median = weighted_median(values, sample_weight, indices);
+            (
+                median,
+                weighted_mae(values, sample_weight, indices) / weight,
+            )
+        }
+        _ => {
+            let mean = indices
+                .iter()
+                .map(|i| sample_weight[*i] * values[*i])
+                .sum::<f32>()
+                / weight;
+            let variance = indices
+                .iter()
+                .map(|i| sample_weight[*i] * (values[*i] - mean).powf(2.0))
+                .sum::<f32>()
+                / weight;
+            (mean, variance)
+        }
+    }
+}
+
+/// Weighted sum of absolute deviations from the weighted median.
+fn weighted_mae(values: &[f32], sample_weight: &[f32], indices: &[usize]) -> f32 {
+    let median = weighted_median(values, sample_weight, indices);
+    return indices
+        .iter()
+        .map(|i| sample_weight[*i] * (values[*i] - median).abs())
+        .sum();
+}
+
+pub(crate) fn weighted_median(values: &[f32], sample_weight: &[f32], indices: &[usize]) -> f32 {
+    weighted_quantile(values, sample_weight, indices, 0.5)
+}
+
+/// Smallest value whose cumulative weight reaches `quantile` of the total weight.
+pub(crate) fn weighted_quantile(
+    values: &[f32],
+    sample_weight: &[f32],
+    indices: &[usize],
+    quantile: f32,
+) -> f32 {
+    if indices.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = indices.to_vec();
+    sorted.sort_by(|a, b| values[*a].partial_cmp(&values[*b]).unwrap());
+
+    let total: f32 = sorted.iter().map(|i| sample_weight[*i]).sum();
+    let mut cumulative = 0.0;
+    for i in sorted.iter() {
+        cumulative += sample_weight[*i];
+        if cumulative >= quantile * total {
+            return values[*i];
+        }
+    }
+
+    return values[*sorted.last().unwrap()];
+}
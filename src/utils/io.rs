@@ -1,7 +1,8 @@
-use nalgebra::Scalar;
+use nalgebra::{DMatrix, Scalar};
 use plotly::common::{Mode, Marker};
 use plotly::{Plot, Scatter};
-use std::{io::BufRead, str::FromStr};
+use std::io::{BufRead, Lines};
+use std::str::FromStr;
 
 
 #[allow(non_camel_case_types)]
@@ -27,6 +28,101 @@ where
     Ok((rows, cols, data))
 }
 
+/// Reads a CSV file with a header line in batches of at most `chunk_size` rows, so a
+/// file larger than memory can be fed to incremental learners one batch at a time.
+///
+/// The schema is fixed by the header: every row should hold one numeric value per
+/// header column, otherwise the batch is an error naming the offending line. Empty
+/// lines are skipped.
+pub struct CsvChunks<R: BufRead> {
+    pub header: Vec<String>,
+    pub chunk_size: usize,
+    lines: Lines<R>,
+    line_number: usize,
+}
+
+impl<R: BufRead> CsvChunks<R> {
+    pub fn new(input: R, chunk_size: usize) -> Result<CsvChunks<R>, String> {
+        if chunk_size == 0 {
+            return Err("chunk_size should be at least 1".to_string());
+        }
+        let mut lines = input.lines();
+        let header: Vec<String> = match lines.next() {
+            Some(line) => line
+                .map_err(|e| e.to_string())?
+                .split_terminator(',')
+                .map(|name| name.trim().to_string())
+                .collect(),
+            None => return Err("the CSV input is empty".to_string()),
+        };
+
+        Ok(CsvChunks {
+            header,
+            chunk_size,
+            lines,
+            line_number: 1,
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for CsvChunks<R> {
+    type Item = Result<DMatrix<f32>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ncols = self.header.len();
+        let mut data = Vec::with_capacity(self.chunk_size * ncols);
+        let mut rows = 0;
+
+        while rows < self.chunk_size {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Some(Err(e.to_string())),
+                None => break,
+            };
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let start = data.len();
+            for datum in line.split_terminator(',') {
+                match datum.trim().parse::<f32>() {
+                    Ok(value) => data.push(value),
+                    Err(_) => {
+                        return Some(Err(format!(
+                            "line {}: {:?} is not a number",
+                            self.line_number, datum
+                        )))
+                    }
+                }
+            }
+            if data.len() - start != ncols {
+                return Some(Err(format!(
+                    "line {}: expected {} values, got {}",
+                    self.line_number,
+                    ncols,
+                    data.len() - start
+                )));
+            }
+            rows += 1;
+        }
+
+        if rows == 0 {
+            return None;
+        }
+        Some(Ok(DMatrix::from_row_slice(rows, ncols, &data)))
+    }
+}
+
+/// Splits a batch into its features and its last column, the target.
+pub fn split_target(batch: &DMatrix<f32>) -> (DMatrix<f32>, DMatrix<f32>) {
+    let ncols = batch.ncols();
+    return (
+        batch.columns(0, ncols - 1).into_owned(),
+        batch.columns(ncols - 1, 1).into_owned(),
+    );
+}
+
 pub fn line_and_scatter_plot(x: Vec<f32>, y: Vec<Vec<f32>>, names: Vec<&str>) {
     let mut plot = Plot::new();
     let mut idx: usize = 0;
@@ -0,0 +1,162 @@
+use nalgebra::DMatrix;
+
+/// Regression metrics accumulated batch by batch, so that predictions over a stream
+/// never need to be held in memory. Every metric has one entry per target column and
+/// matches `mse_per_target`, `mae_per_target` and `r2_per_target` on the concatenated
+/// batches.
+#[derive(Clone, Debug, Default)]
+pub struct RegressionMetrics {
+    pub n_samples: usize,
+    sum_squared_error: Vec<f32>,
+    sum_absolute_error: Vec<f32>,
+    mean_y: Vec<f32>,
+    sum_squared_y: Vec<f32>,
+}
+
+impl RegressionMetrics {
+    pub fn new() -> RegressionMetrics {
+        RegressionMetrics::default()
+    }
+
+    pub fn update(&mut self, y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Result<(), String> {
+        if y.shape() != y_hat.shape() {
+            return Err(format!(
+                "y and y_hat should have the same shape. |y|: {:?}, |y_hat|: {:?}",
+                y.shape(),
+                y_hat.shape()
+            ));
+        }
+        if self.n_samples == 0 {
+            *self = RegressionMetrics {
+                n_samples: 0,
+                sum_squared_error: vec![0.0; y.ncols()],
+                sum_absolute_error: vec![0.0; y.ncols()],
+                mean_y: vec![0.0; y.ncols()],
+                sum_squared_y: vec![0.0; y.ncols()],
+            };
+        } else if y.ncols() != self.mean_y.len() {
+            return Err(format!(
+                "expected {} target columns, got {}",
+                self.mean_y.len(),
+                y.ncols()
+            ));
+        }
+
+        for i in 0..y.nrows() {
+            self.n_samples += 1;
+            for j in 0..y.ncols() {
+                let error = y[(i, j)] - y_hat[(i, j)];
+                self.sum_squared_error[j] += error * error;
+                self.sum_absolute_error[j] += error.abs();
+                // Welford's update of the variance of y, needed by r2.
+                let delta = y[(i, j)] - self.mean_y[j];
+                self.mean_y[j] += delta / self.n_samples as f32;
+                self.sum_squared_y[j] += delta * (y[(i, j)] - self.mean_y[j]);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn mse(&self) -> Vec<f32> {
+        return self.per_sample(&self.sum_squared_error);
+    }
+
+    pub fn mae(&self) -> Vec<f32> {
+        return self.per_sample(&self.sum_absolute_error);
+    }
+
+    pub fn r2(&self) -> Vec<f32> {
+        return self
+            .sum_squared_error
+            .iter()
+            .zip(self.sum_squared_y.iter())
+            .map(|(sse, sst)| 1.0 - sse / sst)
+            .collect();
+    }
+
+    fn per_sample(&self, sums: &[f32]) -> Vec<f32> {
+        return sums.iter().map(|s| s / self.n_samples as f32).collect();
+    }
+}
+
+/// Confusion matrix accumulated batch by batch; `confusion[(i, j)]` counts the samples
+/// of class `classes[i]` predicted as `classes[j]`. Classes are added in sorted order
+/// as they appear in the stream.
+#[derive(Clone, Debug)]
+pub struct ClassificationMetrics {
+    pub classes: Vec<f32>,
+    pub confusion: DMatrix<f32>,
+}
+
+impl ClassificationMetrics {
+    pub fn new() -> ClassificationMetrics {
+        ClassificationMetrics {
+            classes: Vec::new(),
+            confusion: DMatrix::zeros(0, 0),
+        }
+    }
+
+    pub fn update(&mut self, y: &[f32], y_hat: &[f32]) -> Result<(), String> {
+        if y.len() != y_hat.len() {
+            return Err(format!(
+                "y and y_hat should have the same length. |y|: {}, |y_hat|: {}",
+                y.len(),
+                y_hat.len()
+            ));
+        }
+
+        for (target, prediction) in y.iter().zip(y_hat.iter()) {
+            // Adding a class shifts the rows after it, so both labels are added before
+            // either index is looked up.
+            self.add_class(*target);
+            self.add_class(*prediction);
+            let i = self.class_index(*target);
+            let j = self.class_index(*prediction);
+            self.confusion[(i, j)] += 1.0;
+        }
+        Ok(())
+    }
+
+    pub fn n_samples(&self) -> usize {
+        return self.confusion.sum() as usize;
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        return self.confusion.trace() / self.confusion.sum();
+    }
+
+    /// Fraction of the samples predicted as each class that belong to it.
+    pub fn precision(&self) -> Vec<f32> {
+        return (0..self.classes.len())
+            .map(|k| self.confusion[(k, k)] / self.confusion.column(k).sum())
+            .collect();
+    }
+
+    /// Fraction of the samples of each class predicted as such.
+    pub fn recall(&self) -> Vec<f32> {
+        return (0..self.classes.len())
+            .map(|k| self.confusion[(k, k)] / self.confusion.row(k).sum())
+            .collect();
+    }
+
+    fn class_index(&self, label: f32) -> usize {
+        return self.classes.partition_point(|c| *c < label);
+    }
+
+    fn add_class(&mut self, label: f32) {
+        let position = self.class_index(label);
+        if self.classes.get(position) != Some(&label) {
+            self.classes.insert(position, label);
+            let confusion = std::mem::replace(&mut self.confusion, DMatrix::zeros(0, 0));
+            self.confusion = confusion
+                .insert_row(position, 0.0)
+                .insert_column(position, 0.0);
+        }
+    }
+}
+
+impl Default for ClassificationMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,4 +1,7 @@
 pub mod stats;
 pub mod io;
+pub mod metrics;
+pub mod preprocessing;
+pub mod solvers;
 pub mod utils;
 pub mod types;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use nalgebra::DMatrix;
+
+/// Standardizes every column to zero mean and unit variance.
+///
+/// Constant columns (such as the `x^0` column of `expand_matrix`) are left untouched so
+/// they can keep acting as an intercept.
+pub struct StandardScaler {
+    pub mean: DMatrix<f32>,
+    pub std: DMatrix<f32>,
+}
+
+impl StandardScaler {
+    pub fn fit(x: &DMatrix<f32>) -> StandardScaler {
+        let mut mean = DMatrix::<f32>::zeros(1, x.ncols());
+        let mut std = DMatrix::<f32>::from_element(1, x.ncols(), 1.0);
+
+        for j in 0..x.ncols() {
+            let column_mean = x.column(j).mean();
+            let column_std = x
+                .column(j)
+                .map(|v| (v - column_mean).powf(2.0))
+                .mean()
+                .sqrt();
+            if column_std > f32::EPSILON * column_mean.abs().max(1.0) {
+                mean[(0, j)] = column_mean;
+                std[(0, j)] = column_std;
+            }
+        }
+
+        StandardScaler { mean, std }
+    }
+
+    pub fn transform(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut transformed = x.clone();
+        for j in 0..x.ncols() {
+            for i in 0..x.nrows() {
+                transformed[(i, j)] = (x[(i, j)] - self.mean[(0, j)]) / self.std[(0, j)];
+            }
+        }
+
+        return transformed;
+    }
+
+    pub fn inverse_transform(&self, x: &DMatrix<f32>) -> DMatrix<f32> {
+        let mut original = x.clone();
+        for j in 0..x.ncols() {
+            for i in 0..x.nrows() {
+                original[(i, j)] = x[(i, j)] * self.std[(0, j)] + self.mean[(0, j)];
+            }
+        }
+
+        return original;
+    }
+}
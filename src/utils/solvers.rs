@@ -0,0 +1,336 @@
+use nalgebra::DMatrix;
+
+use crate::utils::types::TypeFactoration;
+
+/// Relative residual at which the iterative solvers (CG and LSQR) stop when no
+/// tolerance is given.
+const DEFAULT_ITERATIVE_TOLERANCE: f32 = 1e-6;
+
+/// Diagnostics reported back by [`LeastSquaresSolver::solve`].
+///
+/// The direct factorizations (SVD, QR, Cholesky, LU) report the numerical rank
+/// and the 2-norm condition number of the design matrix. SVD gives exact values,
+/// the other factorizations estimate them from the diagonal of their triangular
+/// factor. The iterative solvers report the number of iterations performed
+/// instead.
+#[derive(Clone, Debug)]
+pub struct SolverDiagnostics {
+    pub rank: Option<usize>,
+    pub condition_number: Option<f32>,
+    pub iterations: Option<usize>,
+}
+
+/// Solves the linear least squares problem `min ||a * x - b||` for every column of `b`.
+///
+/// `tolerance` is relative: for the direct factorizations it is the fraction of the
+/// largest singular value (or pivot) below which a direction is treated as rank
+/// deficient, defaulting to `f32::EPSILON * max(nrows, ncols)`; for CG and LSQR it is
+/// the relative residual of the normal equations at which iterations stop.
+#[derive(Clone, Debug)]
+pub struct LeastSquaresSolver {
+    pub type_factoration: TypeFactoration,
+    pub tolerance: Option<f32>,
+    pub max_iterations: usize,
+}
+
+impl LeastSquaresSolver {
+    pub fn new(type_factoration: TypeFactoration) -> LeastSquaresSolver {
+        LeastSquaresSolver {
+            type_factoration,
+            tolerance: None,
+            max_iterations: 1000,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> LeastSquaresSolver {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> LeastSquaresSolver {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn solve(
+        &self,
+        a: &DMatrix<f32>,
+        b: &DMatrix<f32>,
+    ) -> Result<(DMatrix<f32>, SolverDiagnostics), String> {
+        if a.nrows() != b.nrows() {
+            return Err(format!(
+                "a and b should have the same number of rows. |a|: {:?}, |b|: {:?}",
+                a.shape(),
+                b.shape()
+            ));
+        }
+
+        let tolerance = self
+            .tolerance
+            .unwrap_or(f32::EPSILON * a.nrows().max(a.ncols()) as f32);
+
+        match self.type_factoration {
+            TypeFactoration::SVD => solve_svd(a, b, tolerance),
+            TypeFactoration::QR => solve_qr(a, b, tolerance),
+            TypeFactoration::CHOLESKY => solve_cholesky(a, b, tolerance),
+            TypeFactoration::LU => solve_lu(a, b, tolerance),
+            TypeFactoration::CG => Ok(solve_cg(
+                a,
+                b,
+                self.tolerance.unwrap_or(DEFAULT_ITERATIVE_TOLERANCE),
+                self.max_iterations,
+            )),
+            TypeFactoration::LSQR => Ok(solve_lsqr(
+                a,
+                b,
+                self.tolerance.unwrap_or(DEFAULT_ITERATIVE_TOLERANCE),
+                self.max_iterations,
+            )),
+        }
+    }
+}
+
+impl Default for LeastSquaresSolver {
+    fn default() -> Self {
+        Self::new(TypeFactoration::SVD)
+    }
+}
+
+fn rank_and_condition(values: &[f32], tolerance: f32) -> (usize, f32) {
+    let max = values.iter().fold(0f32, |acc, v| acc.max(v.abs()));
+    let min = values.iter().fold(f32::MAX, |acc, v| acc.min(v.abs()));
+    let rank = values.iter().filter(|v| v.abs() > tolerance * max).count();
+    let condition_number = if min > 0.0 { max / min } else { f32::INFINITY };
+
+    (rank, condition_number)
+}
+
+fn solve_svd(
+    a: &DMatrix<f32>,
+    b: &DMatrix<f32>,
+    tolerance: f32,
+) -> Result<(DMatrix<f32>, SolverDiagnostics), String> {
+    let svd = a.clone().svd(true, true);
+    let singular_values = svd.singular_values.as_slice().to_vec();
+    let (rank, condition_number) = rank_and_condition(&singular_values, tolerance);
+    let max = singular_values.iter().fold(0f32, |acc, v| acc.max(*v));
+
+    let solution = svd.solve(b, tolerance * max)?;
+
+    Ok((
+        solution,
+        SolverDiagnostics {
+            rank: Some(rank),
+            condition_number: Some(condition_number),
+            iterations: None,
+        },
+    ))
+}
+
+fn solve_qr(
+    a: &DMatrix<f32>,
+    b: &DMatrix<f32>,
+    tolerance: f32,
+) -> Result<(DMatrix<f32>, SolverDiagnostics), String> {
+    if a.nrows() < a.ncols() {
+        return Err(format!(
+            "QR needs at least as many rows as columns, got {:?}; use SVD or LSQR instead",
+            a.shape()
+        ));
+    }
+
+    let qr = a.clone().qr();
+    let r = qr.r();
+    let diagonal = r.diagonal().as_slice().to_vec();
+    let (rank, condition_number) = rank_and_condition(&diagonal, tolerance);
+
+    let solution = r
+        .solve_upper_triangular(&(qr.q().transpose() * b))
+        .ok_or("R has a zero pivot; use SVD instead")?;
+
+    Ok((
+        solution,
+        SolverDiagnostics {
+            rank: Some(rank),
+            condition_number: Some(condition_number),
+            iterations: None,
+        },
+    ))
+}
+
+fn solve_cholesky(
+    a: &DMatrix<f32>,
+    b: &DMatrix<f32>,
+    tolerance: f32,
+) -> Result<(DMatrix<f32>, SolverDiagnostics), String> {
+    let cholesky = (a.transpose() * a)
+        .cholesky()
+        .ok_or("normal equations are not positive definite; use QR or SVD instead")?;
+
+    // cond(A) = sqrt(cond(A^T A)) and the diagonal of L already holds square roots.
+    let diagonal = cholesky.l_dirty().diagonal().as_slice().to_vec();
+    let (rank, condition_number) = rank_and_condition(&diagonal, tolerance);
+
+    Ok((
+        cholesky.solve(&(a.transpose() * b)),
+        SolverDiagnostics {
+            rank: Some(rank),
+            condition_number: Some(condition_number),
+            iterations: None,
+        },
+    ))
+}
+
+fn solve_lu(
+    a: &DMatrix<f32>,
+    b: &DMatrix<f32>,
+    tolerance: f32,
+) -> Result<(DMatrix<f32>, SolverDiagnostics), String> {
+    let lu = (a.transpose() * a).lu();
+
+    // The pivots of U estimate the eigenvalues of A^T A, hence the square root.
+    let diagonal: Vec<f32> = lu
+        .u()
+        .diagonal()
+        .iter()
+        .map(|value| value.abs().sqrt())
+        .collect();
+    let (rank, condition_number) = rank_and_condition(&diagonal, tolerance);
+
+    let solution = lu
+        .solve(&(a.transpose() * b))
+        .ok_or("normal equations are singular; use SVD instead")?;
+
+    Ok((
+        solution,
+        SolverDiagnostics {
+            rank: Some(rank),
+            condition_number: Some(condition_number),
+            iterations: None,
+        },
+    ))
+}
+
+/// Conjugate gradient on the normal equations (CGLS), never forming `a^T a`.
+fn solve_cg(
+    a: &DMatrix<f32>,
+    b: &DMatrix<f32>,
+    tolerance: f32,
+    max_iterations: usize,
+) -> (DMatrix<f32>, SolverDiagnostics) {
+    let mut solution = DMatrix::<f32>::zeros(a.ncols(), b.ncols());
+    let mut total_iterations = 0;
+
+    for target in 0..b.ncols() {
+        let mut x = DMatrix::<f32>::zeros(a.ncols(), 1);
+        let mut r = b.columns(target, 1).into_owned();
+        let mut s = a.transpose() * &r;
+        let mut p = s.clone();
+        let mut gamma = s.norm_squared();
+        let stop = tolerance * gamma.sqrt();
+
+        for _ in 0..max_iterations {
+            if gamma.sqrt() <= stop || gamma == 0.0 {
+                break;
+            }
+            total_iterations += 1;
+
+            let q = a * &p;
+            let alpha = gamma / q.norm_squared();
+            x += alpha * &p;
+            r -= alpha * &q;
+            s = a.transpose() * &r;
+
+            let gamma_new = s.norm_squared();
+            p = &s + (gamma_new / gamma) * &p;
+            gamma = gamma_new;
+        }
+
+        solution.set_column(target, &x.column(0));
+    }
+
+    (
+        solution,
+        SolverDiagnostics {
+            rank: None,
+            condition_number: None,
+            iterations: Some(total_iterations),
+        },
+    )
+}
+
+/// LSQR of Paige and Saunders, better behaved than CGLS on ill-conditioned problems.
+fn solve_lsqr(
+    a: &DMatrix<f32>,
+    b: &DMatrix<f32>,
+    tolerance: f32,
+    max_iterations: usize,
+) -> (DMatrix<f32>, SolverDiagnostics) {
+    let mut solution = DMatrix::<f32>::zeros(a.ncols(), b.ncols());
+    let mut total_iterations = 0;
+
+    for target in 0..b.ncols() {
+        let mut x = DMatrix::<f32>::zeros(a.ncols(), 1);
+        let mut u = b.columns(target, 1).into_owned();
+        let mut beta = u.norm();
+        if beta == 0.0 {
+            continue;
+        }
+        u /= beta;
+
+        let mut v = a.transpose() * &u;
+        let mut alpha = v.norm();
+        if alpha == 0.0 {
+            continue;
+        }
+        v /= alpha;
+
+        let mut w = v.clone();
+        let mut phi_bar = beta;
+        let mut rho_bar = alpha;
+        let stop = tolerance * alpha * beta;
+
+        for _ in 0..max_iterations {
+            total_iterations += 1;
+
+            u = a * &v - alpha * &u;
+            beta = u.norm();
+            if beta > 0.0 {
+                u /= beta;
+            }
+
+            v = a.transpose() * &u - beta * &v;
+            alpha = v.norm();
+            if alpha > 0.0 {
+                v /= alpha;
+            }
+
+            let rho = (rho_bar * rho_bar + beta * beta).sqrt();
+            let c = rho_bar / rho;
+            let s = beta / rho;
+            let theta = s * alpha;
+            rho_bar = -c * alpha;
+            let phi = c * phi_bar;
+            phi_bar *= s;
+
+            x += (phi / rho) * &w;
+            w = &v - (theta / rho) * &w;
+
+            // phi_bar * alpha * |c| equals ||a^T (b - a x)||.
+            if phi_bar * alpha * c.abs() <= stop || alpha == 0.0 {
+                break;
+            }
+        }
+
+        solution.set_column(target, &x.column(0));
+    }
+
+    (
+        solution,
+        SolverDiagnostics {
+            rank: None,
+            condition_number: None,
+            iterations: Some(total_iterations),
+        },
+    )
+}
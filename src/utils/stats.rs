@@ -4,6 +4,8 @@
 
 use nalgebra::DMatrix;
 
+use crate::utils::types::Penalty;
+
 pub fn mean(values: &Vec<f32>) -> f32 {
     if values.len() == 0 {
         return 0f32;
@@ -47,85 +49,266 @@ pub fn covariance(x_values: &Vec<f32>, y_values: &Vec<f32>) -> f32 {
     return covariance / length as f32;
 }
 
+pub fn weighted_mean(values: &Vec<f32>, weights: &Vec<f32>) -> f32 {
+    if values.len() != weights.len() {
+        panic!("values and weights must be of equal length.");
+    }
+
+    let total_weight = weights.iter().sum::<f32>();
+    if total_weight == 0f32 {
+        return 0f32;
+    }
+
+    return values.iter().zip(weights).map(|(v, w)| v * w).sum::<f32>() / total_weight;
+}
+
+pub fn weighted_variance(values: &Vec<f32>, weights: &Vec<f32>) -> f32 {
+    return weighted_covariance(values, values, weights);
+}
+
+pub fn weighted_covariance(x_values: &Vec<f32>, y_values: &Vec<f32>, weights: &Vec<f32>) -> f32 {
+    if x_values.len() != y_values.len() || x_values.len() != weights.len() {
+        panic!("x_values, y_values and weights must be of equal length.");
+    }
+
+    let total_weight = weights.iter().sum::<f32>();
+    if total_weight == 0f32 {
+        return 0f32;
+    }
+
+    let mean_x = weighted_mean(x_values, weights);
+    let mean_y = weighted_mean(y_values, weights);
+
+    let mut covariance: f32 = 0f32;
+    for i in 0..x_values.len() {
+        covariance += weights[i] * (x_values[i] - mean_x) * (y_values[i] - mean_y)
+    }
+
+    return covariance / total_weight;
+}
+
+/// Mean squared error over every sample and target column.
 pub fn mse(y: DMatrix<f32>, y_hat: DMatrix<f32>) -> f32 {
-    let n_rows = y.nrows() as f32;
-    return (0..y.nrows())
-        .map(|idx| ((y.row(idx)[0] - y_hat.row(idx)[0]).powf(2.0)))
+    return mean_per_target(&y, &y_hat, |residual| residual.powf(2.0))
+        .iter()
         .sum::<f32>()
-        / n_rows;
+        / y.ncols() as f32;
 }
 
+/// Mean absolute error over every sample and target column.
 pub fn mae(y: DMatrix<f32>, y_hat: DMatrix<f32>) -> f32 {
-    let n_rows = y.nrows() as f32;
-    return (0..y.nrows())
-        .map(|idx| ((y.row(idx)[0] - y_hat.row(idx)[0]).abs()))
+    return mean_per_target(&y, &y_hat, |residual| residual.abs())
+        .iter()
         .sum::<f32>()
-        / n_rows;
+        / y.ncols() as f32;
 }
 
-pub fn update_weights_mse_vanilla(
-    x: &DMatrix<f32>,
-    y: &DMatrix<f32>,
-    y_hat: &DMatrix<f32>,
-    lr: f32,
-) -> (DMatrix<f32>, f32) {
-    let (nrows, ncols) = x.shape();
-    let dif = DMatrix::from_vec(nrows, 1, (y - y_hat).data.as_vec().to_vec());
-    let mut dw = vec![0.0; ncols];
-    for j in 0..ncols {
-        for i in 0..nrows {
-            dw[j] += &dif[(i, 0)] * x[(i, j)];
-        }
-        dw[j] *= 1.0 / (2.0 * nrows as f32) * lr;
-    }
-    let db = (dif.iter().sum::<f32>()) * (1.0 / (2.0 * nrows as f32)) * lr;
-    return (DMatrix::from_vec(ncols, 1, dw.clone()), db);
+/// Mean squared error of each target column.
+pub fn mse_per_target(y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Vec<f32> {
+    return mean_per_target(y, y_hat, |residual| residual.powf(2.0));
 }
 
-pub fn update_weights_mse(
-    x: &DMatrix<f32>,
-    y: &DMatrix<f32>,
-    y_hat: &DMatrix<f32>,
-    lr: f32,
-) -> (DMatrix<f32>, f32) {
-    let (nrows, ncols) = x.shape();
-    let dif = DMatrix::from_vec(nrows, 1, (y - y_hat).data.as_vec().to_vec());
-
-    let dw = (0..ncols)
-        .map(|i| (1.0 / (2.0 * nrows as f32)) * x.column(i).dot(&dif))
+/// Mean absolute error of each target column.
+pub fn mae_per_target(y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Vec<f32> {
+    return mean_per_target(y, y_hat, |residual| residual.abs());
+}
+
+/// Coefficient of determination of each target column.
+pub fn r2_per_target(y: &DMatrix<f32>, y_hat: &DMatrix<f32>) -> Vec<f32> {
+    let mse = mse_per_target(y, y_hat);
+    return (0..y.ncols())
+        .map(|j| {
+            let column: Vec<f32> = y.column(j).iter().cloned().collect();
+            1.0 - mse[j] / variance(&column)
+        })
         .collect();
+}
+
+fn mean_per_target<F: Fn(f32) -> f32>(y: &DMatrix<f32>, y_hat: &DMatrix<f32>, f: F) -> Vec<f32> {
+    if y.shape() != y_hat.shape() {
+        panic!(
+            "y and y_hat must have the same shape. |y|: {:?}, |y_hat|: {:?}",
+            y.shape(),
+            y_hat.shape()
+        );
+    }
 
-    let db: f32 = (dif.iter().sum::<f32>()) * (1.0 / (2.0 * nrows as f32)) * lr;
-    return (DMatrix::from_vec(x.ncols(), 1, dw), db);
+    let n_rows = y.nrows() as f32;
+    return (0..y.ncols())
+        .map(|j| {
+            (0..y.nrows())
+                .map(|i| f(y[(i, j)] - y_hat[(i, j)]))
+                .sum::<f32>()
+                / n_rows
+        })
+        .collect();
 }
 
-pub fn update_weights_mae(
+/// Gradient of a linear model with one column of weights per target: with `d` the
+/// derivatives of the loss with respect to the predictions, returns
+/// `(x^T (w * d) / sum(w), sum(w * d) / sum(w))` where the bias gradient is a row with
+/// one entry per target. A missing `sample_weight` weights every sample equally.
+pub fn gradient_from_derivatives_per_target(
     x: &DMatrix<f32>,
-    y: &DMatrix<f32>,
-    y_hat: &DMatrix<f32>,
-    lr: f32,
-) -> (DMatrix<f32>, f32) {
-    let (nrows, ncols) = x.shape();
-    let dif = DMatrix::from_vec(nrows, 1, (y - y_hat).data.as_vec().to_vec());
-
-    let dw = (0..ncols)
-        .map(|i| (1.0 / dif.abs().sum()) * x.column(i).dot(&dif) * lr)
-        .collect();
+    derivatives: &DMatrix<f32>,
+    sample_weight: Option<&DMatrix<f32>>,
+) -> (DMatrix<f32>, DMatrix<f32>) {
+    let mut weighted = derivatives.clone();
+    let total = match sample_weight {
+        Some(sample_weight) => {
+            for i in 0..weighted.nrows() {
+                for j in 0..weighted.ncols() {
+                    weighted[(i, j)] *= sample_weight[(i, 0)];
+                }
+            }
+            sample_weight.sum()
+        }
+        None => x.nrows() as f32,
+    };
 
-    let db: f32 = (-1.0 / dif.abs().sum()) * lr * (dif.sum());
-    return (DMatrix::from_vec(ncols, 1, dw), db);
+    let dw = x.transpose() * &weighted / total;
+    let db =
+        DMatrix::from_iterator(1, weighted.ncols(), weighted.row_sum().iter().cloned()) / total;
+    return (dw, db);
 }
 
-pub fn update_weights_huber(
-    x: &DMatrix<f32>,
-    y: &DMatrix<f32>,
-    y_hat: &DMatrix<f32>,
-    lr: f32,
-    delta: f32,
-) -> (DMatrix<f32>, f32) {
-    if (y - y_hat).abs().sum() <= delta {
-        return update_weights_mae(x, y, y_hat, lr);
-    } else {
-        return update_weights_mse(x, y, y_hat, lr);
+pub fn penalty(weights: &DMatrix<f32>, penalty: &Penalty) -> f32 {
+    let l1 = weights.abs().sum();
+    let l2 = weights.norm_squared() / 2.0;
+    return match *penalty {
+        Penalty::L1(alpha) => alpha * l1,
+        Penalty::L2(alpha) => alpha * l2,
+        Penalty::ELASTICNET(alpha, l1_ratio) => alpha * (l1_ratio * l1 + (1.0 - l1_ratio) * l2),
     };
 }
+
+/// (Sub)gradient of `penalty(weights, penalty)`, taking 0 as the subgradient of `|0|`.
+pub fn gradient_penalty(weights: &DMatrix<f32>, penalty: &Penalty) -> DMatrix<f32> {
+    let sign = weights.map(|w| {
+        if w > 0.0 {
+            1.0
+        } else if w < 0.0 {
+            -1.0
+        } else {
+            0.0
+        }
+    });
+    return match *penalty {
+        Penalty::L1(alpha) => sign * alpha,
+        Penalty::L2(alpha) => weights * alpha,
+        Penalty::ELASTICNET(alpha, l1_ratio) => {
+            (sign * l1_ratio + weights * (1.0 - l1_ratio)) * alpha
+        }
+    };
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000000000190015;
+    for (j, c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + j as f64);
+    }
+
+    return -tmp + (2.5066282746310005 * series / x).ln();
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+pub fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    // The continued fraction converges quickly only on this side of the mean.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        return front * beta_continued_fraction(x, a, b) / a;
+    } else {
+        return 1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b;
+    }
+}
+
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut fraction = d;
+
+    for m in 1..300 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+
+        for numerator in [even, odd] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            fraction *= d * c;
+        }
+
+        if (d * c - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+
+    return fraction;
+}
+
+pub fn student_t_cdf(t: f64, degrees_of_freedom: f64) -> f64 {
+    let tail = 0.5
+        * incomplete_beta(
+            degrees_of_freedom / (degrees_of_freedom + t * t),
+            degrees_of_freedom / 2.0,
+            0.5,
+        );
+
+    return if t > 0.0 { 1.0 - tail } else { tail };
+}
+
+/// Inverse of `student_t_cdf`, found by bisection.
+pub fn student_t_ppf(p: f64, degrees_of_freedom: f64) -> f64 {
+    let (mut low, mut high) = (-1e4, 1e4);
+    for _ in 0..200 {
+        let middle = (low + high) / 2.0;
+        if student_t_cdf(middle, degrees_of_freedom) < p {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    return (low + high) / 2.0;
+}
+
+pub fn f_cdf(f: f64, d1: f64, d2: f64) -> f64 {
+    if f <= 0.0 {
+        return 0.0;
+    }
+
+    return incomplete_beta(d1 * f / (d1 * f + d2), d1 / 2.0, d2 / 2.0);
+}
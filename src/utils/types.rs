@@ -1,13 +1,112 @@
+/// Loss minimised by gradient-trained regressions. The parameter of `HUBER` and
+/// `TUKEY` is the residual threshold, of `QUANTILE` the target quantile in `(0, 1)` and
+/// of `EPSILON_INSENSITIVE` the width of the tube in which residuals are ignored.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TypeRegression {
     MAE,
     MSE,
-    HUBER,
+    HUBER(f32),
+    LOGCOSH,
+    QUANTILE(f32),
+    EPSILON_INSENSITIVE(f32),
+    TUKEY(f32),
 }
 
+/// Penalty on the weights of gradient-trained models: `L1(alpha)` adds `alpha * |w|_1`
+/// to the loss, `L2(alpha)` adds `alpha / 2 * |w|^2` and `ELASTICNET(alpha, l1_ratio)`
+/// mixes both. Biases are never penalised.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Penalty {
+    L1(f32),
+    L2(f32),
+    ELASTICNET(f32, f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TypeFactoration {
     SVD,
     QR,
     LU,
+    CHOLESKY,
+    CG,
+    LSQR,
+}
+
+/// How decompositions compute their singular value decomposition: `FULL` is exact,
+/// `RANDOMIZED` approximates the leading components through random projections and
+/// is much cheaper when only a few components of a large matrix are needed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TypeSVD {
+    FULL,
+    RANDOMIZED,
+}
+
+/// Impurity measure minimised by decision tree splits: `GINI` and `ENTROPY` for
+/// classification, `MSE` and `MAE` for regression.
+///
+/// `MAE` recomputes the weighted median of both children at every candidate threshold,
+/// so the best split of a node of `n` samples costs `O(n^2 log n)` per feature instead
+/// of `O(n log n)`; prefer a `HISTOGRAM` splitter on large datasets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Criterion {
+    GINI,
+    ENTROPY,
+    MSE,
+    MAE,
+}
+
+/// Number of features drawn at random as split candidates at every tree node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaxFeatures {
+    ALL,
+    SQRT,
+    LOG2,
+    N(usize),
+    FRACTION(f32),
+}
+
+/// How a tree node picks its threshold: `BEST` scans every threshold of every
+/// candidate feature, `RANDOM` draws one threshold per feature (extremely randomized
+/// trees) and `HISTOGRAM(max_bins)` only scans the boundaries of at most `max_bins`
+/// quantile bins per feature, which avoids sorting at every node on large data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Splitter {
+    BEST,
+    RANDOM,
+    HISTOGRAM(usize),
+}
+
+/// Built-in kernels: `POLY(degree, gamma, coef0)` is `(gamma * <a, b> + coef0)^degree`,
+/// `RBF(gamma)` is `exp(-gamma * |a - b|^2)`, `LAPLACIAN(gamma)` is
+/// `exp(-gamma * |a - b|_1)` and `SIGMOID(gamma, coef0)` is `tanh(gamma * <a, b> + coef0)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TypeKernel {
+    LINEAR,
+    POLY(i32, f32, f32),
+    RBF(f32),
+    LAPLACIAN(f32),
+    SIGMOID(f32, f32),
+}
+
+/// Activation of the hidden layers of a neural network.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    IDENTITY,
+    RELU,
+    TANH,
+    SIGMOID,
+}
+
+/// Initial weights of a dense layer with `n_in` inputs and `n_out` outputs: `XAVIER`
+/// draws from `U(-sqrt(6 / (n_in + n_out)), sqrt(6 / (n_in + n_out)))`, `HE` from
+/// `N(0, 2 / n_in)` (suited to ReLU) and `NORMAL(std)` from `N(0, std^2)`. Biases
+/// start at zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Initialization {
+    XAVIER,
+    HE,
+    NORMAL(f32),
 }
 
 pub enum Option<TypeFactoration> {
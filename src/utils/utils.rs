@@ -2,7 +2,7 @@ use std::vec;
 
 use nalgebra::DMatrix;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 // use std::default::Default;
 //use smartcore::linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix};
 
@@ -59,6 +59,94 @@ pub fn train_test_split(
     (x_train, x_test, y_train, y_test)
 }
 
+/// Splits `0..n` into consecutive batches of `batch_size` indices, the last one possibly
+/// smaller, shuffling the order first when `shuffle` is set.
+pub fn batch_indices(n: usize, batch_size: usize, shuffle: bool) -> Vec<Vec<usize>> {
+    return batch_indices_with_rng(n, batch_size, shuffle, &mut thread_rng());
+}
+
+/// [`batch_indices`] shuffling with `rng`, for reproducible batches.
+pub fn batch_indices_with_rng<R: Rng>(
+    n: usize,
+    batch_size: usize,
+    shuffle: bool,
+    rng: &mut R,
+) -> Vec<Vec<usize>> {
+    if batch_size == 0 {
+        panic!("batch_size should be greater than 0");
+    }
+
+    let mut indices = (0..n).collect::<Vec<usize>>();
+    if shuffle {
+        indices.shuffle(rng);
+    }
+
+    return indices
+        .chunks(batch_size)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+}
+
+/// Checks that `sample_weight` is a column of `nrows` non-negative weights that are not
+/// all zero.
+pub fn validate_sample_weight(sample_weight: &DMatrix<f32>, nrows: usize) -> Result<(), String> {
+    if sample_weight.shape() != (nrows, 1) {
+        return Err(format!(
+            "sample_weight should be a column with one weight per sample. |sample_weight|: {:?}, samples: {}",
+            sample_weight.shape(),
+            nrows
+        ));
+    }
+    if sample_weight.iter().any(|w| *w < 0.0 || !w.is_finite()) || sample_weight.sum() <= 0.0 {
+        return Err("sample_weight should be finite, non-negative and not all zero".to_string());
+    }
+
+    Ok(())
+}
+
+/// Multiplies every row of `m` by the square root of its weight, which turns weighted
+/// least squares into ordinary least squares.
+pub fn scale_rows_by_sqrt_weight(m: &DMatrix<f32>, sample_weight: &DMatrix<f32>) -> DMatrix<f32> {
+    let mut scaled = m.clone();
+    for i in 0..m.nrows() {
+        let factor = sample_weight[(i, 0)].sqrt();
+        for j in 0..m.ncols() {
+            scaled[(i, j)] *= factor;
+        }
+    }
+
+    return scaled;
+}
+
+/// Matrix of independent standard normal samples, drawn with the Box-Muller transform.
+pub fn random_normal_matrix<R: Rng + ?Sized>(
+    nrows: usize,
+    ncols: usize,
+    rng: &mut R,
+) -> DMatrix<f32> {
+    DMatrix::from_fn(nrows, ncols, |_, _| {
+        let u1: f32 = 1.0 - rng.gen::<f32>();
+        let u2: f32 = rng.gen::<f32>();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+    })
+}
+
+/// Sorted distinct labels of the first column of `y`.
+pub fn unique_classes(y: &DMatrix<f32>) -> Vec<f32> {
+    let mut classes: Vec<f32> = y.column(0).iter().cloned().collect();
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    classes.dedup();
+    return classes;
+}
+
+/// Label of the highest scoring column of every row of `scores`.
+pub fn argmax_classes(scores: &DMatrix<f32>, classes: &[f32]) -> Vec<f32> {
+    return scores
+        .row_iter()
+        .map(|row| classes[row.transpose().argmax().0])
+        .collect();
+}
+
 pub fn accuracy(y_hat: Vec<f32>, y_target: Vec<f32>) -> f32 {
     return (y_hat
         .iter()
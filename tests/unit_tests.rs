@@ -1,5 +1,65 @@
 use nalgebra::DMatrix;
-use rust_regressions::utils::utils::slice_by_row;
+use rust_regressions::classifications::linear_discriminant_analysis::LinearDiscriminantAnalysis;
+use rust_regressions::classifications::quadratic_discriminant_analysis::QuadraticDiscriminantAnalysis;
+use rust_regressions::clusters::kmeans::KMeans;
+use rust_regressions::clusters::knn::KNN;
+use rust_regressions::decomposition::pca::PCA;
+use rust_regressions::decomposition::truncated_svd::TruncatedSVD;
+use rust_regressions::ensembles::extra_trees::{extra_trees_classifier, extra_trees_regressor};
+use rust_regressions::ensembles::gradient_boosting_classifier::GradientBoostingClassifier;
+use rust_regressions::ensembles::gradient_boosting_regressor::GradientBoostingRegressor;
+use rust_regressions::ensembles::random_forest_classifier::RandomForestClassifier;
+use rust_regressions::ensembles::random_forest_regressor::RandomForestRegressor;
+use rust_regressions::gaussian_process::gaussian_process_regressor::GaussianProcessRegressor;
+use rust_regressions::gaussian_process::kernels::GPKernel;
+use rust_regressions::kernels::kernel::{
+    CustomKernel, Kernel, KernelProduct, KernelSum, ScaledKernel,
+};
+use rust_regressions::losses::classification::{CrossEntropy, Hinge, LogLoss, ModifiedHuber};
+use rust_regressions::losses::loss::Loss;
+use rust_regressions::losses::regression::SquaredError;
+use rust_regressions::naive_bayes::base::NaiveBayes;
+use rust_regressions::naive_bayes::bernoulli_naive_bayes::BernoulliNaiveBayes;
+use rust_regressions::naive_bayes::gaussian_naive_bayes::GaussianNaiveBayes;
+use rust_regressions::naive_bayes::multinomial_naive_bayes::MultinomialNaiveBayes;
+use rust_regressions::neural_network::mlp_classifier::MLPClassifier;
+use rust_regressions::neural_network::mlp_regressor::MLPRegressor;
+use rust_regressions::online::incremental::{IncrementalModel, IncrementalTrainer};
+use rust_regressions::online::passive_aggressive::{
+    PassiveAggressiveClassifier, PassiveAggressiveRegressor,
+};
+use rust_regressions::online::perceptron::Perceptron;
+use rust_regressions::online::sgd_classifier::SGDClassifier;
+use rust_regressions::optim::adagrad::Adagrad;
+use rust_regressions::optim::adam::Adam;
+use rust_regressions::optim::callbacks::Callback;
+use rust_regressions::optim::optimizer::Optimizer;
+use rust_regressions::optim::rmsprop::RMSProp;
+use rust_regressions::optim::schedules::{
+    CosineAnnealing, CosineWarmRestarts, LearningRateSchedule, ReduceOnPlateau, StepDecay,
+};
+use rust_regressions::optim::sgd::SGD;
+use rust_regressions::optim::training::{train, FitConfig, GradientModel, TrainingHistory};
+use rust_regressions::regressions::kernel_ridge::KernelRidge;
+use rust_regressions::regressions::linear_regression::LinearRegression;
+use rust_regressions::regressions::polynomial_regression::PolynomialRegression;
+use rust_regressions::regressions::rbf_regression::RBFRegression;
+use rust_regressions::regressions::simple_linear_regression::SimpleLinearRegression;
+use rust_regressions::svm::linear_svm::{LinearSVC, LinearSVR};
+use rust_regressions::svm::svc::SVC;
+use rust_regressions::svm::svr::SVR;
+use rust_regressions::trees::decision_tree_classifier::DecisionTreeClassifier;
+use rust_regressions::trees::decision_tree_regressor::DecisionTreeRegressor;
+use rust_regressions::utils::io::{parse_csv, split_target, CsvChunks};
+use rust_regressions::utils::metrics::{ClassificationMetrics, RegressionMetrics};
+use rust_regressions::utils::solvers::LeastSquaresSolver;
+use rust_regressions::utils::stats::{mae, mae_per_target, mse, mse_per_target, r2_per_target};
+use rust_regressions::utils::types::{
+    Activation, Criterion, Initialization, Penalty, Splitter, TypeFactoration, TypeKernel,
+    TypeRegression, TypeSVD,
+};
+use rust_regressions::utils::utils::{accuracy, batch_indices, slice_by_row};
+use std::{fs::File, io::BufReader};
 
 pub fn get_dmatrix() -> DMatrix<f32> {
     return DMatrix::from_row_slice(
@@ -24,3 +84,1690 @@ fn test_data_slice_by_row() {
 
     assert_eq!(slice_by_row(&input, &[0, 2]).eq(&expected_dmatrix), true);
 }
+
+#[test]
+fn test_least_squares_solvers_agree() {
+    let a = DMatrix::from_row_slice(5, 2, &[1.0, 1.0, 2.0, 1.0, 3.0, 1.0, 4.0, 1.0, 5.0, 1.0]);
+    let b = DMatrix::from_row_slice(5, 1, &[3.1, 4.9, 7.0, 9.1, 10.9]);
+
+    for type_factoration in [
+        TypeFactoration::SVD,
+        TypeFactoration::QR,
+        TypeFactoration::LU,
+        TypeFactoration::CHOLESKY,
+        TypeFactoration::CG,
+        TypeFactoration::LSQR,
+    ] {
+        let (solution, diagnostics) = LeastSquaresSolver::new(type_factoration)
+            .solve(&a, &b)
+            .unwrap();
+
+        assert!((solution[(0, 0)] - 1.98).abs() < 1e-3);
+        assert!((solution[(1, 0)] - 1.06).abs() < 1e-3);
+        assert!(diagnostics.rank.unwrap_or(2) == 2);
+    }
+
+    // Solver errors reach the caller of fit and leave the model unfitted.
+    let mut model = LinearRegression::with_solver(LeastSquaresSolver::new(TypeFactoration::QR));
+    assert!(model
+        .fit(&DMatrix::from_element(2, 3, 1.0), &DMatrix::zeros(2, 1))
+        .is_err());
+    assert!(model.coefficients.is_none());
+}
+
+#[test]
+fn test_optimizers_minimize_quadratic() {
+    let target = DMatrix::from_vec(2, 1, vec![3.0, -2.0]);
+    let optimizers: Vec<Box<dyn Optimizer>> = vec![
+        Box::new(SGD::new(0.1)),
+        Box::new(SGD::with_momentum(0.1, 0.9)),
+        Box::new(SGD::nesterov(0.1, 0.9)),
+        Box::new(Adagrad::new(0.5)),
+        Box::new(RMSProp::new(0.05)),
+        Box::new(Adam::new(0.1)),
+    ];
+
+    for mut optimizer in optimizers {
+        let mut parameters = DMatrix::<f32>::zeros(2, 1);
+        for _ in 0..1000 {
+            let gradient = (&parameters - &target) * 2.0;
+            optimizer.step(&mut parameters, &gradient);
+        }
+
+        assert!((&parameters - &target).norm() < 1e-2);
+    }
+}
+
+#[test]
+fn test_batch_indices_cover_all_samples() {
+    let batches = batch_indices(10, 3, true);
+    let mut indices: Vec<usize> = batches.iter().flatten().cloned().collect();
+    indices.sort();
+
+    assert_eq!(batches.len(), 4);
+    assert_eq!(batches[3].len(), 1);
+    assert_eq!(indices, (0..10).collect::<Vec<usize>>());
+}
+
+#[test]
+fn test_polynomial_regression_mini_batch() {
+    let x = DMatrix::from_vec(100, 1, (0..100).map(|i| i as f32 / 100.0).collect());
+    let y = x.map(|v| v * v);
+
+    let mut model = PolynomialRegression::new(3, TypeRegression::MSE).with_seed(7);
+    model
+        .fit_with_config(
+            &x,
+            &y,
+            &mut Adam::new(0.01),
+            &mut FitConfig::mini_batch(200, 16).with_seed(7),
+        )
+        .unwrap();
+
+    assert!(model.loss(&x, &y, None) < 1e-3);
+
+    // The same seeds replay the same initialisation and batches.
+    let mut replay = PolynomialRegression::new(3, TypeRegression::MSE).with_seed(7);
+    replay
+        .fit_with_config(
+            &x,
+            &y,
+            &mut Adam::new(0.01),
+            &mut FitConfig::mini_batch(200, 16).with_seed(7),
+        )
+        .unwrap();
+    assert_eq!(replay.coefficients, model.coefficients);
+}
+
+#[test]
+fn test_polynomial_regression_divergence_is_an_error() {
+    let x = DMatrix::from_vec(100, 1, (0..100).map(|i| i as f32 / 10.0).collect());
+    let y = x.map(|v| v * v);
+
+    let mut model = PolynomialRegression::new(4, TypeRegression::MSE);
+    let initial_coefficients = model.coefficients.clone();
+
+    assert!(model.fit(&x, &y, 100, 10.0).is_err());
+    // The diverged weights are discarded.
+    assert_eq!(model.coefficients, initial_coefficients);
+    assert_eq!(model.bias, DMatrix::zeros(1, 1));
+}
+
+#[test]
+fn test_learning_rate_schedules() {
+    assert_eq!(StepDecay::new(10, 0.5).learning_rate(25, 1.0, None), 0.25);
+    assert!((CosineAnnealing::new(10, 0.0).learning_rate(5, 1.0, None) - 0.5).abs() < 1e-6);
+    assert_eq!(
+        CosineWarmRestarts::new(5, 2, 0.0).learning_rate(15, 1.0, None),
+        1.0
+    );
+
+    let mut plateau = ReduceOnPlateau::new(0.5, 1);
+    let rates: Vec<f32> = [1.0, 1.0, 1.0, 1.0]
+        .iter()
+        .enumerate()
+        .map(|(epoch, loss)| plateau.learning_rate(epoch, 1.0, Some(*loss)))
+        .collect();
+    assert_eq!(rates, vec![1.0, 1.0, 0.5, 0.5]);
+}
+
+struct StopAfter(usize);
+
+impl Callback for StopAfter {
+    fn on_epoch_end(&mut self, epoch: usize, _: &TrainingHistory, _: &DMatrix<f32>) -> bool {
+        epoch + 1 < self.0
+    }
+}
+
+#[test]
+fn test_training_history_and_callbacks() {
+    let x = DMatrix::from_vec(100, 1, (0..100).map(|i| i as f32 / 100.0).collect());
+    let y = x.map(|v| v * v);
+
+    let mut model = PolynomialRegression::new(3, TypeRegression::MSE);
+    let history = model
+        .fit_with_config(
+            &x,
+            &y,
+            &mut SGD::new(0.1),
+            &mut FitConfig::new(100)
+                .with_validation(0.2, 100)
+                .with_callback(StopAfter(3)),
+        )
+        .unwrap();
+
+    assert_eq!(history.train_loss.len(), 3);
+    assert_eq!(history.validation_loss.len(), 3);
+    assert!(history.stopped_early);
+}
+
+/// Walks its single parameter up by one every step, so the loss against a constant
+/// target of 3 falls to zero at the third epoch and grows again afterwards.
+struct Overshoot(DMatrix<f32>);
+
+impl GradientModel for Overshoot {
+    fn parameters(&self) -> DMatrix<f32> {
+        self.0.clone()
+    }
+
+    fn set_parameters(&mut self, parameters: &DMatrix<f32>) {
+        self.0 = parameters.clone();
+    }
+
+    fn loss(&self, _: &DMatrix<f32>, y: &DMatrix<f32>, _: Option<&DMatrix<f32>>) -> f32 {
+        y.map(|v| (self.0[0] - v).powi(2)).mean()
+    }
+
+    fn gradient(
+        &self,
+        _: &DMatrix<f32>,
+        _: &DMatrix<f32>,
+        _: Option<&DMatrix<f32>>,
+    ) -> DMatrix<f32> {
+        DMatrix::from_element(1, 1, -1.0)
+    }
+}
+
+fn overshoot_data() -> (DMatrix<f32>, DMatrix<f32>) {
+    (DMatrix::zeros(10, 1), DMatrix::from_element(10, 1, 3.0))
+}
+
+#[test]
+fn test_training_stops_once_improvement_is_below_tolerance() {
+    let (x, y) = overshoot_data();
+    let mut model = Overshoot(DMatrix::zeros(1, 1));
+    let mut config = FitConfig::new(100).with_tolerance(2.0);
+    config.patience = 1;
+
+    let history = train(&mut model, &mut SGD::new(1.0), &x, &y, None, &mut config).unwrap();
+
+    // The losses are 4, 1 and 0: the last improvement is smaller than the tolerance.
+    assert_eq!(history.train_loss, vec![4.0, 1.0, 0.0]);
+    assert!(history.stopped_early);
+    assert_eq!(history.best_epoch, Some(2));
+}
+
+#[test]
+fn test_validation_patience_restores_best_weights() {
+    let (x, y) = overshoot_data();
+    let mut model = Overshoot(DMatrix::zeros(1, 1));
+
+    let history = train(
+        &mut model,
+        &mut SGD::new(1.0),
+        &x,
+        &y,
+        None,
+        &mut FitConfig::new(100).with_validation(0.5, 2),
+    )
+    .unwrap();
+
+    assert_eq!(history.validation_loss, vec![4.0, 1.0, 0.0, 1.0, 4.0]);
+    assert!(history.stopped_early);
+    assert_eq!(history.best_epoch, Some(2));
+    assert_eq!(model.0[0], 3.0);
+}
+
+#[test]
+fn test_best_weights_are_only_restored_with_early_stopping() {
+    let (x, y) = overshoot_data();
+
+    let mut model = Overshoot(DMatrix::zeros(1, 1));
+    let history = train(
+        &mut model,
+        &mut SGD::new(1.0),
+        &x,
+        &y,
+        None,
+        &mut FitConfig::new(5),
+    )
+    .unwrap();
+    assert!(!history.stopped_early);
+    assert_eq!(history.best_epoch, Some(2));
+    assert_eq!(model.0[0], 5.0);
+
+    let mut model = Overshoot(DMatrix::zeros(1, 1));
+    train(
+        &mut model,
+        &mut SGD::new(1.0),
+        &x,
+        &y,
+        None,
+        &mut FitConfig::new(100)
+            .with_validation(0.5, 2)
+            .with_restore_best_weights(false),
+    )
+    .unwrap();
+    assert_eq!(model.0[0], 5.0);
+}
+
+#[test]
+fn test_regression_losses_match_finite_differences() {
+    let x = DMatrix::from_vec(20, 1, (0..20).map(|i| i as f32 / 20.0).collect());
+    let y = x.map(|v| (6.0 * v).sin());
+
+    for type_regression in [
+        TypeRegression::MSE,
+        TypeRegression::HUBER(0.3),
+        TypeRegression::LOGCOSH,
+        TypeRegression::QUANTILE(0.8),
+        TypeRegression::EPSILON_INSENSITIVE(0.1),
+        TypeRegression::TUKEY(1.5),
+    ] {
+        let mut model = PolynomialRegression::new(3, type_regression);
+        let parameters = model.parameters();
+        let gradient = model.gradient(&x, &y, None);
+
+        for i in 0..parameters.nrows() {
+            let step = 1e-2;
+            let mut shifted = parameters.clone();
+            shifted[(i, 0)] += step;
+            model.set_parameters(&shifted);
+            let loss_up = model.loss(&x, &y, None);
+            shifted[(i, 0)] -= 2.0 * step;
+            model.set_parameters(&shifted);
+            let loss_down = model.loss(&x, &y, None);
+            model.set_parameters(&parameters);
+
+            let numeric = (loss_up - loss_down) / (2.0 * step);
+            assert!(
+                (numeric - gradient[(i, 0)]).abs() < 5e-2,
+                "{:?}: {} != {}",
+                type_regression,
+                numeric,
+                gradient[(i, 0)]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_polynomial_regression_closed_form_and_ridge() {
+    let x = DMatrix::from_vec(50, 1, (0..50).map(|i| i as f32 / 5.0).collect());
+    let y = x.map(|v| 0.5 * v * v - v + 2.0);
+
+    let mut exact = PolynomialRegression::new(8, TypeRegression::MSE).with_feature_scaling();
+    exact.fit_closed_form(&x, &y).unwrap();
+    assert!(exact.loss(&x, &y, None) < 1e-3);
+
+    let mut ridge = PolynomialRegression::new(8, TypeRegression::MSE)
+        .with_feature_scaling()
+        .with_penalty(Penalty::L2(1.0));
+    ridge.fit_closed_form(&x, &y).unwrap();
+    assert!(ridge.coefficients.rows(0, 7).norm() < exact.coefficients.rows(0, 7).norm());
+
+    let mut lasso =
+        PolynomialRegression::new(8, TypeRegression::MSE).with_penalty(Penalty::L1(1.0));
+    assert!(lasso.fit_closed_form(&x, &y).is_err());
+
+    let mut absolute = PolynomialRegression::new(2, TypeRegression::MAE);
+    assert!(absolute.fit_closed_form(&x, &y).is_err());
+    let mut squared = PolynomialRegression::new(2, SquaredError);
+    assert!(squared.fit_closed_form(&x, &y).is_ok());
+}
+
+#[test]
+fn test_classification_losses_match_finite_differences() {
+    let scores = DMatrix::from_row_slice(3, 2, &[0.5, -1.0, 2.0, 0.3, -0.7, 1.2]);
+    let one_hot = DMatrix::from_row_slice(3, 2, &[1.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+    let losses: Vec<Box<dyn Loss>> = vec![Box::new(LogLoss), Box::new(CrossEntropy)];
+
+    for loss in losses {
+        let gradient = loss.gradient(&one_hot, &scores);
+        for i in 0..scores.len() {
+            let mut shifted = scores.clone();
+            shifted[i] += 1e-2;
+            let loss_up = loss.value(&one_hot, &shifted);
+            shifted[i] -= 2e-2;
+            let loss_down = loss.value(&one_hot, &shifted);
+
+            // value averages over the 3 samples, gradient is per sample.
+            let numeric = (loss_up - loss_down) / 2e-2 * 3.0;
+            assert!((numeric - gradient[i]).abs() < 1e-2, "{}", loss.name());
+        }
+    }
+}
+
+#[test]
+fn test_simple_linear_regression_summary() {
+    let x = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    let y = vec![3.1, 4.9, 7.0, 9.1, 10.9];
+
+    let mut model = SimpleLinearRegression::new();
+    model.fit(&x, &y);
+    let summary = model.summary(&x, &y, 0.95).unwrap();
+
+    let slope = &summary.coefficients[0];
+    assert!((slope.estimate - 1.98).abs() < 1e-4);
+    assert!((slope.standard_error - 0.034641).abs() < 1e-4);
+    assert!((slope.confidence_interval.1 - (1.98 + 3.182446 * 0.034641)).abs() < 1e-3);
+    assert!(slope.p_value < 1e-4);
+    assert!((summary.r_squared - 0.999083).abs() < 1e-4);
+    assert_eq!(summary.degrees_of_freedom, 3);
+
+    let new_point = DMatrix::from_vec(1, 1, vec![3.0]);
+    let (low, high) = summary.confidence_interval(&new_point)[0];
+    let (prediction_low, prediction_high) = summary.prediction_interval(&new_point)[0];
+    assert!(prediction_low < low && low < 7.0 && 7.0 < high && high < prediction_high);
+}
+
+#[test]
+fn test_sample_weights_match_duplicated_rows() {
+    let x = DMatrix::from_vec(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+    let y = DMatrix::from_vec(4, 1, vec![2.0, 4.5, 5.5, 9.0]);
+    let weights = DMatrix::from_vec(4, 1, vec![1.0, 3.0, 1.0, 2.0]);
+    let x_repeated = DMatrix::from_vec(7, 1, vec![1.0, 2.0, 2.0, 2.0, 3.0, 4.0, 4.0]);
+    let y_repeated = DMatrix::from_vec(7, 1, vec![2.0, 4.5, 4.5, 4.5, 5.5, 9.0, 9.0]);
+
+    let mut weighted = LinearRegression::new();
+    weighted.fit_weighted(&x, &y, &weights).unwrap();
+    let mut repeated = LinearRegression::new();
+    repeated.fit(&x_repeated, &y_repeated).unwrap();
+    assert!(
+        (weighted.coefficients.as_ref().unwrap()[0] - repeated.coefficients.as_ref().unwrap()[0])
+            .abs()
+            < 1e-4
+    );
+    assert!((weighted.bias.as_ref().unwrap()[0] - repeated.bias.as_ref().unwrap()[0]).abs() < 1e-4);
+    // The OLS standard errors do not apply to a weighted fit.
+    assert!(weighted.summary(&x, &y, 0.95).is_err());
+    assert!(repeated.summary(&x_repeated, &y_repeated, 0.95).is_ok());
+    assert!(LinearRegression::new()
+        .fit_weighted(&x, &y, &DMatrix::from_element(4, 1, -1.0))
+        .is_err());
+
+    let x_values: Vec<f32> = x.iter().cloned().collect();
+    let y_values: Vec<f32> = y.iter().cloned().collect();
+    let mut simple = SimpleLinearRegression::new();
+    simple
+        .fit_weighted(&x_values, &y_values, &weights.iter().cloned().collect())
+        .unwrap();
+    assert!((simple.bias.unwrap() - repeated.bias.as_ref().unwrap()[0]).abs() < 1e-4);
+    assert!(simple.summary(&x_values, &y_values, 0.95).is_err());
+    assert!(simple
+        .fit_weighted(&x_values, &y_values, &vec![1.0; 3])
+        .is_err());
+
+    let mut polynomial = PolynomialRegression::new(2, TypeRegression::MSE);
+    polynomial
+        .fit_weighted(
+            &x,
+            &y,
+            &weights,
+            &mut SGD::new(0.05),
+            &mut FitConfig::new(3000),
+        )
+        .unwrap();
+    assert!((polynomial.predict(&x) - repeated.predict(&x)).abs().max() < 1e-2);
+
+    // A zero weight removes the sample from the fit entirely.
+    let outlier = DMatrix::from_vec(5, 1, vec![1.0, 2.0, 3.0, 4.0, 10.0]);
+    let target = DMatrix::from_vec(5, 1, vec![2.0, 4.0, 6.0, 8.0, -100.0]);
+    let mut model = LinearRegression::new();
+    model
+        .fit_weighted(
+            &outlier,
+            &target,
+            &DMatrix::from_vec(5, 1, vec![1.0, 1.0, 1.0, 1.0, 0.0]),
+        )
+        .unwrap();
+    assert!((model.coefficients.unwrap()[0] - 2.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_knn_sample_weights_change_the_vote() {
+    let x = DMatrix::from_vec(4, 1, vec![0.0, 0.1, 0.2, 1.0]);
+    let y = DMatrix::from_vec(4, 1, vec![0.0, 0.0, 1.0, 1.0]);
+    let query = DMatrix::from_vec(1, 1, vec![0.05]);
+
+    let mut unweighted = KNN::new(x.clone(), y.clone(), 3);
+    assert_eq!(unweighted.predict(query.clone()), vec!["0"]);
+
+    // A heavy third neighbour outvotes the two closer ones.
+    let weights = DMatrix::from_vec(4, 1, vec![1.0, 1.0, 5.0, 1.0]);
+    let mut weighted = KNN::new(x.clone(), y.clone(), 3)
+        .with_sample_weight(weights)
+        .unwrap();
+    assert_eq!(weighted.predict(query), vec!["1"]);
+
+    for invalid in [vec![1.0, f32::NAN, 1.0, 1.0], vec![1.0, -1.0, 1.0, 1.0]] {
+        assert!(KNN::new(x.clone(), y.clone(), 3)
+            .with_sample_weight(DMatrix::from_vec(4, 1, invalid))
+            .is_err());
+    }
+}
+
+#[test]
+fn test_multi_output_regression() {
+    let x = DMatrix::from_vec(20, 1, (0..20).map(|i| i as f32 / 10.0).collect());
+    let mut y = DMatrix::zeros(20, 2);
+    for i in 0..20 {
+        y[(i, 0)] = 2.0 * x[(i, 0)] + 1.0;
+        y[(i, 1)] = x[(i, 0)] * x[(i, 0)] - 3.0;
+    }
+
+    let mut linear = LinearRegression::new();
+    linear.fit(&x, &y).unwrap();
+    assert_eq!(linear.coefficients.as_ref().unwrap().shape(), (1, 2));
+    assert_eq!(linear.predict(&x).shape(), (20, 2));
+    assert!((linear.coefficients.as_ref().unwrap()[(0, 0)] - 2.0).abs() < 1e-4);
+    assert!((linear.bias.as_ref().unwrap()[0] - 1.0).abs() < 1e-4);
+    assert!(linear.summary(&x, &y, 0.95).is_err());
+
+    let mut polynomial = PolynomialRegression::new(3, TypeRegression::MSE);
+    polynomial.fit_closed_form(&x, &y).unwrap();
+    let y_hat = polynomial.predict(&x);
+    assert!(mse_per_target(&y, &y_hat).iter().all(|e| *e < 1e-6));
+    assert!(r2_per_target(&y, &y_hat).iter().all(|r2| *r2 > 0.9999));
+
+    let mut trained = PolynomialRegression::new(3, TypeRegression::MSE);
+    trained
+        .fit_with_config(&x, &y, &mut Adam::new(0.05), &mut FitConfig::new(2000))
+        .unwrap();
+    let errors = mae_per_target(&y, &trained.predict(&x));
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|e| *e < 5e-2), "{:?}", errors);
+
+    let mut weighted = PolynomialRegression::new(3, TypeRegression::MSE);
+    weighted
+        .fit_weighted(
+            &x,
+            &y,
+            &DMatrix::from_element(20, 1, 1.0),
+            &mut Adam::new(0.05),
+            &mut FitConfig::new(2000),
+        )
+        .unwrap();
+    let errors = mae_per_target(&y, &weighted.predict(&x));
+    assert!(errors.iter().all(|e| *e < 5e-2), "{:?}", errors);
+
+    // With a center on every sample the RBF network interpolates each target.
+    let mut rbf = RBFRegression::new(50.0, 20, 2, None);
+    rbf.fit(&x, &y).unwrap();
+    assert_eq!(rbf.weight.shape(), (20, 2));
+    let errors = mse_per_target(&y, &rbf.predict(&x));
+    assert!(errors.iter().all(|e| *e < 1e-3), "{:?}", errors);
+
+    // The overall metrics average over every target instead of reading the first one.
+    let mut shifted = y.clone();
+    shifted.column_mut(1).add_scalar_mut(1.0);
+    assert!((mse(y.clone(), shifted.clone()) - 0.5).abs() < 1e-6);
+    assert!((mae(y, shifted) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_pca_and_truncated_svd() {
+    // Rank 2 data embedded in 5 dimensions, plus a constant offset.
+    let mut x = DMatrix::zeros(40, 5);
+    for i in 0..40 {
+        let (a, b) = ((i as f32 * 0.7).sin() * 3.0, (i as f32 * 0.3).cos());
+        for j in 0..5 {
+            x[(i, j)] = a * (j as f32 + 1.0) + b * (j as f32 - 2.0) + 10.0;
+        }
+    }
+
+    let mut pca = PCA::new(2);
+    let projected = pca.fit_transform(&x).unwrap();
+    assert_eq!(projected.shape(), (40, 2));
+    assert!((pca.explained_variance_ratio.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    assert!((pca.inverse_transform(&projected) - &x).abs().max() < 1e-3);
+
+    let mut threshold = PCA::with_variance_threshold(0.999);
+    threshold.fit(&x).unwrap();
+    assert_eq!(threshold.components.nrows(), 2);
+
+    let mut randomized = PCA::new(2).with_solver(TypeSVD::RANDOMIZED).with_seed(7);
+    randomized.fit(&x).unwrap();
+    assert!(
+        (randomized.components.clone() - &pca.components)
+            .abs()
+            .max()
+            < 1e-3
+    );
+
+    let mut whitened = PCA::new(2).with_whitening();
+    let white = whitened.fit_transform(&x).unwrap();
+    for k in 0..2 {
+        let variance = white.column(k).norm_squared() / 39.0;
+        assert!((variance - 1.0).abs() < 1e-3);
+    }
+    assert!((whitened.inverse_transform(&white) - &x).abs().max() < 1e-3);
+
+    let mut svd = TruncatedSVD::new(3).with_seed(7);
+    let reduced = svd.fit_transform(&x).unwrap();
+    assert!((svd.inverse_transform(&reduced) - &x).abs().max() < 1e-3);
+    let mut exact = TruncatedSVD::new(3).with_solver(TypeSVD::FULL);
+    exact.fit(&x).unwrap();
+    for k in 0..3 {
+        assert!((svd.singular_values[k] - exact.singular_values[k]).abs() < 1e-2);
+    }
+}
+
+/// A tight blob of class 0 inside a ring of class 1: the means coincide, so only the
+/// per-class covariances of QDA can separate them.
+fn blob_in_ring() -> (DMatrix<f32>, DMatrix<f32>) {
+    let n = 60;
+    let mut x = DMatrix::zeros(2 * n, 2);
+    let mut y = DMatrix::zeros(2 * n, 1);
+    for i in 0..n {
+        let angle = i as f32 * 0.37;
+        let radius = 0.3 + 0.2 * (i as f32 * 1.3).sin().abs();
+        x[(i, 0)] = radius * angle.cos();
+        x[(i, 1)] = radius * angle.sin();
+        x[(n + i, 0)] = 3.0 * angle.cos();
+        x[(n + i, 1)] = 3.0 * angle.sin();
+        y[(n + i, 0)] = 1.0;
+    }
+    (x, y)
+}
+
+#[test]
+fn test_qda_separates_classes_with_equal_means() {
+    let (x, y) = blob_in_ring();
+    let y_target: Vec<f32> = y.iter().cloned().collect();
+
+    let mut qda = QuadraticDiscriminantAnalysis::new().with_regularization(0.01);
+    qda.fit(&x, &y).unwrap();
+    assert_eq!(accuracy(qda.predict(&x), y_target), 1.0);
+    let probabilities = qda.predict_proba(&x);
+    assert!(probabilities
+        .row_iter()
+        .all(|row| (row.sum() - 1.0).abs() < 1e-5));
+}
+
+#[test]
+fn test_lda_classifies_and_projects() {
+    // Shifting the ring makes the classes linearly separable.
+    let (mut x, y) = blob_in_ring();
+    let n = x.nrows() / 2;
+    for i in n..2 * n {
+        x[(i, 0)] += 10.0;
+    }
+    let y_target: Vec<f32> = y.iter().cloned().collect();
+
+    let mut lda = LinearDiscriminantAnalysis::new().with_shrinkage(0.1);
+    lda.fit(&x, &y).unwrap();
+    assert_eq!(accuracy(lda.predict(&x), y_target), 1.0);
+    assert!(
+        (lda.predict_log_proba(&x).map(|v| v.exp()) - lda.predict_proba(&x))
+            .abs()
+            .max()
+            < 1e-5
+    );
+
+    let projected = lda.transform(&x);
+    assert_eq!(projected.shape(), (2 * n, 1));
+    assert!(projected[(0, 0)].signum() != projected[(n, 0)].signum());
+    assert!(LinearDiscriminantAnalysis::new()
+        .with_n_components(2)
+        .fit(&x, &y)
+        .is_err());
+}
+
+/// Two classes of continuous features, alternating from one sample to the next.
+fn two_gaussian_classes() -> (DMatrix<f32>, DMatrix<f32>) {
+    let n = 80;
+    let mut x = DMatrix::zeros(n, 2);
+    let mut y = DMatrix::zeros(n, 1);
+    for i in 0..n {
+        let class = (i % 2) as f32;
+        x[(i, 0)] = class * 4.0 + (i as f32 * 0.91).sin();
+        x[(i, 1)] = -class * 3.0 + (i as f32 * 1.7).cos();
+        y[(i, 0)] = class;
+    }
+    (x, y)
+}
+
+/// Word counts where class 0 uses the first two words and class 1 the last two.
+fn word_counts() -> (DMatrix<f32>, DMatrix<f32>) {
+    let counts = DMatrix::from_row_slice(
+        4,
+        4,
+        &[
+            3.0, 2.0, 0.0, 0.0, //
+            4.0, 1.0, 1.0, 0.0, //
+            0.0, 0.0, 2.0, 5.0, //
+            0.0, 1.0, 3.0, 2.0, //
+        ],
+    );
+    (counts, DMatrix::from_vec(4, 1, vec![0.0, 0.0, 1.0, 1.0]))
+}
+
+#[test]
+fn test_gaussian_naive_bayes() {
+    let (x, y) = two_gaussian_classes();
+    let y_target: Vec<f32> = y.iter().cloned().collect();
+
+    let mut gaussian = GaussianNaiveBayes::new();
+    gaussian.fit(&x, &y).unwrap();
+    assert_eq!(accuracy(gaussian.predict(&x), y_target), 1.0);
+    let probabilities = gaussian.predict_proba(&x);
+    assert!(probabilities
+        .row_iter()
+        .all(|row| (row.sum() - 1.0).abs() < 1e-5));
+}
+
+#[test]
+fn test_gaussian_naive_bayes_partial_fit() {
+    let (x, y) = two_gaussian_classes();
+    let mut gaussian = GaussianNaiveBayes::new();
+    gaussian.fit(&x, &y).unwrap();
+
+    // Two batches give the same statistics as one pass over the data.
+    let mut incremental = GaussianNaiveBayes::new();
+    incremental
+        .partial_fit(
+            &x.rows(0, 30).into_owned(),
+            &y.rows(0, 30).into_owned(),
+            Some(&[0.0, 1.0]),
+        )
+        .unwrap();
+    incremental
+        .partial_fit(
+            &x.rows(30, x.nrows() - 30).into_owned(),
+            &y.rows(30, x.nrows() - 30).into_owned(),
+            None,
+        )
+        .unwrap();
+    assert!((incremental.means.clone() - &gaussian.means).abs().max() < 1e-4);
+    assert!(
+        (incremental.variances.clone() - &gaussian.variances)
+            .abs()
+            .max()
+            < 1e-4
+    );
+    assert!(incremental
+        .partial_fit(
+            &x.rows(0, 1).into_owned(),
+            &DMatrix::from_element(1, 1, 2.0),
+            None
+        )
+        .is_err());
+}
+
+#[test]
+fn test_multinomial_naive_bayes() {
+    let (counts, labels) = word_counts();
+    let mut multinomial = MultinomialNaiveBayes::new();
+    multinomial.fit(&counts, &labels).unwrap();
+    assert_eq!(multinomial.predict(&counts), vec![0.0, 0.0, 1.0, 1.0]);
+    // (3 + 4 + 1) / (11 + 4) with Laplace smoothing.
+    assert!((multinomial.feature_log_prob[(0, 0)] - (8.0f32 / 15.0).ln()).abs() < 1e-5);
+}
+
+#[test]
+fn test_bernoulli_naive_bayes() {
+    let (counts, labels) = word_counts();
+    let mut bernoulli = BernoulliNaiveBayes::new();
+    bernoulli.fit(&counts, &labels).unwrap();
+    assert_eq!(bernoulli.predict(&counts), vec![0.0, 0.0, 1.0, 1.0]);
+    let log_probabilities = bernoulli.predict_log_proba(&counts);
+    assert!(
+        (log_probabilities.map(|v| v.exp()) - bernoulli.predict_proba(&counts))
+            .abs()
+            .max()
+            < 1e-5
+    );
+}
+
+/// Labels that only depend on feature 1 (a band in the middle is class 0); feature 0 is
+/// noise.
+fn band_labels() -> (DMatrix<f32>, DMatrix<f32>) {
+    let n = 60;
+    let mut x = DMatrix::zeros(n, 2);
+    let mut y = DMatrix::zeros(n, 1);
+    for i in 0..n {
+        x[(i, 0)] = (i as f32 * 2.3).sin();
+        x[(i, 1)] = i as f32 / n as f32;
+        y[(i, 0)] = if x[(i, 1)] < 0.3 || x[(i, 1)] > 0.7 {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    (x, y)
+}
+
+/// A step on feature 1 plus a little noise from feature 0.
+fn noisy_step(x: &DMatrix<f32>) -> DMatrix<f32> {
+    DMatrix::from_fn(x.nrows(), 1, |i, _| if x[(i, 1)] < 0.5 { 1.0 } else { 5.0 })
+        + DMatrix::from_fn(x.nrows(), 1, |i, _| 0.1 * x[(i, 0)])
+}
+
+#[test]
+fn test_decision_tree_classifier_criteria() {
+    let (x, y) = band_labels();
+    let y_target: Vec<f32> = y.iter().cloned().collect();
+
+    for criterion in [Criterion::GINI, Criterion::ENTROPY] {
+        let mut tree = DecisionTreeClassifier::new(criterion).with_seed(0);
+        tree.fit(&x, &y).unwrap();
+        assert_eq!(accuracy(tree.predict(&x), y_target.clone()), 1.0);
+        assert_eq!(tree.tree.as_ref().unwrap().n_leaves(), 3);
+        assert!((tree.feature_importances()[1] - 1.0).abs() < 1e-6);
+    }
+    assert!(DecisionTreeClassifier::new(Criterion::MSE)
+        .fit(&x, &y)
+        .is_err());
+}
+
+#[test]
+fn test_decision_tree_export_text() {
+    let (x, y) = band_labels();
+    let mut tree = DecisionTreeClassifier::new(Criterion::GINI).with_seed(0);
+    tree.fit(&x, &y).unwrap();
+    assert!(tree
+        .export_text(Some(&["noise", "signal"]))
+        .contains("|--- signal <= 0.29"));
+}
+
+#[test]
+fn test_decision_tree_max_depth() {
+    let (x, y) = band_labels();
+    let mut stump = DecisionTreeClassifier::new(Criterion::GINI).with_max_depth(1);
+    stump.fit(&x, &y).unwrap();
+    assert_eq!(stump.tree.as_ref().unwrap().depth(), 1);
+}
+
+#[test]
+fn test_decision_tree_cost_complexity_pruning() {
+    // Deep trees overfit the noise, pruning removes those splits.
+    let (x, _) = band_labels();
+    let values = noisy_step(&x);
+    let mut deep = DecisionTreeRegressor::new(Criterion::MSE).with_seed(0);
+    deep.fit(&x, &values).unwrap();
+    assert!((deep.predict(&x) - &values).abs().max() < 1e-5);
+    let mut pruned = DecisionTreeRegressor::new(Criterion::MSE)
+        .with_ccp_alpha(0.01)
+        .with_seed(0);
+    pruned.fit(&x, &values).unwrap();
+    assert_eq!(pruned.tree.as_ref().unwrap().n_leaves(), 2);
+    assert!((pruned.predict(&x) - &values).abs().max() < 0.2);
+}
+
+#[test]
+fn test_decision_tree_mae_criterion() {
+    let (x, _) = band_labels();
+    let values = noisy_step(&x);
+    let mut leafy = DecisionTreeRegressor::new(Criterion::MAE).with_min_samples_leaf(10);
+    leafy.fit(&x, &values).unwrap();
+    let tree = leafy.tree.as_ref().unwrap();
+    assert!(tree.nodes.iter().all(|node| node.n_samples >= 10));
+}
+
+#[test]
+fn test_decision_tree_random_splitter() {
+    let (x, _) = band_labels();
+    let values = noisy_step(&x);
+    let mut extra = DecisionTreeRegressor::new(Criterion::MSE)
+        .with_splitter(Splitter::RANDOM)
+        .with_seed(3);
+    extra.fit(&x, &values).unwrap();
+    assert!((extra.predict(&x) - &values).abs().max() < 1e-5);
+}
+
+/// Four features of which only the first two decide the label.
+fn two_relevant_features() -> (DMatrix<f32>, DMatrix<f32>) {
+    let n = 120;
+    let x = DMatrix::from_fn(n, 4, |i, j| ((i * (j + 3)) as f32 * 0.618).fract());
+    let y = DMatrix::from_fn(n, 1, |i, _| {
+        if x[(i, 0)] + x[(i, 1)] > 1.0 {
+            1.0
+        } else {
+            0.0
+        }
+    });
+    (x, y)
+}
+
+fn two_relevant_values(x: &DMatrix<f32>) -> DMatrix<f32> {
+    DMatrix::from_fn(x.nrows(), 1, |i, _| {
+        3.0 * x[(i, 0)] - 2.0 * x[(i, 1)] * x[(i, 1)]
+    })
+}
+
+#[test]
+fn test_random_forest_oob_score_and_importances() {
+    let (x, y) = two_relevant_features();
+    let y_target: Vec<f32> = y.iter().cloned().collect();
+
+    let mut forest = RandomForestClassifier::new(30, Criterion::GINI)
+        .with_oob_score()
+        .with_seed(11);
+    forest.fit(&x, &y).unwrap();
+    assert!(accuracy(forest.predict(&x), y_target) > 0.95);
+    assert!(forest.oob_score.unwrap() > 0.75);
+    let importances = forest.feature_importances();
+    assert!((importances.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    assert!(importances[0] + importances[1] > 0.7, "{:?}", importances);
+}
+
+#[test]
+fn test_random_forest_parallel_matches_sequential() {
+    let (x, y) = two_relevant_features();
+    let mut sequential = RandomForestClassifier::new(30, Criterion::GINI)
+        .with_oob_score()
+        .with_seed(11);
+    sequential.fit(&x, &y).unwrap();
+    let mut parallel = RandomForestClassifier::new(30, Criterion::GINI)
+        .with_oob_score()
+        .with_n_jobs(4)
+        .with_seed(11);
+    parallel.fit(&x, &y).unwrap();
+    assert_eq!(parallel.predict_proba(&x), sequential.predict_proba(&x));
+    assert_eq!(parallel.oob_score, sequential.oob_score);
+}
+
+#[test]
+fn test_extra_trees_classifier() {
+    let (x, y) = two_relevant_features();
+    let y_target: Vec<f32> = y.iter().cloned().collect();
+
+    let mut extra = extra_trees_classifier(30, Criterion::ENTROPY).with_seed(5);
+    extra.fit(&x, &y).unwrap();
+    assert!(accuracy(extra.predict(&x), y_target) > 0.95);
+    // Without bootstrap there are no out-of-bag samples.
+    assert!(extra_trees_classifier(3, Criterion::GINI)
+        .with_oob_score()
+        .fit(&x, &y)
+        .is_err());
+}
+
+#[test]
+fn test_random_forest_regressor_oob_score() {
+    let (x, _) = two_relevant_features();
+    let values = two_relevant_values(&x);
+    let mut regressor = RandomForestRegressor::new(30, Criterion::MSE)
+        .with_oob_score()
+        .with_n_jobs(2)
+        .with_seed(1);
+    regressor.fit(&x, &values).unwrap();
+    assert!(regressor.oob_score.unwrap() > 0.8);
+}
+
+#[test]
+fn test_extra_trees_regressor() {
+    let (x, _) = two_relevant_features();
+    let values = two_relevant_values(&x);
+    let mut extra_regressor = extra_trees_regressor(30, Criterion::MSE).with_seed(2);
+    extra_regressor.fit(&x, &values).unwrap();
+    assert!(r2_per_target(&values, &extra_regressor.predict(&x))[0] > 0.95);
+}
+
+fn boosting_data() -> (DMatrix<f32>, DMatrix<f32>) {
+    let n = 200;
+    let x = DMatrix::from_fn(n, 3, |i, j| ((i * (j + 2)) as f32 * 0.618).fract());
+    let y = DMatrix::from_fn(n, 1, |i, _| (3.0 * x[(i, 0)]).sin() + x[(i, 1)] * x[(i, 1)]);
+    (x, y)
+}
+
+fn with_noise(y: &DMatrix<f32>) -> DMatrix<f32> {
+    DMatrix::from_fn(y.nrows(), 1, |i, _| {
+        y[(i, 0)] + ((i * 7) as f32 * 0.377).fract() - 0.5
+    })
+}
+
+/// Three classes along the diagonal of the first two features.
+fn boosting_labels(x: &DMatrix<f32>) -> DMatrix<f32> {
+    DMatrix::from_fn(x.nrows(), 1, |i, _| {
+        ((x[(i, 0)] + x[(i, 1)]) * 1.5).floor().min(2.0)
+    })
+}
+
+#[test]
+fn test_gradient_boosting_regression_losses() {
+    let (x, y) = boosting_data();
+    for loss in [
+        TypeRegression::MSE,
+        TypeRegression::MAE,
+        TypeRegression::HUBER(0.5),
+        TypeRegression::QUANTILE(0.5),
+    ] {
+        let mut model = GradientBoostingRegressor::new(100, loss).with_seed(3);
+        model.fit(&x, &y).unwrap();
+        assert!(
+            r2_per_target(&y, &model.predict(&x))[0] > 0.95,
+            "{:?}",
+            loss
+        );
+    }
+}
+
+#[test]
+fn test_gradient_boosting_quantile() {
+    // The 0.9 quantile lies above about 90% of the targets.
+    let (x, y) = boosting_data();
+    let noisy = with_noise(&y);
+    let mut upper = GradientBoostingRegressor::new(100, TypeRegression::QUANTILE(0.9))
+        .with_subsample(0.8)
+        .with_seed(3);
+    upper.fit(&x, &noisy).unwrap();
+    let y_hat = upper.predict(&x);
+    let below = (0..x.nrows())
+        .filter(|i| noisy[(*i, 0)] <= y_hat[(*i, 0)])
+        .count();
+    assert!(below as f32 / x.nrows() as f32 > 0.8, "{}", below);
+}
+
+#[test]
+fn test_gradient_boosting_histogram_early_stopping() {
+    let (x, y) = boosting_data();
+    let mut histogram = GradientBoostingRegressor::new(500, TypeRegression::MSE)
+        .with_histogram(16)
+        .with_early_stopping(0.2, 5)
+        .with_seed(4);
+    histogram.fit(&x, &with_noise(&y)).unwrap();
+    assert!(histogram.history.stopped_early);
+    assert_eq!(
+        histogram.n_estimators(),
+        histogram.history.best_epoch.unwrap() + 1
+    );
+    assert!(r2_per_target(&y, &histogram.predict(&x))[0] > 0.8);
+}
+
+#[test]
+fn test_gradient_boosting_multiclass() {
+    let (x, _) = boosting_data();
+    let labels = boosting_labels(&x);
+    let label_target: Vec<f32> = labels.iter().cloned().collect();
+    let mut multiclass = GradientBoostingClassifier::new(50).with_seed(5);
+    multiclass.fit(&x, &labels).unwrap();
+    assert!(accuracy(multiclass.predict(&x), label_target) > 0.95);
+    let probabilities = multiclass.predict_proba(&x);
+    assert_eq!(probabilities.ncols(), 3);
+    assert!((probabilities.row(0).sum() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_gradient_boosting_binary_classifier() {
+    let (x, _) = boosting_data();
+    let binary = boosting_labels(&x).map(|l| if l > 0.0 { 1.0 } else { 0.0 });
+    let binary_target: Vec<f32> = binary.iter().cloned().collect();
+    let mut classifier = GradientBoostingClassifier::new(50)
+        .with_histogram(32)
+        .with_subsample(0.7)
+        .with_seed(6);
+    classifier.fit(&x, &binary).unwrap();
+    assert_eq!(classifier.decision_function(&x).ncols(), 1);
+    assert!(accuracy(classifier.predict(&x), binary_target) > 0.95);
+    assert!(classifier.history.train_loss[49] < classifier.history.train_loss[0]);
+}
+
+fn svm_inputs() -> DMatrix<f32> {
+    DMatrix::from_fn(120, 2, |i, j| {
+        ((i * (j + 2)) as f32 * 0.618).fract() * 2.0 - 1.0
+    })
+}
+
+/// Three linearly separable classes.
+fn three_regions(x: &DMatrix<f32>) -> DMatrix<f32> {
+    DMatrix::from_fn(x.nrows(), 1, |i, _| {
+        if x[(i, 0)] < -0.3 {
+            0.0
+        } else if x[(i, 1)] < 0.0 {
+            1.0
+        } else {
+            2.0
+        }
+    })
+}
+
+#[test]
+fn test_svc_kernels_on_a_disc() {
+    // A disc is not linearly separable but is with an RBF or a quadratic kernel.
+    let x = svm_inputs();
+    let disc = DMatrix::from_fn(x.nrows(), 1, |i, _| {
+        if x[(i, 0)].powi(2) + x[(i, 1)].powi(2) < 0.5 {
+            1.0
+        } else {
+            0.0
+        }
+    });
+    let disc_target: Vec<f32> = disc.iter().cloned().collect();
+    let mut rbf = SVC::new(TypeKernel::RBF(2.0)).with_c(10.0);
+    rbf.fit(&x, &disc).unwrap();
+    assert!(accuracy(rbf.predict(&x), disc_target.clone()) > 0.95);
+    let support = &rbf.support[0];
+    assert!(support.indices.len() < x.nrows());
+    assert!(support.dual_coef.iter().all(|a| a.abs() <= 10.0 + 1e-4));
+    assert!(support.dual_coef.iter().sum::<f32>().abs() < 1e-3);
+    let mut linear = SVC::new(TypeKernel::LINEAR);
+    linear.fit(&x, &disc).unwrap();
+    assert!(accuracy(linear.predict(&x), disc_target.clone()) < 0.8);
+    let mut poly = SVC::new(TypeKernel::POLY(2, 1.0, 1.0)).with_c(10.0);
+    poly.fit(&x, &disc).unwrap();
+    assert!(accuracy(poly.predict(&x), disc_target) > 0.95);
+}
+
+#[test]
+fn test_svc_one_vs_rest() {
+    let x = svm_inputs();
+    let labels = three_regions(&x);
+    let label_target: Vec<f32> = labels.iter().cloned().collect();
+    let mut multiclass = SVC::new(TypeKernel::LINEAR).with_c(100.0);
+    multiclass.fit(&x, &labels).unwrap();
+    assert_eq!(multiclass.n_support().len(), 3);
+    assert!(accuracy(multiclass.predict(&x), label_target) > 0.95);
+}
+
+#[test]
+fn test_linear_svc() {
+    let x = svm_inputs();
+    let labels = three_regions(&x);
+    let label_target: Vec<f32> = labels.iter().cloned().collect();
+    let mut linear_svc = LinearSVC::new().with_c(100.0).with_seed(1);
+    linear_svc.fit(&x, &labels).unwrap();
+    assert_eq!(linear_svc.coefficients.shape(), (2, 3));
+    assert!(accuracy(linear_svc.predict(&x), label_target) > 0.95);
+}
+
+#[test]
+fn test_svr_epsilon_tube() {
+    // Only the samples outside of the tube are kept as support vectors.
+    let x = svm_inputs();
+    let y = DMatrix::from_fn(x.nrows(), 1, |i, _| {
+        (2.0 * x[(i, 0)]).sin() + 0.5 * x[(i, 1)]
+    });
+    let mut svr = SVR::new(TypeKernel::RBF(1.0))
+        .with_c(10.0)
+        .with_epsilon(0.05);
+    svr.fit(&x, &y).unwrap();
+    let y_hat = svr.predict(&x);
+    assert!(r2_per_target(&y, &y_hat)[0] > 0.98);
+    assert!((0..x.nrows()).all(|i| (y[(i, 0)] - y_hat[(i, 0)]).abs() < 0.05 + 1e-2));
+    assert!(svr.support.as_ref().unwrap().indices.len() < x.nrows());
+}
+
+#[test]
+fn test_linear_svr() {
+    let x = svm_inputs();
+    let line = DMatrix::from_fn(x.nrows(), 1, |i, _| 2.0 * x[(i, 0)] - x[(i, 1)] + 0.5);
+    let mut linear_svr = LinearSVR::new().with_c(100.0).with_seed(2);
+    linear_svr.fit(&x, &line).unwrap();
+    assert!((linear_svr.coefficients[(0, 0)] - 2.0).abs() < 0.05);
+    assert!((linear_svr.coefficients[(1, 0)] + 1.0).abs() < 0.05);
+    assert!((linear_svr.intercept - 0.5).abs() < 0.05);
+    assert!(SVR::new(TypeKernel::LINEAR)
+        .with_c(0.0)
+        .fit(&x, &line)
+        .is_err());
+}
+
+fn kernel_inputs() -> (DMatrix<f32>, DMatrix<f32>) {
+    (
+        DMatrix::from_row_slice(3, 2, &[0.0, 1.0, 1.0, 2.0, -1.0, 0.5]),
+        DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 0.0, -2.0]),
+    )
+}
+
+fn wavy_line() -> (DMatrix<f32>, DMatrix<f32>) {
+    let n = 80;
+    let x = DMatrix::from_fn(n, 1, |i, _| i as f32 / n as f32);
+    let y = x.map(|v| (6.0 * v).sin() + 0.3 * v);
+    (x, y)
+}
+
+#[test]
+fn test_rbf_and_laplacian_kernels() {
+    let (a, b) = kernel_inputs();
+    let rbf = TypeKernel::RBF(0.5).gram(&a, &b);
+    let laplacian = TypeKernel::LAPLACIAN(0.5).gram(&a, &b);
+    assert_eq!(rbf.shape(), (3, 2));
+    for i in 0..3 {
+        for j in 0..2 {
+            let difference = a.row(i) - b.row(j);
+            assert!((rbf[(i, j)] - (-0.5 * difference.norm_squared()).exp()).abs() < 1e-6);
+            let l1: f32 = difference.iter().map(|v| v.abs()).sum();
+            assert!((laplacian[(i, j)] - (-0.5 * l1).exp()).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_custom_and_composite_kernels() {
+    let (a, b) = kernel_inputs();
+    let dot = CustomKernel::new(|u: &[f32], v: &[f32]| u.iter().zip(v).map(|(p, q)| p * q).sum());
+    assert_eq!(dot.gram(&a, &b), TypeKernel::LINEAR.gram(&a, &b));
+    let combined = KernelSum::new(
+        ScaledKernel::new(2.0, TypeKernel::RBF(0.5)),
+        KernelProduct::new(TypeKernel::LINEAR, TypeKernel::LINEAR),
+    );
+    let linear = TypeKernel::LINEAR.gram(&a, &b);
+    let expected = TypeKernel::RBF(0.5).gram(&a, &b) * 2.0 + linear.component_mul(&linear);
+    assert!((combined.gram(&a, &b) - expected).abs().max() < 1e-5);
+}
+
+#[test]
+fn test_kernel_ridge_and_rbf_regression() {
+    // Kernel ridge with the same Gaussian kernel as RBFRegression, which only uses a
+    // few centers and no regularisation. RBFRegression appends a constant column to x.
+    let (x, y) = wavy_line();
+    let mut ridge = KernelRidge::new(1e-3, TypeKernel::RBF(20.0));
+    ridge.fit(&x, &y).unwrap();
+    assert_eq!(ridge.dual_coef.shape(), (x.nrows(), 1));
+    assert!(mse_per_target(&y, &ridge.predict(&x))[0] < 1e-3);
+    let mut rbf_regression = RBFRegression::new(20.0, 20, 2, None);
+    rbf_regression.fit(&x, &y).unwrap();
+    assert!(mse_per_target(&y, &rbf_regression.predict(&x))[0] < 1e-2);
+}
+
+#[test]
+fn test_kernel_ridge_regularization_and_weights() {
+    // Heavier regularisation shrinks the fit; heavier weights pull it back.
+    let (x, y) = wavy_line();
+    let mut ridge = KernelRidge::new(1e-3, TypeKernel::RBF(20.0));
+    ridge.fit(&x, &y).unwrap();
+    let mut smooth = KernelRidge::new(10.0, TypeKernel::RBF(20.0));
+    smooth.fit(&x, &y).unwrap();
+    assert!(mse_per_target(&y, &smooth.predict(&x))[0] > mse_per_target(&y, &ridge.predict(&x))[0]);
+    let weights = DMatrix::from_element(x.nrows(), 1, 1e4);
+    smooth.fit_weighted(&x, &y, &weights).unwrap();
+    assert!(mse_per_target(&y, &smooth.predict(&x))[0] < 1e-2);
+    assert!(KernelRidge::new(0.0, TypeKernel::LINEAR)
+        .fit(&x, &y)
+        .is_err());
+}
+
+fn sine_samples() -> (DMatrix<f32>, DMatrix<f32>) {
+    let x = DMatrix::from_fn(30, 1, |i, _| i as f32 / 5.0);
+    let y = x.map(|v| v.sin());
+    (x, y)
+}
+
+#[test]
+fn test_gp_kernel_hyperparameters() {
+    let kernel = GPKernel::CONSTANT(2.0) * GPKernel::RATIONAL_QUADRATIC(0.5, 1.5)
+        + GPKernel::MATERN(1.0, 2.5)
+        + GPKernel::WHITE(0.1);
+    let theta = kernel.theta();
+    assert_eq!(theta.len(), 5);
+    let rebuilt = kernel.with_theta(&theta).theta();
+    assert!(rebuilt
+        .iter()
+        .zip(theta.iter())
+        .all(|(a, b)| (a - b).abs() < 1e-6));
+
+    // White noise only shows up on the prior variance, not between distinct samples.
+    let (x, _) = sine_samples();
+    assert!((kernel.diagonal(&x)[0] - 3.1).abs() < 1e-6);
+    assert!((kernel.gram(&x, &x)[(0, 0)] - 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_gp_interpolates_with_zero_std() {
+    // Without noise and optimisation the posterior interpolates the training samples
+    // and falls back to the prior far from them.
+    let (x, y) = sine_samples();
+    let mut exact = GaussianProcessRegressor::new(GPKernel::RBF(1.0)).with_optimizer(0, 0.0);
+    exact.fit(&x, &y).unwrap();
+    let (mean, std) = exact.predict_with_std(&x);
+    assert!((&mean - &y).abs().max() < 1e-2);
+    assert!(std.max() < 1e-2);
+    let far = DMatrix::from_element(1, 1, 20.0);
+    assert!((exact.predict_with_std(&far).1[(0, 0)] - 1.0).abs() < 1e-3);
+
+    assert!(GaussianProcessRegressor::new(GPKernel::MATERN(1.0, 1.0))
+        .fit(&x, &y)
+        .is_err());
+}
+
+#[test]
+fn test_gp_hyperparameter_optimisation() {
+    // Optimising the hyperparameters on noisy observations raises the likelihood and
+    // roughly recovers the noise level.
+    let (x, y) = sine_samples();
+    let noisy = DMatrix::from_fn(30, 1, |i, _| {
+        y[(i, 0)] + 0.3 * (((i * 7) as f32 * 0.618).fract() - 0.5)
+    });
+    let mut gp = GaussianProcessRegressor::new(
+        GPKernel::CONSTANT(1.0) * GPKernel::RBF(0.3) + GPKernel::WHITE(1.0),
+    )
+    .with_normalize_y()
+    .with_optimizer(200, 0.05)
+    .with_restarts(2)
+    .with_seed(3);
+    let initial = gp.kernel.theta();
+    gp.fit(&x, &noisy).unwrap();
+    assert!(gp.log_marginal_likelihood_value > gp.log_marginal_likelihood(Some(&initial)));
+    assert!((gp.log_marginal_likelihood(None) - gp.log_marginal_likelihood_value).abs() < 1e-3);
+
+    let (mean, std) = gp.predict_with_std(&x);
+    assert!(mse_per_target(&y, &mean)[0] < 0.01);
+    let covered = (0..30)
+        .filter(|i| (noisy[(*i, 0)] - mean[(*i, 0)]).abs() <= 2.0 * std[(*i, 0)])
+        .count();
+    assert!(covered >= 27, "{}", covered);
+}
+
+fn sine_curve() -> (DMatrix<f32>, DMatrix<f32>) {
+    let x = DMatrix::from_fn(40, 1, |i, _| i as f32 / 10.0 - 2.0);
+    let y = x.map(|v| v.sin());
+    (x, y)
+}
+
+#[test]
+fn test_mlp_gradient_matches_finite_differences() {
+    let (x, y) = sine_curve();
+    let mut model = MLPRegressor::new(vec![5, 4])
+        .with_activation(Activation::TANH)
+        .with_initialization(Initialization::XAVIER)
+        .with_alpha(0.01)
+        .with_seed(1);
+    model.fit(&x, &y, 0, 0.01).unwrap();
+    let parameters = model.parameters();
+    let gradient = model.gradient(&x, &y, None);
+    for i in [0, 7, 20, parameters.nrows() - 1] {
+        let mut shifted = parameters.clone();
+        shifted[(i, 0)] += 1e-2;
+        model.set_parameters(&shifted);
+        let upper = model.loss(&x, &y, None);
+        shifted[(i, 0)] -= 2e-2;
+        model.set_parameters(&shifted);
+        let lower = model.loss(&x, &y, None);
+        assert!(((upper - lower) / 2e-2 - gradient[(i, 0)]).abs() < 1e-2);
+    }
+}
+
+#[test]
+fn test_mlp_regressor() {
+    let (x, y) = sine_curve();
+    let mut model = MLPRegressor::new(vec![5, 4])
+        .with_activation(Activation::TANH)
+        .with_initialization(Initialization::XAVIER)
+        .with_alpha(0.01)
+        .with_seed(1);
+    let history = model.fit(&x, &y, 500, 0.01).unwrap();
+    assert!(history.train_loss.last().unwrap() < &history.train_loss[0]);
+    assert!(mse_per_target(&y, &model.predict(&x))[0] < 0.01);
+}
+
+#[test]
+fn test_mlp_dropout() {
+    let (x, y) = sine_curve();
+    let mut dropout = MLPRegressor::new(vec![32]).with_dropout(0.2).with_seed(2);
+    dropout.fit(&x, &y, 500, 0.01).unwrap();
+    assert!(mse_per_target(&y, &dropout.predict(&x))[0] < 0.05);
+    assert!(MLPRegressor::new(vec![8])
+        .with_dropout(1.0)
+        .fit(&x, &y, 1, 0.01)
+        .is_err());
+}
+
+#[test]
+fn test_mlp_initializations() {
+    // The weights between the two hidden layers have the scale of each scheme.
+    let (x, y) = sine_curve();
+    for (initialization, expected_std) in [
+        (
+            Initialization::XAVIER,
+            (6.0f32 / 200.0).sqrt() / 3.0f32.sqrt(),
+        ),
+        (Initialization::HE, (2.0f32 / 100.0).sqrt()),
+        (Initialization::NORMAL(0.05), 0.05),
+    ] {
+        let mut model = MLPRegressor::new(vec![100, 100])
+            .with_initialization(initialization)
+            .with_seed(4);
+        model.fit(&x, &y, 0, 0.01).unwrap();
+        let weights = &model.network.weights[1];
+        let std = (weights.map(|w| w * w).mean() - weights.mean().powi(2)).sqrt();
+        assert!(
+            (std - expected_std).abs() < 0.1 * expected_std,
+            "{:?}: {}",
+            initialization,
+            std
+        );
+    }
+}
+
+#[test]
+fn test_mlp_classifier() {
+    // Three classes along the diagonal.
+    let x = DMatrix::from_fn(60, 2, |i, j| {
+        (i / 20) as f32 * 2.0 + ((i * (j + 3)) % 7) as f32 / 7.0
+    });
+    let y = DMatrix::from_fn(60, 1, |i, _| (i / 20) as f32);
+    let mut classifier = MLPClassifier::new(vec![16]).with_seed(3);
+    classifier.fit(&x, &y, 300, 0.01).unwrap();
+    let probabilities = classifier.predict_proba(&x);
+    assert!(probabilities
+        .column_sum()
+        .iter()
+        .all(|s| (s - 1.0).abs() < 1e-4));
+    let labels: Vec<f32> = y.iter().cloned().collect();
+    assert!(accuracy(classifier.predict(&x), labels) > 0.95);
+}
+
+#[test]
+fn test_modified_huber_loss() {
+    let margin = DMatrix::from_row_slice(4, 1, &[2.0, 0.5, -0.5, -3.0]);
+    let positive = DMatrix::from_element(4, 1, 1.0);
+    let loss = ModifiedHuber.per_sample(&positive, &margin);
+    assert_eq!(loss.as_slice(), &[0.0, 0.25, 2.25, 12.0]);
+    assert_eq!(
+        ModifiedHuber.gradient(&positive, &margin).as_slice(),
+        &[0.0, -1.0, -3.0, -4.0]
+    );
+}
+
+/// Three separable classes, interleaved so that every batch mixes them.
+fn three_separable_classes() -> (DMatrix<f32>, DMatrix<f32>) {
+    let x = DMatrix::from_fn(90, 2, |i, j| {
+        let class = (i % 3) as f32;
+        let offset = ((i * (j + 5)) % 11) as f32 / 11.0 - 0.5;
+        match j {
+            0 => 3.0 * class + offset,
+            _ => 3.0 * (class - 1.0).abs() + offset,
+        }
+    });
+    let y = DMatrix::from_fn(90, 1, |i, _| (i % 3) as f32);
+    (x, y)
+}
+
+#[test]
+fn test_perceptron() {
+    let (x, y) = three_separable_classes();
+    let labels: Vec<f32> = y.iter().cloned().collect();
+    let mut perceptron = Perceptron::new().with_seed(0);
+    perceptron.fit(&x, &y).unwrap();
+    assert_eq!(accuracy(perceptron.predict(&x), labels), 1.0);
+}
+
+#[test]
+fn test_passive_aggressive_classifier() {
+    let (x, y) = three_separable_classes();
+    let labels: Vec<f32> = y.iter().cloned().collect();
+    let mut passive_aggressive = PassiveAggressiveClassifier::new().with_seed(0);
+    passive_aggressive.fit(&x, &y).unwrap();
+    assert_eq!(accuracy(passive_aggressive.predict(&x), labels), 1.0);
+}
+
+#[test]
+fn test_sgd_classifier_streaming() {
+    // The first batch only holds classes 0 and 1, so every class is announced.
+    let (x, y) = three_separable_classes();
+    let labels: Vec<f32> = y.iter().cloned().collect();
+    for loss in [0, 1, 2] {
+        let mut sgd = match loss {
+            0 => SGDClassifier::new().with_loss(Hinge),
+            1 => SGDClassifier::new().with_loss(LogLoss),
+            _ => SGDClassifier::new().with_loss(ModifiedHuber),
+        }
+        .with_eta0(0.05);
+        let first = DMatrix::from_row_slice(2, 1, &[0.0, 1.0]);
+        sgd.partial_fit(&x.rows(0, 2).into_owned(), &first, Some(&[0.0, 1.0, 2.0]))
+            .unwrap();
+        for _ in 0..10 {
+            for start in (0..90).step_by(15) {
+                let batch_x = x.rows(start, 15).into_owned();
+                let batch_y = y.rows(start, 15).into_owned();
+                sgd.partial_fit(&batch_x, &batch_y, None).unwrap();
+            }
+        }
+        assert_eq!(sgd.n_samples_seen, 902);
+        assert!(accuracy(sgd.predict(&x), labels.clone()) > 0.95);
+    }
+}
+
+#[test]
+fn test_partial_fit_rejects_unannounced_classes() {
+    let (x, y) = three_separable_classes();
+    let mut unannounced = Perceptron::new();
+    unannounced
+        .partial_fit(&x.rows(0, 2).into_owned(), &y.rows(0, 2).into_owned(), None)
+        .unwrap();
+    assert!(unannounced.partial_fit(&x, &y, None).is_err());
+}
+
+#[test]
+fn test_passive_aggressive_regressor() {
+    let (x, _) = three_separable_classes();
+    let targets = DMatrix::from_fn(90, 1, |i, _| 2.0 * x[(i, 0)] - 0.5 * x[(i, 1)] + 1.0);
+    let mut regressor = PassiveAggressiveRegressor::new().with_epsilon(0.01);
+    for start in (0..90).step_by(10) {
+        let batch_x = x.rows(start, 10).into_owned();
+        let batch_y = targets.rows(start, 10).into_owned();
+        regressor.partial_fit(&batch_x, &batch_y).unwrap();
+    }
+    regressor.fit(&x, &targets).unwrap();
+    assert!(mse_per_target(&targets, &regressor.predict(&x))[0] < 0.01);
+    assert!(PassiveAggressiveRegressor::new()
+        .with_c(0.0)
+        .fit(&x, &targets)
+        .is_err());
+}
+
+/// Feeds `(x, y)` to `model` in consecutive batches of `batch_size` rows.
+fn stream<M: IncrementalModel>(
+    model: &mut M,
+    x: &DMatrix<f32>,
+    y: &DMatrix<f32>,
+    batch_size: usize,
+) {
+    for start in (0..x.nrows()).step_by(batch_size) {
+        let n = batch_size.min(x.nrows() - start);
+        model
+            .partial_fit(
+                &x.rows(start, n).into_owned(),
+                &y.rows(start, n).into_owned(),
+            )
+            .unwrap();
+    }
+}
+
+/// A noisy plane in two features.
+fn noisy_plane() -> (DMatrix<f32>, DMatrix<f32>) {
+    let x = DMatrix::from_fn(120, 2, |i, j| ((i * (3 + 4 * j)) % 17) as f32 / 4.0);
+    let y = DMatrix::from_fn(120, 1, |i, _| {
+        1.5 * x[(i, 0)] - 2.0 * x[(i, 1)] + 3.0 + ((i * 7) % 5) as f32 / 10.0
+    });
+    (x, y)
+}
+
+/// Three well separated blobs, interleaved sample by sample.
+fn three_blobs() -> DMatrix<f32> {
+    DMatrix::from_fn(150, 2, |i, j| {
+        10.0 * ((i % 3) as f32) * (j as f32 * 2.0 - 1.0) + ((i * (j + 2)) % 7) as f32 / 7.0
+    })
+}
+
+#[test]
+fn test_simple_linear_regression_partial_fit() {
+    // Running sums give exactly the batch line.
+    let (x, y) = noisy_plane();
+    let x_values: Vec<f32> = x.column(0).iter().cloned().collect();
+    let y_values: Vec<f32> = y.column(0).iter().cloned().collect();
+    let mut batch = SimpleLinearRegression::new();
+    batch.fit(&x_values, &y_values);
+    let mut simple = SimpleLinearRegression::new();
+    stream(&mut simple, &x.columns(0, 1).into_owned(), &y, 25);
+    assert_eq!(simple.n_samples_seen, 120);
+    assert!((simple.coefficient.unwrap() - batch.coefficient.unwrap()).abs() < 1e-4);
+    assert!((simple.bias.unwrap() - batch.bias.unwrap()).abs() < 1e-4);
+
+    // A rejected batch leaves the running sums untouched.
+    let mut constant = SimpleLinearRegression::new();
+    assert!(constant
+        .partial_fit(&vec![1.0, 1.0], &vec![2.0, 3.0])
+        .is_err());
+    assert_eq!(constant.n_samples_seen, 0);
+    constant
+        .partial_fit(&vec![1.0, 2.0], &vec![1.0, 3.0])
+        .unwrap();
+    assert_eq!(constant.coefficient, Some(2.0));
+}
+
+#[test]
+fn test_recursive_least_squares() {
+    // Recursive least squares matches ordinary least squares, also when continuing a fit.
+    let (x, y) = noisy_plane();
+    let mut ols = LinearRegression::new();
+    ols.fit(&x, &y).unwrap();
+    let mut rls = LinearRegression::new();
+    stream(&mut rls, &x, &y, 16);
+    assert!((rls.predict(&x) - ols.predict(&x)).abs().max() < 1e-2);
+    let mut continued = LinearRegression::new();
+    continued
+        .fit(&x.rows(0, 60).into_owned(), &y.rows(0, 60).into_owned())
+        .unwrap();
+    stream(
+        &mut continued,
+        &x.rows(60, 60).into_owned(),
+        &y.rows(60, 60).into_owned(),
+        20,
+    );
+    assert!((continued.predict(&x) - ols.predict(&x)).abs().max() < 1e-2);
+    assert!(LinearRegression::new()
+        .with_forgetting_factor(0.0)
+        .partial_fit(&x, &y)
+        .is_err());
+}
+
+#[test]
+fn test_incremental_trainer() {
+    // Any gradient-trained model, with the optimizer state kept between batches.
+    let (x, y) = noisy_plane();
+    let mut polynomial = PolynomialRegression::new(3, TypeRegression::MSE);
+    polynomial.coefficients.fill(0.0);
+    let mut trainer = IncrementalTrainer::new(polynomial, Adam::new(0.05)).with_epochs(5);
+    let (x_poly, y_poly) = (x.columns(0, 1).into_owned(), y.map(|v| v / 10.0));
+    let initial_loss = trainer.model.loss(&x_poly, &y_poly, None);
+    for _ in 0..20 {
+        stream(&mut trainer, &x_poly, &y_poly, 30);
+    }
+    assert_eq!(trainer.batch_loss.len(), 80);
+    assert!(trainer.batch_loss.last().unwrap() < &(initial_loss / 2.0));
+}
+
+#[test]
+fn test_gaussian_naive_bayes_streaming() {
+    let (x, _) = noisy_plane();
+    let labels = DMatrix::from_fn(120, 1, |i, _| (x[(i, 0)] > 2.0) as usize as f32);
+    let mut gaussian = GaussianNaiveBayes::new();
+    stream(&mut gaussian, &x, &labels, 40);
+    let expected: Vec<f32> = labels.iter().cloned().collect();
+    assert!(accuracy(gaussian.predict(&x), expected) > 0.8);
+}
+
+#[test]
+fn test_kmeans() {
+    let blobs = three_blobs();
+    let mut kmeans = KMeans::new(3).with_seed(0);
+    kmeans.fit(&blobs).unwrap();
+    let labels = kmeans.predict(&blobs);
+    assert!((0..150).all(|i| labels[i] == labels[i % 3]));
+    assert!(kmeans.inertia < 150.0);
+    assert!(KMeans::new(4).fit(&blobs.rows(0, 3).into_owned()).is_err());
+}
+
+#[test]
+fn test_mini_batch_kmeans() {
+    let blobs = three_blobs();
+    let mut kmeans = KMeans::new(3).with_seed(0);
+    kmeans.fit(&blobs).unwrap();
+
+    let mut mini_batch = KMeans::new(3).with_seed(1);
+    for _ in 0..5 {
+        stream(&mut mini_batch, &blobs, &DMatrix::zeros(150, 1), 30);
+    }
+    let labels = mini_batch.predict(&blobs);
+    assert!((0..150).all(|i| labels[i] == labels[i % 3]));
+    assert!(mini_batch.score(&blobs) < 2.0 * kmeans.inertia);
+}
+
+#[test]
+fn test_csv_chunks() {
+    let csv = "a,b,y\n1,2,3\n4,5,6\n\n7,8,9\n10,11,12\n13,14,15\n";
+    let mut chunks = CsvChunks::new(csv.as_bytes(), 2).unwrap();
+    assert_eq!(chunks.header, vec!["a", "b", "y"]);
+    let batches: Vec<DMatrix<f32>> = chunks.by_ref().map(|batch| batch.unwrap()).collect();
+    assert_eq!(
+        batches.iter().map(|b| b.nrows()).collect::<Vec<usize>>(),
+        vec![2, 2, 1]
+    );
+    assert_eq!(
+        batches[1].row(0).iter().cloned().collect::<Vec<f32>>(),
+        vec![7.0, 8.0, 9.0]
+    );
+    let (x, y) = split_target(&batches[2]);
+    assert_eq!(
+        (x.as_slice(), y.as_slice()),
+        (&[13.0, 14.0][..], &[15.0][..])
+    );
+}
+
+#[test]
+fn test_csv_chunk_errors() {
+    let errors: Vec<String> = CsvChunks::new("a,b\n1,2\n3\n4,x\n".as_bytes(), 1)
+        .unwrap()
+        .filter_map(|batch| batch.err())
+        .collect();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("line 3") && errors[1].starts_with("line 4"));
+    assert!(CsvChunks::new("".as_bytes(), 10).is_err());
+}
+
+#[test]
+fn test_streaming_fit_and_regression_metrics() {
+    // Streaming a dataset gives the same data, fit and metrics as loading it at once.
+    let file = File::open("datasets/linear_regression.csv").unwrap();
+    let (nrows, ncols, values) = parse_csv(BufReader::new(file)).unwrap();
+    let (x, y) = split_target(&DMatrix::from_row_slice(nrows, ncols, &values));
+    let mut batch = LinearRegression::new();
+    batch.fit(&x, &y).unwrap();
+
+    let file = File::open("datasets/linear_regression.csv").unwrap();
+    let mut streamed = LinearRegression::new();
+    let mut n_samples = 0;
+    for chunk in CsvChunks::new(BufReader::new(file), 256).unwrap() {
+        let (x_chunk, y_chunk) = split_target(&chunk.unwrap());
+        n_samples += x_chunk.nrows();
+        IncrementalModel::partial_fit(&mut streamed, &x_chunk, &y_chunk).unwrap();
+    }
+    assert_eq!(n_samples, nrows);
+    let y_hat = streamed.predict(&x);
+    assert!((&y_hat - batch.predict(&x)).abs().max() < 1e-2);
+
+    let mut metrics = RegressionMetrics::new();
+    for start in (0..nrows).step_by(300) {
+        let n = 300.min(nrows - start);
+        metrics
+            .update(
+                &y.rows(start, n).into_owned(),
+                &y_hat.rows(start, n).into_owned(),
+            )
+            .unwrap();
+    }
+    assert_eq!(metrics.n_samples, nrows);
+    assert!((metrics.mse()[0] - mse_per_target(&y, &y_hat)[0]).abs() < 1e-3);
+    assert!((metrics.mae()[0] - mae_per_target(&y, &y_hat)[0]).abs() < 1e-3);
+    assert!((metrics.r2()[0] - r2_per_target(&y, &y_hat)[0]).abs() < 1e-4);
+    assert!(metrics.update(&y, &x).is_err());
+}
+
+#[test]
+fn test_classification_metrics() {
+    let mut confusion = ClassificationMetrics::new();
+    confusion
+        .update(&[1.0, 0.0, 1.0], &[1.0, 0.0, 0.0])
+        .unwrap();
+    confusion.update(&[2.0, 1.0], &[2.0, 1.0]).unwrap();
+    assert_eq!(confusion.classes, vec![0.0, 1.0, 2.0]);
+    assert_eq!(confusion.n_samples(), 5);
+    assert_eq!(
+        confusion.accuracy(),
+        accuracy(vec![1.0, 0.0, 0.0, 2.0, 1.0], vec![1.0, 0.0, 1.0, 2.0, 1.0])
+    );
+    assert_eq!(confusion.precision(), vec![0.5, 1.0, 1.0]);
+    assert_eq!(confusion.recall(), vec![1.0, 2.0 / 3.0, 1.0]);
+}
+
+#[test]
+fn test_classification_metrics_new_class_keeps_rows_aligned() {
+    // An unseen predicted class sorting before the target must not shift the target row.
+    let mut confusion = ClassificationMetrics::new();
+    confusion.update(&[1.0], &[0.0]).unwrap();
+    assert_eq!(confusion.classes, vec![0.0, 1.0]);
+    assert_eq!(confusion.confusion[(1, 0)], 1.0);
+    assert_eq!(confusion.accuracy(), 0.0);
+}